@@ -0,0 +1,75 @@
+//! Implements blocker and combination-removal analysis of ranges.
+//!
+//! Known cards (e.g. one's own hole cards or the board) make every combination containing them
+//! impossible. This module reports, class by class, how much of a range such cards remove.
+
+use crate::analysis::range::{hand_class, Range};
+use crate::utilities::Card;
+
+/// The combinations of a hand class in a range that are blocked by known cards.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClassBlockers {
+    /// The hand class (e.g. `AKs`), or the cards themselves for combinations that do not belong
+    /// to a two-card class.
+    pub class: String,
+    /// The number of combinations of the class in the range.
+    pub combo_count: usize,
+    /// The total weight of the combinations of the class in the range.
+    pub weight: f64,
+    /// The specific holdings that contain a known card.
+    pub blocked_combos: Vec<Vec<Card>>,
+    /// The total weight of the blocked holdings.
+    pub blocked_weight: f64,
+}
+
+impl ClassBlockers {
+    /// Returns the number of combinations of the class that remain possible.
+    pub fn remaining_count(&self) -> usize {
+        self.combo_count - self.blocked_combos.len()
+    }
+
+    /// Returns the share of the class' weight that the known cards remove.
+    pub fn blocked_fraction(&self) -> f64 {
+        if self.weight == 0.0 { 0.0 } else { self.blocked_weight / self.weight }
+    }
+}
+
+/// Reports, for every hand class of the range, which of its combinations are blocked by the
+/// known cards.
+///
+/// Classes are listed in the order they first appear in the range.
+pub fn blockers(range: &Range, known_cards: &[Card]) -> Vec<ClassBlockers> {
+    let mut reports: Vec<ClassBlockers> = Vec::new();
+
+    for (combo, weight) in range.iter() {
+        let class = hand_class(combo).unwrap_or_else(|| combo.iter().map(|card| card.to_string()).collect());
+        let index = match reports.iter().position(|report| report.class == class) {
+            Some(index) => index,
+            None => {
+                reports.push(ClassBlockers {
+                    class,
+                    combo_count: 0,
+                    weight: 0.0,
+                    blocked_combos: Vec::new(),
+                    blocked_weight: 0.0,
+                });
+                reports.len() - 1
+            }
+        };
+        let report = &mut reports[index];
+
+        report.combo_count += 1;
+        report.weight += weight;
+        if combo.iter().any(|card| known_cards.contains(card)) {
+            report.blocked_combos.push(combo.clone());
+            report.blocked_weight += weight;
+        }
+    }
+
+    reports
+}
+
+/// Returns the total weight of the range that the known cards remove.
+pub fn blocked_weight(range: &Range, known_cards: &[Card]) -> f64 {
+    range.total_weight() - range.without(known_cards).total_weight()
+}
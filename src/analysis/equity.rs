@@ -0,0 +1,351 @@
+//! Implements equity calculations of hands against ranges.
+//!
+//! Runouts of the board are either enumerated exhaustively or sampled, and every runout is
+//! shared among all the combinations being compared so that each hand is evaluated only once per
+//! runout.
+
+use std::cmp::Ordering;
+use std::collections::HashSet;
+use std::ops::AddAssign;
+use std::sync::Arc;
+
+use itertools::Itertools;
+use rand::distributions::{Distribution, WeightedIndex};
+use rand::seq::SliceRandom;
+use rand::thread_rng;
+
+use crate::analysis::range::Range;
+use crate::hands::{EvaluatorSpec, HandType};
+use crate::lookups::Entry;
+use crate::state::State;
+use crate::utilities::{Card, Deck};
+
+/// The equities of a hand against each of the combinations of a range.
+pub type ComboEquities = Vec<(Vec<Card>, Equity)>;
+
+/// How the runouts of the board are visited.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Sampling {
+    /// Every possible runout is enumerated.
+    Exhaustive,
+    /// The given number of runouts are sampled at random.
+    MonteCarlo(usize),
+}
+
+/// The accumulated outcomes of a hand against an opponent.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Equity {
+    pub wins: f64,
+    pub ties: f64,
+    pub losses: f64,
+}
+
+impl Equity {
+    /// Returns the total number of outcomes.
+    pub fn total(&self) -> f64 {
+        self.wins + self.ties + self.losses
+    }
+
+    /// Returns the share of the pot won on average, where ties count as half.
+    pub fn equity(&self) -> f64 {
+        if self.total() == 0.0 { 0.0 } else { (self.wins + self.ties / 2.0) / self.total() }
+    }
+
+    /// Returns the outcomes with every count multiplied by the factor.
+    pub fn scaled(&self, factor: f64) -> Self {
+        Self { wins: self.wins * factor, ties: self.ties * factor, losses: self.losses * factor }
+    }
+
+    fn record(&mut self, ordering: Ordering, weight: f64) {
+        match ordering {
+            Ordering::Greater => self.wins += weight,
+            Ordering::Equal => self.ties += weight,
+            Ordering::Less => self.losses += weight,
+        }
+    }
+}
+
+impl AddAssign for Equity {
+    fn add_assign(&mut self, other: Self) {
+        self.wins += other.wins;
+        self.ties += other.ties;
+        self.losses += other.losses;
+    }
+}
+
+/// A configurable equity calculator.
+pub struct Calculator {
+    hand_type: HandType,
+    deck: Vec<Card>,
+    board_cards: Vec<Card>,
+    dead_cards: Vec<Card>,
+    board_card_count: usize,
+    sampling: Sampling,
+}
+
+impl Calculator {
+    pub fn new(hand_type: HandType) -> Self {
+        Self {
+            hand_type,
+            deck: Deck::standard(),
+            board_cards: Vec::new(),
+            dead_cards: Vec::new(),
+            board_card_count: 5,
+            sampling: Sampling::Exhaustive,
+        }
+    }
+
+    pub fn deck(mut self, deck: Vec<Card>) -> Self { self.deck = deck; self }
+    pub fn board_cards(mut self, board_cards: Vec<Card>) -> Self { self.board_cards = board_cards; self }
+    pub fn dead_cards(mut self, dead_cards: Vec<Card>) -> Self { self.dead_cards = dead_cards; self }
+    pub fn board_card_count(mut self, board_card_count: usize) -> Self { self.board_card_count = board_card_count; self }
+    pub fn sampling(mut self, sampling: Sampling) -> Self { self.sampling = sampling; self }
+
+    /// Returns the runouts completing the board, drawn from the deck minus the excluded cards.
+    fn runouts(&self, excluded_cards: &[Card]) -> Box<dyn Iterator<Item = Vec<Card>>> {
+        let excluded_cards: HashSet<&Card> = excluded_cards.iter()
+            .chain(&self.board_cards)
+            .chain(&self.dead_cards)
+            .collect();
+        let live_cards: Vec<Card> = self.deck.iter().filter(|card| !excluded_cards.contains(card)).cloned().collect();
+        let count = self.board_card_count.saturating_sub(self.board_cards.len());
+
+        match self.sampling {
+            Sampling::Exhaustive => Box::new(live_cards.into_iter().combinations(count)),
+            Sampling::MonteCarlo(sample_count) => {
+                let mut rng = thread_rng();
+                Box::new((0..sample_count).map(move |_| live_cards.choose_multiple(&mut rng, count).cloned().collect()))
+            }
+        }
+    }
+
+    /// Compares the best hands of two players, a player with no hand losing to any hand.
+    fn compare(&self, entry: &Option<Entry>, other_entry: &Option<Entry>) -> Ordering {
+        match (entry, other_entry) {
+            (Some(entry), Some(other_entry)) => self.hand_type.compare(entry, other_entry),
+            (Some(_), None) => Ordering::Greater,
+            (None, Some(_)) => Ordering::Less,
+            (None, None) => Ordering::Equal,
+        }
+    }
+
+    /// Computes the equity of the hand against every combination of the range.
+    ///
+    /// The outcomes against each combination are scaled by its weight in the range. Combinations
+    /// that conflict with the hand, the board, or the dead cards are left out of the result.
+    pub fn hand_vs_range(&self, hole_cards: &[Card], range: &Range) -> ComboEquities {
+        let excluded_cards: Vec<Card> = hole_cards.iter().chain(&self.board_cards).chain(&self.dead_cards).cloned().collect();
+        let range = range.without(&excluded_cards);
+        let mut equities = vec![Equity::default(); range.len()];
+
+        for runout in self.runouts(hole_cards) {
+            let board_cards: Vec<Card> = self.board_cards.iter().chain(&runout).cloned().collect();
+            let entry = self.hand_type.evaluate(hole_cards, &board_cards);
+
+            for ((combo, weight), equity) in range.iter().zip(equities.iter_mut()) {
+                if combo.iter().any(|card| runout.contains(card)) {
+                    continue;
+                }
+                let other_entry = self.hand_type.evaluate(combo, &board_cards);
+                equity.record(self.compare(&entry, &other_entry), weight);
+            }
+        }
+
+        range.combos().iter().cloned().zip(equities).collect()
+    }
+
+    /// Computes the equity of every combination of the first range against every combination of
+    /// the second range.
+    ///
+    /// The rows follow the combinations of the first range. Each row holds the results of
+    /// `hand_vs_range` further scaled by the weight of the row's combination, so the cells can be
+    /// summed directly into the weighted range-vs-range equity. Conflicting combinations are left
+    /// out.
+    ///
+    /// Each runout is visited once, evaluating every combination of both ranges against it and
+    /// comparing every pair of them that shares no card.
+    pub fn range_vs_range(&self, range: &Range, other_range: &Range) -> Vec<(Vec<Card>, ComboEquities)> {
+        let excluded_cards: Vec<Card> = self.board_cards.iter().chain(&self.dead_cards).cloned().collect();
+        let range = range.without(&excluded_cards);
+        let other_range = other_range.without(&excluded_cards);
+        let other_indices: Vec<Vec<usize>> = range.combos().iter()
+            .map(|combo| (0..other_range.len()).filter(|&j| !other_range.combos()[j].iter().any(|card| combo.contains(card))).collect())
+            .collect();
+        let mut equities: Vec<Vec<Equity>> = other_indices.iter().map(|indices| vec![Equity::default(); indices.len()]).collect();
+
+        for runout in self.runouts(&[]) {
+            let board_cards: Vec<Card> = self.board_cards.iter().chain(&runout).cloned().collect();
+            let evaluate = |combo: &Vec<Card>| {
+                (!combo.iter().any(|card| runout.contains(card))).then(|| self.hand_type.evaluate(combo, &board_cards))
+            };
+            let entries: Vec<_> = range.combos().iter().map(evaluate).collect();
+            let other_entries: Vec<_> = other_range.combos().iter().map(evaluate).collect();
+
+            for (i, (_, weight)) in range.iter().enumerate() {
+                let Some(entry) = &entries[i] else { continue };
+                for (equity, &j) in equities[i].iter_mut().zip(&other_indices[i]) {
+                    if let Some(other_entry) = &other_entries[j] {
+                        equity.record(self.compare(entry, other_entry), weight * other_range.weights()[j]);
+                    }
+                }
+            }
+        }
+
+        range.combos().iter()
+            .zip(other_indices)
+            .zip(equities)
+            .map(|((combo, indices), equities)| {
+                let combo_equities = indices.into_iter().map(|j| other_range.combos()[j].clone()).zip(equities).collect();
+                (combo.clone(), combo_equities)
+            })
+            .collect()
+    }
+}
+
+/// Splits a unit pot among the showdown hands, adding each player's share to `shares`.
+///
+/// The pot is divided evenly among the hand types for which any hand qualifies, and each part is
+/// split among the players holding the best hand of that type.
+fn award(evaluators: &[Arc<dyn EvaluatorSpec>], hands: &[(usize, &[Card])], board_cards: &[Card], weight: f64, shares: &mut [f64]) {
+    let results: Vec<Vec<usize>> = evaluators
+        .iter()
+        .map(|hand_type| {
+            let entries: Vec<_> = hands.iter()
+                .filter_map(|(i, hole_cards)| hand_type.evaluate(hole_cards, board_cards).map(|entry| (*i, entry)))
+                .collect();
+            let best = entries.iter().map(|(_, entry)| *entry).max_by(|entry, other| hand_type.compare(entry, other));
+            entries.iter()
+                .filter(|(_, entry)| best.is_some_and(|best| hand_type.compare(entry, &best) == Ordering::Equal))
+                .map(|(i, _)| *i)
+                .collect()
+        })
+        .filter(|winners: &Vec<usize>| !winners.is_empty())
+        .collect();
+
+    for winners in &results {
+        for &i in winners {
+            shares[i] += weight / (results.len() * winners.len()) as f64;
+        }
+    }
+}
+
+/// Calls `visit` with every way to complete the boards from the live cards, each board taking
+/// its count of cards and no card being dealt twice.
+fn visit_runouts(live_cards: &[Card], counts: &[usize], runouts: &mut Vec<Vec<Card>>, visit: &mut dyn FnMut(&[Vec<Card>])) {
+    let Some((&count, counts)) = counts.split_first() else { return visit(runouts) };
+    for runout in live_cards.iter().cloned().combinations(count) {
+        let remaining_cards: Vec<Card> = if counts.is_empty() {
+            Vec::new()
+        } else {
+            live_cards.iter().filter(|card| !runout.contains(card)).cloned().collect()
+        };
+        runouts.push(runout);
+        visit_runouts(&remaining_cards, counts, runouts, visit);
+        runouts.pop();
+    }
+}
+
+/// Computes the equity of every player in the hand, seeded from the cards known to the state.
+///
+/// The dead cards (board, burned, mucked, and discarded cards) are taken from the state so they
+/// need not be assembled manually. Players with a range in `ranges` are assumed to hold any of
+/// its combinations, in proportion to their weights, instead of their actual hole cards. The others play their hole cards as
+/// dealt. Folded players have an equity of zero.
+///
+/// In hands with several boards, each board is completed with cards of its own and awards an
+/// equal share of the pot.
+pub fn from_state(state: &State, ranges: &[Option<Range>], sampling: Sampling) -> Result<Vec<f64>, String> {
+    if ranges.len() != state.player_count {
+        return Err("A range (or None) must be supplied for every player.".to_string());
+    }
+
+    let player_indices: Vec<usize> = (0..state.player_count).filter(|&i| state.statuses[i]).collect();
+    let board_card_count: usize = state.streets.iter().map(|street| street.board_dealing_count).sum();
+    let mut dead_cards: Vec<Card> = state.board_cards.iter()
+        .flatten()
+        .chain(&state.burn_cards)
+        .chain(&state.mucked_cards)
+        .chain(state.discarded_cards.iter().flatten())
+        .cloned()
+        .collect();
+
+    for &i in &player_indices {
+        if ranges[i].is_none() {
+            if state.hole_cards[i].is_empty() {
+                return Err(format!("Player {} has no hole cards and no range.", i));
+            }
+            dead_cards.extend(state.hole_cards[i].iter().cloned());
+        }
+    }
+
+    let live_cards: Vec<Card> = state.deck.iter().filter(|card| !dead_cards.contains(card)).cloned().collect();
+    let ranged: Vec<(usize, Range)> = player_indices.iter()
+        .filter_map(|&i| ranges[i].as_ref().map(|range| (i, range.without(&dead_cards))))
+        .collect();
+    if let Some((i, _)) = ranged.iter().find(|(_, range)| range.is_empty()) {
+        return Err(format!("The range of player {} is empty after removing the dead cards.", i));
+    }
+
+    let runout_counts: Vec<usize> = state.board_cards.iter().map(|board_cards| board_card_count.saturating_sub(board_cards.len())).collect();
+    let board_weight = 1.0 / state.board_cards.len() as f64;
+    let mut shares = vec![0.0; state.player_count];
+    let mut total = 0.0;
+    let mut showdown = |combos: &[&Vec<Card>], runouts: &[Vec<Card>], weight: f64, shares: &mut [f64]| {
+        let mut hands: Vec<(usize, &[Card])> = player_indices.iter()
+            .filter(|&&i| ranges[i].is_none())
+            .map(|&i| (i, state.hole_cards[i].as_slice()))
+            .collect();
+        hands.extend(ranged.iter().zip(combos).map(|((i, _), combo)| (*i, combo.as_slice())));
+        for (board_cards, runout) in state.board_cards.iter().zip(runouts) {
+            let board_cards: Vec<Card> = board_cards.iter().chain(runout).cloned().collect();
+            award(&state.evaluators, &hands, &board_cards, weight * board_weight, shares);
+        }
+        total += weight;
+    };
+
+    match sampling {
+        Sampling::Exhaustive => {
+            let combo_lists = ranged.iter().map(|(_, range)| range.iter()).multi_cartesian_product();
+            let combo_lists: Box<dyn Iterator<Item = Vec<(&Vec<Card>, f64)>>> = if ranged.is_empty() {
+                Box::new(std::iter::once(Vec::new()))
+            } else {
+                Box::new(combo_lists)
+            };
+            for combos in combo_lists {
+                let weight: f64 = combos.iter().map(|(_, weight)| weight).product();
+                let combos: Vec<&Vec<Card>> = combos.into_iter().map(|(combo, _)| combo).collect();
+                let used_cards: Vec<Card> = combos.iter().flat_map(|combo| combo.iter().cloned()).collect();
+                if used_cards.iter().unique().count() != used_cards.len() {
+                    continue;
+                }
+                let remaining_cards: Vec<Card> = live_cards.iter().filter(|card| !used_cards.contains(card)).cloned().collect();
+                visit_runouts(&remaining_cards, &runout_counts, &mut Vec::new(), &mut |runouts| showdown(&combos, runouts, weight, &mut shares));
+            }
+        }
+        Sampling::MonteCarlo(sample_count) => {
+            let mut rng = thread_rng();
+            let distributions = ranged.iter()
+                .map(|(_, range)| WeightedIndex::new(range.weights()).map_err(|e| e.to_string()))
+                .collect::<Result<Vec<_>, String>>()?;
+            for _ in 0..sample_count {
+                let combos: Vec<&Vec<Card>> = ranged.iter()
+                    .zip(&distributions)
+                    .map(|((_, range), distribution)| &range.combos()[distribution.sample(&mut rng)])
+                    .collect();
+                let used_cards: Vec<Card> = combos.iter().flat_map(|combo| combo.iter().cloned()).collect();
+                if used_cards.iter().unique().count() != used_cards.len() {
+                    continue;
+                }
+                let remaining_cards: Vec<Card> = live_cards.iter().filter(|card| !used_cards.contains(card)).cloned().collect();
+                let mut cards = remaining_cards.choose_multiple(&mut rng, runout_counts.iter().sum()).cloned();
+                let runouts: Vec<Vec<Card>> = runout_counts.iter().map(|&count| cards.by_ref().take(count).collect()).collect();
+                showdown(&combos, &runouts, 1.0, &mut shares);
+            }
+        }
+    }
+
+    if total == 0.0 {
+        return Err("No consistent deal exists for the supplied ranges.".to_string());
+    }
+    Ok(shares.into_iter().map(|share| share / total).collect())
+}
@@ -0,0 +1,38 @@
+//! Implements the enumeration of strategically distinct flops.
+//!
+//! Flops that only differ by a relabeling of the suits are strategically equivalent. With the
+//! standard deck, the 22,100 flops collapse into 1,755 classes.
+
+use std::collections::HashMap;
+
+use itertools::Itertools;
+
+use crate::analysis::range::sort_cards;
+use crate::utilities::{canonicalize_suits, Card, Deck};
+
+/// Returns the strategically distinct flops of the deck, each with the number of flops it
+/// stands for.
+///
+/// The weights sum up to the total number of flops, so they can be used directly as sampling
+/// frequencies when solving a subset of flops.
+pub fn distinct_flops_of(deck: &[Card]) -> Vec<(Vec<Card>, usize)> {
+    let mut weights: HashMap<Vec<Card>, usize> = HashMap::new();
+    let mut flops = Vec::new();
+
+    for flop in deck.iter().cloned().combinations(3) {
+        let mut flop = canonicalize_suits(&[flop]).remove(0);
+        sort_cards(&mut flop);
+        let weight = weights.entry(flop.clone()).or_insert(0);
+        if *weight == 0 {
+            flops.push(flop);
+        }
+        *weight += 1;
+    }
+
+    flops.into_iter().map(|flop| { let weight = weights[&flop]; (flop, weight) }).collect()
+}
+
+/// Returns the 1,755 strategically distinct flops of the standard deck with their weights.
+pub fn distinct_flops() -> Vec<(Vec<Card>, usize)> {
+    distinct_flops_of(&Deck::standard())
+}
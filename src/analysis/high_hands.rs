@@ -0,0 +1,108 @@
+//! Implements the tracking of promotional high hands over a session.
+//!
+//! Rooms commonly reward the best hand shown within a period (e.g. the "high hand of the hour"),
+//! provided it meets a minimum strength and, often, plays both hole cards. Every qualifying hand
+//! is recorded with the time it was made, so the best hand of any period can be looked up later.
+
+use chrono::NaiveDateTime;
+
+use crate::analysis::stats::showdown_indices;
+use crate::hands::HandType;
+use crate::lookups::{Entry, Label};
+use crate::state::State;
+use crate::utilities::Card;
+
+/// The requirements for a hand to qualify for the promotion.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HighHandRule {
+    pub min_label: Label,
+    /// Whether both hole cards must play, as opposed to any of them.
+    pub both_hole_cards_required: bool,
+}
+
+impl Default for HighHandRule {
+    fn default() -> Self {
+        Self { min_label: Label::FourOfAKind, both_hole_cards_required: true }
+    }
+}
+
+/// A qualifying hand.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HighHand<K> {
+    pub hand_number: usize,
+    pub player: K,
+    pub hole_cards: Vec<Card>,
+    pub board_cards: Vec<Card>,
+    pub entry: Entry,
+    pub time: NaiveDateTime,
+}
+
+/// Records the qualifying hands of players, identified by keys of type `K`, over a session.
+#[derive(Debug, Clone)]
+pub struct HighHandTracker<K> {
+    rule: HighHandRule,
+    high_hands: Vec<HighHand<K>>,
+    hand_count: usize,
+}
+
+impl<K: Clone> HighHandTracker<K> {
+    pub fn new(rule: HighHandRule) -> Self {
+        Self { rule, high_hands: Vec::new(), hand_count: 0 }
+    }
+
+    /// Returns the qualifying hands, in the order they were made.
+    pub fn high_hands(&self) -> &[HighHand<K>] {
+        &self.high_hands
+    }
+
+    /// Records the qualifying hands shown down in a hold'em hand played at the given time and
+    /// returns them. `players` maps each seat of the state to its player.
+    ///
+    /// Hands are ranked by the lookup of the first hand type of the game, so that the minimum
+    /// label is as strong as it is in the game, e.g. a flush beating a full house in short-deck
+    /// hold'em.
+    pub fn record(&mut self, state: &State, players: &[K], time: NaiveDateTime) -> Result<&[HighHand<K>], String> {
+        if players.len() != state.player_count {
+            return Err("A player must be supplied for every seat.".to_string());
+        }
+        let game_hand_type = state.hand_types.first().copied().unwrap_or(HandType::StandardHighHand);
+        if !game_hand_type.is_available() {
+            return Err(format!("The lookup of {:?} requires a disabled feature", game_hand_type));
+        }
+
+        // Hold'em hands using exactly two hole cards are evaluated like Omaha hands.
+        let hand_type = if self.rule.both_hole_cards_required { HandType::OmahaHoldemHand } else { game_hand_type };
+        let lookup = game_hand_type.lookup();
+        let min_entry = lookup.min_entry(self.rule.min_label);
+        let board_cards = state.board_cards.first().cloned().unwrap_or_default();
+        let start = self.high_hands.len();
+
+        for i in showdown_indices(state) {
+            let hole_cards = &state.hole_cards[i];
+            let entry = hand_type.evaluate_with(lookup, hole_cards, &board_cards);
+            if let Some(entry) = entry.filter(|entry| min_entry.is_some_and(|min_entry| *entry >= min_entry)) {
+                self.high_hands.push(HighHand {
+                    hand_number: self.hand_count,
+                    player: players[i].clone(),
+                    hole_cards: hole_cards.clone(),
+                    board_cards: board_cards.clone(),
+                    entry,
+                    time,
+                });
+            }
+        }
+        self.hand_count += 1;
+
+        Ok(&self.high_hands[start..])
+    }
+
+    /// Returns the best qualifying hand made within the period, the earliest one winning ties.
+    pub fn best_between(&self, start: NaiveDateTime, end: NaiveDateTime) -> Option<&HighHand<K>> {
+        self.high_hands.iter()
+            .filter(|high_hand| start <= high_hand.time && high_hand.time < end)
+            .fold(None, |best: Option<&HighHand<K>>, high_hand| match best {
+                Some(best) if best.entry >= high_hand.entry => Some(best),
+                _ => Some(high_hand),
+            })
+    }
+}
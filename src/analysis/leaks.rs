@@ -0,0 +1,194 @@
+//! Implements the detection of likely leaks from aggregated player statistics.
+//!
+//! A player's frequencies are compared against configurable baselines. Every frequency that
+//! falls outside of its baseline, over a large enough sample, is reported as a likely leak. The
+//! reports serialize to JSON for consumption by external tools.
+
+use std::hash::Hash;
+
+use serde::{Deserialize, Serialize};
+
+use crate::analysis::stats::{Aggregator, PlayerStats};
+
+/// A statistic tracked by the aggregator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Statistic {
+    VoluntarilyPutInPot,
+    PreflopRaise,
+    ThreeBet,
+    FoldToThreeBet,
+    ContinuationBet,
+    FoldToContinuationBet,
+    WentToShowdown,
+    WonAtShowdown,
+    Squeeze,
+    CheckRaise,
+    DonkBet,
+    Float,
+}
+
+impl Statistic {
+    /// Returns the frequency of the statistic, if there was any opportunity.
+    pub fn frequency(&self, stats: &PlayerStats) -> Option<f64> {
+        match self {
+            Statistic::VoluntarilyPutInPot => stats.voluntarily_put_in_pot(),
+            Statistic::PreflopRaise => stats.preflop_raise(),
+            Statistic::ThreeBet => stats.three_bet(),
+            Statistic::FoldToThreeBet => stats.fold_to_three_bet(),
+            Statistic::ContinuationBet => stats.continuation_bet(),
+            Statistic::FoldToContinuationBet => stats.fold_to_continuation_bet(),
+            Statistic::WentToShowdown => stats.went_to_showdown(),
+            Statistic::WonAtShowdown => stats.won_at_showdown(),
+            Statistic::Squeeze => stats.squeeze(),
+            Statistic::CheckRaise => stats.check_raise(),
+            Statistic::DonkBet => stats.donk_bet(),
+            Statistic::Float => stats.float(),
+        }
+    }
+
+    /// Returns the number of opportunities the frequency is computed over.
+    pub fn sample_size(&self, stats: &PlayerStats) -> usize {
+        match self {
+            Statistic::VoluntarilyPutInPot | Statistic::PreflopRaise | Statistic::Squeeze => stats.hand_count,
+            Statistic::ThreeBet => stats.three_bet_opportunity_count,
+            Statistic::FoldToThreeBet => stats.fold_to_three_bet_opportunity_count,
+            Statistic::ContinuationBet => stats.continuation_bet_opportunity_count,
+            Statistic::FoldToContinuationBet => stats.fold_to_continuation_bet_opportunity_count,
+            Statistic::WentToShowdown | Statistic::CheckRaise | Statistic::DonkBet | Statistic::Float => stats.saw_flop_count,
+            Statistic::WonAtShowdown => stats.went_to_showdown_count,
+        }
+    }
+
+    fn description(&self) -> &'static str {
+        match self {
+            Statistic::VoluntarilyPutInPot => "voluntarily puts money in the pot",
+            Statistic::PreflopRaise => "raises preflop",
+            Statistic::ThreeBet => "3-bets",
+            Statistic::FoldToThreeBet => "folds to 3-bets",
+            Statistic::ContinuationBet => "continuation bets",
+            Statistic::FoldToContinuationBet => "folds to continuation bets",
+            Statistic::WentToShowdown => "goes to showdown after seeing the flop",
+            Statistic::WonAtShowdown => "wins at showdown",
+            Statistic::Squeeze => "squeezes",
+            Statistic::CheckRaise => "check-raises after the flop",
+            Statistic::DonkBet => "donk bets",
+            Statistic::Float => "floats",
+        }
+    }
+}
+
+/// The acceptable range of a statistic's frequency.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Baseline {
+    pub statistic: Statistic,
+    pub min_frequency: f64,
+    pub max_frequency: f64,
+    /// The minimum number of opportunities before the statistic is judged.
+    pub min_sample_size: usize,
+}
+
+impl Baseline {
+    pub fn new(statistic: Statistic, min_frequency: f64, max_frequency: f64, min_sample_size: usize) -> Self {
+        Self { statistic, min_frequency, max_frequency, min_sample_size }
+    }
+
+    /// Returns typical baselines of a solid six-max no-limit hold'em player.
+    pub fn defaults() -> Vec<Self> {
+        vec![
+            Self::new(Statistic::VoluntarilyPutInPot, 0.18, 0.30, 100),
+            Self::new(Statistic::PreflopRaise, 0.14, 0.25, 100),
+            Self::new(Statistic::ThreeBet, 0.05, 0.12, 30),
+            Self::new(Statistic::FoldToThreeBet, 0.40, 0.65, 20),
+            Self::new(Statistic::ContinuationBet, 0.50, 0.80, 30),
+            Self::new(Statistic::FoldToContinuationBet, 0.30, 0.60, 30),
+            Self::new(Statistic::WentToShowdown, 0.22, 0.32, 50),
+            Self::new(Statistic::WonAtShowdown, 0.45, 0.60, 30),
+        ]
+    }
+}
+
+/// The direction in which a frequency deviates from its baseline.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Deviation {
+    TooLow,
+    TooHigh,
+}
+
+/// A statistic whose frequency falls outside of its baseline.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Leak {
+    pub statistic: Statistic,
+    pub deviation: Deviation,
+    pub frequency: f64,
+    pub min_frequency: f64,
+    pub max_frequency: f64,
+    pub sample_size: usize,
+    pub description: String,
+}
+
+/// The likely leaks of a player.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LeakReport<K> {
+    pub player: K,
+    pub hand_count: usize,
+    pub leaks: Vec<Leak>,
+}
+
+impl<K: Serialize> LeakReport<K> {
+    /// Serializes the report to JSON.
+    pub fn to_json(&self) -> Result<String, String> {
+        serde_json::to_string_pretty(self).map_err(|e| e.to_string())
+    }
+}
+
+/// Compares the statistics against the baselines and returns the likely leaks.
+pub fn detect_leaks(stats: &PlayerStats, baselines: &[Baseline]) -> Vec<Leak> {
+    baselines
+        .iter()
+        .filter_map(|baseline| {
+            let sample_size = baseline.statistic.sample_size(stats);
+            let frequency = baseline.statistic.frequency(stats)?;
+            if sample_size < baseline.min_sample_size {
+                return None;
+            }
+            let deviation = if frequency < baseline.min_frequency {
+                Deviation::TooLow
+            } else if frequency > baseline.max_frequency {
+                Deviation::TooHigh
+            } else {
+                return None;
+            };
+            let description = format!(
+                "{} {} ({:.0}% over {} opportunities, expected {:.0}%-{:.0}%)",
+                match deviation { Deviation::TooLow => "Too rarely", Deviation::TooHigh => "Too often" },
+                baseline.statistic.description(),
+                frequency * 100.0,
+                sample_size,
+                baseline.min_frequency * 100.0,
+                baseline.max_frequency * 100.0,
+            );
+
+            Some(Leak {
+                statistic: baseline.statistic,
+                deviation,
+                frequency,
+                min_frequency: baseline.min_frequency,
+                max_frequency: baseline.max_frequency,
+                sample_size,
+                description,
+            })
+        })
+        .collect()
+}
+
+/// Produces a leak report for every player of the aggregator.
+pub fn leak_reports<K: Eq + Hash + Clone>(aggregator: &Aggregator<K>, baselines: &[Baseline]) -> Vec<LeakReport<K>> {
+    aggregator
+        .iter()
+        .map(|(player, stats)| LeakReport {
+            player: player.clone(),
+            hand_count: stats.hand_count,
+            leaks: detect_leaks(stats, baselines),
+        })
+        .collect()
+}
@@ -0,0 +1,107 @@
+//! Implements the labeling of betting lines from the operation log.
+//!
+//! Each street is scanned for well-known sequences: 3-bets and squeezes before the flop, and
+//! check-raises, donk bets, and floats after it. The aggressor of a street is the last player to
+//! complete, bet, or raise on it, and is the reference for donk bets and floats on the next one.
+
+use num_traits::Zero;
+use serde::{Deserialize, Serialize};
+
+use crate::analysis::stats::split_streets;
+use crate::state::{Operation, State};
+use crate::utilities::Amount;
+
+/// A recognized betting line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum BettingLine {
+    /// The second raise before the flop, with no caller of the first.
+    ThreeBet,
+    /// The second raise before the flop, after the first was called.
+    Squeeze,
+    /// A raise by a player who checked earlier on the street.
+    CheckRaise,
+    /// A bet into the aggressor of the previous street before they act.
+    DonkBet,
+    /// A bet after the aggressor of the previous street, whose bet the player called, checks.
+    Float,
+}
+
+/// A betting line taken by a player.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LabeledLine {
+    pub player_index: usize,
+    pub line: BettingLine,
+}
+
+/// Returns the betting lines taken on each street of the hand.
+pub fn label_lines(state: &State) -> Vec<Vec<LabeledLine>> {
+    let mut folded = vec![false; state.player_count];
+    let mut previous_aggressor = None;
+    let mut previous_callers = vec![false; state.player_count];
+    let mut lines = Vec::new();
+
+    for (street_index, operations) in split_streets(&state.operations).into_iter().enumerate() {
+        let mut street_lines = Vec::new();
+        let mut raise_count = 0;
+        let mut open_called = false;
+        let mut aggressor = None;
+        let mut checked = vec![false; state.player_count];
+        let mut acted = vec![false; state.player_count];
+        let mut callers = vec![false; state.player_count];
+
+        for operation in operations {
+            let player_index = match operation {
+                Operation::Folding(op) => {
+                    folded[op.player_index] = true;
+                    op.player_index
+                }
+                Operation::CheckingOrCalling(op) => {
+                    if op.amount == Amount::zero() {
+                        checked[op.player_index] = true;
+                    } else {
+                        callers[op.player_index] = true;
+                        open_called |= raise_count == 1;
+                    }
+                    op.player_index
+                }
+                Operation::CompletionBettingOrRaisingTo(op) => {
+                    let player_index = op.player_index;
+                    let line = if street_index == 0 {
+                        match raise_count {
+                            1 if open_called => Some(BettingLine::Squeeze),
+                            1 => Some(BettingLine::ThreeBet),
+                            _ => None,
+                        }
+                    } else if checked[player_index] && aggressor.is_some() {
+                        Some(BettingLine::CheckRaise)
+                    } else if aggressor.is_some() {
+                        None
+                    } else if previous_aggressor.is_some_and(|i: usize| i != player_index && !folded[i] && !acted[i]) {
+                        Some(BettingLine::DonkBet)
+                    } else if previous_aggressor.is_some_and(|i: usize| i != player_index && checked[i]) && previous_callers[player_index] {
+                        Some(BettingLine::Float)
+                    } else {
+                        None
+                    };
+
+                    if let Some(line) = line {
+                        street_lines.push(LabeledLine { player_index, line });
+                    }
+                    raise_count += 1;
+                    aggressor = Some(player_index);
+                    player_index
+                }
+                _ => continue,
+            };
+            acted[player_index] = true;
+        }
+
+        lines.push(street_lines);
+        if acted.iter().any(|&acted| acted) {
+            previous_aggressor = aggressor;
+            previous_callers = callers;
+        }
+    }
+
+    lines
+}
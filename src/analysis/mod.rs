@@ -0,0 +1,12 @@
+//! Implements analysis tools built on top of the hand evaluators and the game state.
+
+pub mod blockers;
+pub mod equity;
+pub mod flops;
+pub mod high_hands;
+pub mod leaks;
+pub mod lines;
+pub mod preflop;
+pub mod range;
+pub mod session;
+pub mod stats;
@@ -0,0 +1,106 @@
+//! Implements preflop all-in equity charts of the 169 starting hand classes.
+//!
+//! Equities are computed by exact enumeration of the board. Since this is expensive, the
+//! equity of every heads-up matchup is cached on first use, keyed by its suit-canonical form so
+//! that isomorphic matchups share the same entry.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use once_cell::sync::Lazy;
+
+use crate::analysis::equity::{Calculator, Equity};
+use crate::analysis::range::Range;
+use crate::hands::HandType;
+use crate::utilities::{canonicalize_suits, Card, RankOrder};
+
+static MATCHUP_EQUITIES: Lazy<Mutex<HashMap<Vec<Vec<Card>>, Equity>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Returns the 169 starting hand classes, from `AA` down to `22`.
+///
+/// Classes are grouped by their highest rank, and each suited class is followed by its offsuit
+/// counterpart.
+pub fn starting_hand_classes() -> Vec<String> {
+    let ranks: Vec<char> = RankOrder::STANDARD.iter().rev().map(|rank| rank.to_char()).collect();
+    let mut classes = Vec::new();
+
+    for (i, &high) in ranks.iter().enumerate() {
+        for &low in &ranks[i..] {
+            if high == low {
+                classes.push(format!("{}{}", high, low));
+            } else {
+                classes.push(format!("{}{}s", high, low));
+                classes.push(format!("{}{}o", high, low));
+            }
+        }
+    }
+
+    classes
+}
+
+/// Returns the exact all-in equity of a hold'em hand against another.
+fn matchup_equity(hole_cards: &[Card], other_hole_cards: &[Card]) -> Equity {
+    let key = canonicalize_suits(&[hole_cards.to_vec(), other_hole_cards.to_vec()]);
+    if let Some(equity) = MATCHUP_EQUITIES.lock().unwrap().get(&key) {
+        return *equity;
+    }
+
+    let equity = Calculator::new(HandType::StandardHighHand)
+        .hand_vs_range(&key[0], &Range::new(vec![key[1].clone()]))
+        .first()
+        .map_or_else(Equity::default, |(_, equity)| *equity);
+    MATCHUP_EQUITIES.lock().unwrap().insert(key, equity);
+    equity
+}
+
+/// Returns the preflop all-in equity of a starting hand class against a range.
+///
+/// Every non-conflicting combination of the class is matched against every combination of the
+/// range, and the outcomes are summed, weighted by the range.
+pub fn class_vs_range(class: &str, range: &Range) -> Result<Equity, String> {
+    let class_range: Range = class.parse()?;
+    let mut total = Equity::default();
+
+    for combo in class_range.combos() {
+        for (other_combo, weight) in range.without(combo).iter() {
+            total += matchup_equity(combo, other_combo).scaled(weight);
+        }
+    }
+
+    Ok(total)
+}
+
+/// Returns the preflop all-in equity of a starting hand class against another.
+pub fn class_vs_class(class: &str, other_class: &str) -> Result<Equity, String> {
+    class_vs_range(class, &other_class.parse()?)
+}
+
+/// Returns the equity of every starting hand class against the range, in the order of
+/// `starting_hand_classes`.
+pub fn chart_vs_range(range: &Range) -> Vec<(String, f64)> {
+    starting_hand_classes()
+        .into_iter()
+        .map(|class| {
+            let equity = class_vs_range(&class, range).unwrap().equity();
+            (class, equity)
+        })
+        .collect()
+}
+
+/// Returns the 169 by 169 matrix of the equity of every starting hand class (rows) against every
+/// other (columns), in the order of `starting_hand_classes`.
+///
+/// Computing the full matrix enumerates every distinct heads-up matchup, which takes hours; the
+/// results stay cached for the rest of the process.
+pub fn equity_matrix() -> Vec<Vec<f64>> {
+    let classes = starting_hand_classes();
+    classes
+        .iter()
+        .map(|class| {
+            classes
+                .iter()
+                .map(|other_class| class_vs_class(class, other_class).unwrap().equity())
+                .collect()
+        })
+        .collect()
+}
@@ -0,0 +1,260 @@
+//! Implements hand ranges and their parsing from the common range notation.
+//!
+//! The notation supports pairs (`QQ`), suited and offsuit classes (`AKs`, `AKo`, or `AK` for
+//! both), the `+` shorthand (`TT+`, `ATs+`), dashed spans (`22-55`, `A2s-A5s`), and explicit
+//! combinations (`AsKs`). Entries are separated by commas or whitespace, and each may carry a
+//! weight after a colon (`AKo:0.5`), which defaults to one.
+
+use std::collections::HashSet;
+use std::fmt;
+use std::str::FromStr;
+
+use itertools::Itertools;
+
+use crate::utilities::{Card, Rank, RankOrder, Suit};
+
+const SUITS: [Suit; 4] = [Suit::Club, Suit::Diamond, Suit::Heart, Suit::Spade];
+
+/// Returns the index of the rank in the standard rank order, where deuces are the lowest.
+pub(crate) fn rank_index(rank: Rank) -> usize {
+    RankOrder::STANDARD.iter().position(|&r| r == rank).unwrap_or(0)
+}
+
+/// Sorts the cards from the highest to the lowest rank, breaking ties by suit.
+pub(crate) fn sort_cards(cards: &mut [Card]) {
+    cards.sort_by_key(|card| (std::cmp::Reverse(rank_index(card.rank)), card.suit as u8));
+}
+
+/// Returns the starting hand class (e.g. `AKs`, `QJo`, or `TT`) of a two-card combination.
+pub fn hand_class(cards: &[Card]) -> Option<String> {
+    if cards.len() != 2 || cards.iter().any(|card| card.rank == Rank::Unknown) {
+        return None;
+    }
+    let mut cards = cards.to_vec();
+    sort_cards(&mut cards);
+    let ranks = format!("{}{}", cards[0].rank.to_char(), cards[1].rank.to_char());
+
+    if cards[0].rank == cards[1].rank {
+        Some(ranks)
+    } else if cards[0].suit == cards[1].suit {
+        Some(ranks + "s")
+    } else {
+        Some(ranks + "o")
+    }
+}
+
+/// A starting hand class, identified by the rank indices of its two cards.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct HandClass {
+    high: usize,
+    low: usize,
+    suited: Option<bool>,
+}
+
+impl HandClass {
+    fn combos(&self) -> Vec<Vec<Card>> {
+        let high = RankOrder::STANDARD[self.high];
+        let low = RankOrder::STANDARD[self.low];
+
+        if self.high == self.low {
+            return SUITS.iter()
+                .combinations(2)
+                .map(|suits| vec![Card::new(high, *suits[0]), Card::new(low, *suits[1])])
+                .collect();
+        }
+
+        SUITS.iter()
+            .cartesian_product(SUITS.iter())
+            .filter(|(s0, s1)| self.suited.is_none_or(|suited| (s0 == s1) == suited))
+            .map(|(&s0, &s1)| vec![Card::new(high, s0), Card::new(low, s1)])
+            .collect()
+    }
+}
+
+impl FromStr for HandClass {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let chars: Vec<char> = s.chars().collect();
+        if chars.len() != 2 && chars.len() != 3 {
+            return Err(format!("Invalid hand class: '{}'", s));
+        }
+        let rank = |c: char| {
+            Rank::from_str(&c.to_string())
+                .ok()
+                .filter(|&r| r != Rank::Unknown)
+                .map(rank_index)
+                .ok_or_else(|| format!("Invalid rank in hand class: '{}'", s))
+        };
+        let (first, second) = (rank(chars[0])?, rank(chars[1])?);
+        let suited = match chars.get(2) {
+            None => None,
+            Some('s') => Some(true),
+            Some('o') => Some(false),
+            Some(_) => return Err(format!("Invalid suitedness in hand class: '{}'", s)),
+        };
+        if first == second && suited.is_some() {
+            return Err(format!("Pairs cannot be suited or offsuit: '{}'", s));
+        }
+
+        Ok(Self { high: first.max(second), low: first.min(second), suited })
+    }
+}
+
+/// A collection of weighted hole card combinations.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Range {
+    combos: Vec<Vec<Card>>,
+    weights: Vec<f64>,
+}
+
+impl Range {
+    /// Creates a range from explicit combinations, each with a weight of one. Duplicates are
+    /// dropped.
+    pub fn new(combos: Vec<Vec<Card>>) -> Self {
+        Self::with_weights(combos.into_iter().map(|combo| (combo, 1.0)).collect())
+    }
+
+    /// Creates a range from explicit weighted combinations. A repeated combination takes the
+    /// last weight, and combinations with a non-positive weight are dropped.
+    pub fn with_weights(combos: Vec<(Vec<Card>, f64)>) -> Self {
+        let mut range = Self::default();
+        for (combo, weight) in combos {
+            range.insert(combo, weight);
+        }
+        range
+    }
+
+    fn insert(&mut self, mut combo: Vec<Card>, weight: f64) {
+        sort_cards(&mut combo);
+        match self.combos.iter().position(|c| *c == combo) {
+            Some(i) if weight > 0.0 => self.weights[i] = weight,
+            Some(i) => {
+                self.combos.remove(i);
+                self.weights.remove(i);
+            }
+            None if weight > 0.0 => {
+                self.combos.push(combo);
+                self.weights.push(weight);
+            }
+            None => {}
+        }
+    }
+
+    /// Returns the combinations of this range.
+    pub fn combos(&self) -> &[Vec<Card>] {
+        &self.combos
+    }
+
+    /// Returns the weights of the combinations, in the same order as `combos`.
+    pub fn weights(&self) -> &[f64] {
+        &self.weights
+    }
+
+    /// Returns the weight of the combination, or zero if it is not in the range.
+    pub fn weight(&self, combo: &[Card]) -> f64 {
+        let mut combo = combo.to_vec();
+        sort_cards(&mut combo);
+        self.combos.iter().position(|c| *c == combo).map_or(0.0, |i| self.weights[i])
+    }
+
+    /// Iterates over the combinations along with their weights.
+    pub fn iter(&self) -> impl Iterator<Item = (&Vec<Card>, f64)> + Clone {
+        self.combos.iter().zip(self.weights.iter().cloned())
+    }
+
+    /// Returns the sum of the weights of the combinations.
+    pub fn total_weight(&self) -> f64 {
+        self.weights.iter().sum()
+    }
+
+    /// Returns the number of combinations.
+    pub fn len(&self) -> usize {
+        self.combos.len()
+    }
+
+    /// Returns `true` if the range holds no combinations.
+    pub fn is_empty(&self) -> bool {
+        self.combos.is_empty()
+    }
+
+    /// Returns the range without the combinations that contain any of the dead cards.
+    pub fn without(&self, dead_cards: &[Card]) -> Self {
+        let dead_cards: HashSet<&Card> = dead_cards.iter().collect();
+        let (combos, weights) = self.iter()
+            .filter(|(combo, _)| !combo.iter().any(|card| dead_cards.contains(card)))
+            .map(|(combo, weight)| (combo.clone(), weight))
+            .unzip();
+        Self { combos, weights }
+    }
+
+    fn parse_entry(entry: &str) -> Result<Vec<Vec<Card>>, String> {
+        if let Some((start, end)) = entry.split_once('-') {
+            let (start, end): (HandClass, HandClass) = (start.parse()?, end.parse()?);
+            let (start, end) = if start.low <= end.low { (start, end) } else { (end, start) };
+            let pairs = start.high == start.low && end.high == end.low;
+            if start.suited != end.suited || (!pairs && start.high != end.high) {
+                return Err(format!("Invalid span: '{}'", entry));
+            }
+            return Ok((start.low..=end.low)
+                .flat_map(|low| {
+                    let high = if pairs { low } else { start.high };
+                    HandClass { high, low, suited: start.suited }.combos()
+                })
+                .collect());
+        }
+
+        if let Some(class) = entry.strip_suffix('+') {
+            let class: HandClass = class.parse()?;
+            let top = if class.high == class.low { RankOrder::STANDARD.len() - 1 } else { class.high - 1 };
+            return Ok((class.low..=top)
+                .flat_map(|low| {
+                    let high = if class.high == class.low { low } else { class.high };
+                    HandClass { high, low, suited: class.suited }.combos()
+                })
+                .collect());
+        }
+
+        if entry.len() >= 4 {
+            if let Ok(cards) = Card::parse_cards(entry) {
+                return Ok(vec![cards]);
+            }
+        }
+
+        Ok(entry.parse::<HandClass>()?.combos())
+    }
+}
+
+impl FromStr for Range {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut range = Self::default();
+        for entry in s.split(|c: char| c == ',' || c.is_whitespace()).filter(|entry| !entry.is_empty()) {
+            let (entry, weight) = match entry.split_once(':') {
+                Some((entry, weight)) => {
+                    let weight = weight.parse::<f64>()
+                        .map_err(|_| format!("Invalid weight in range entry: '{}'", entry))?;
+                    (entry, weight)
+                }
+                None => (entry, 1.0),
+            };
+            for combo in Self::parse_entry(entry)? {
+                range.insert(combo, weight);
+            }
+        }
+        Ok(range)
+    }
+}
+
+impl fmt::Display for Range {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let combos: Vec<String> = self.iter()
+            .map(|(combo, weight)| {
+                let cards: String = combo.iter().map(|card| card.to_string()).collect();
+                if weight == 1.0 { cards } else { format!("{}:{}", cards, weight) }
+            })
+            .collect();
+        write!(f, "{}", combos.join(","))
+    }
+}
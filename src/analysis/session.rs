@@ -0,0 +1,157 @@
+//! Implements the tracking of results over a session of consecutive hands.
+//!
+//! Each finished hand contributes one record per player with the net result, the rake and jackpot
+//! drop paid, and the showdown outcome. The records export to CSV, one row per player and hand,
+//! with running totals ready for graphing cumulative winnings.
+
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::hash::Hash;
+
+use num_traits::{ToPrimitive, Zero};
+
+use crate::analysis::stats::{showdown_indices, won_amounts};
+use crate::export::escape_field;
+use crate::state::{Operation, State};
+use crate::utilities::Amount;
+
+/// How a player's hand ended with respect to the showdown.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShowdownOutcome {
+    NoShowdown,
+    Won,
+    Lost,
+}
+
+impl ShowdownOutcome {
+    fn as_str(&self) -> &'static str {
+        match self {
+            ShowdownOutcome::NoShowdown => "none",
+            ShowdownOutcome::Won => "won",
+            ShowdownOutcome::Lost => "lost",
+        }
+    }
+}
+
+/// The result of a player in a single hand.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HandRecord<K> {
+    /// The zero-based number of the hand within the session.
+    pub hand_number: usize,
+    pub player: K,
+    /// The net amount won (positive) or lost (negative).
+    pub net: Amount,
+    /// The running total of the player's net amounts, including this hand.
+    pub cumulative_net: Amount,
+    /// The rake taken from the pots the player won, each split in proportion to the amounts won.
+    pub rake_paid: f64,
+    /// The jackpot drop taken from the pots the player won, split likewise.
+    pub drop_paid: f64,
+    pub showdown_outcome: ShowdownOutcome,
+}
+
+/// Tracks the results of players, identified by keys of type `K`, over a session.
+#[derive(Debug, Clone)]
+pub struct SessionTracker<K: Eq + Hash + Clone> {
+    records: Vec<HandRecord<K>>,
+    totals: HashMap<K, Amount>,
+    hand_count: usize,
+}
+
+impl<K: Eq + Hash + Clone> Default for SessionTracker<K> {
+    fn default() -> Self {
+        Self { records: Vec::new(), totals: HashMap::new(), hand_count: 0 }
+    }
+}
+
+impl<K: Eq + Hash + Clone> SessionTracker<K> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the records, in the order they were added.
+    pub fn records(&self) -> &[HandRecord<K>] {
+        &self.records
+    }
+
+    /// Returns the number of hands recorded.
+    pub fn hand_count(&self) -> usize {
+        self.hand_count
+    }
+
+    /// Returns the total net result of the player over the session.
+    pub fn net(&self, player: &K) -> Amount {
+        self.totals.get(player).cloned().unwrap_or_default()
+    }
+
+    /// Records a finished hand. `players` maps each seat of the state to its player.
+    pub fn record(&mut self, state: &State, players: &[K]) -> Result<(), String> {
+        if state.status {
+            return Err("Only finished hands can be recorded.".to_string());
+        }
+        if players.len() != state.player_count {
+            return Err("A player must be supplied for every seat.".to_string());
+        }
+
+        let won_amounts = won_amounts(state);
+        let mut rakes_paid = vec![0.0; state.player_count];
+        let mut drops_paid = vec![0.0; state.player_count];
+        for operation in state.operations.iter() {
+            if let Operation::ChipsPushing(op) = operation {
+                let total_amount = op.amounts.iter().sum::<Amount>().to_f64().unwrap_or_default();
+                for (i, &amount) in op.amounts.iter().enumerate().filter(|_| total_amount > 0.0) {
+                    let share = amount.to_f64().unwrap_or_default() / total_amount;
+                    rakes_paid[i] += op.raked_amount.to_f64().unwrap_or_default() * share;
+                    drops_paid[i] += op.dropped_amount.to_f64().unwrap_or_default() * share;
+                }
+            }
+        }
+        let showdown_indices = showdown_indices(state);
+
+        for (i, player) in players.iter().enumerate() {
+            let net = state.payoffs[i];
+            let cumulative_net = self.totals.entry(player.clone()).or_default();
+            *cumulative_net += net;
+            let showdown_outcome = if !showdown_indices.contains(&i) {
+                ShowdownOutcome::NoShowdown
+            } else if won_amounts[i] > Amount::zero() {
+                ShowdownOutcome::Won
+            } else {
+                ShowdownOutcome::Lost
+            };
+
+            self.records.push(HandRecord {
+                hand_number: self.hand_count,
+                player: player.clone(),
+                net,
+                cumulative_net: *cumulative_net,
+                rake_paid: rakes_paid[i],
+                drop_paid: drops_paid[i],
+                showdown_outcome,
+            });
+        }
+        self.hand_count += 1;
+
+        Ok(())
+    }
+}
+
+impl<K: Eq + Hash + Clone + Display> SessionTracker<K> {
+    /// Exports the records to CSV with a header row.
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("hand,player,net,cumulative_net,rake_paid,drop_paid,showdown\n");
+        for record in &self.records {
+            csv.push_str(&format!(
+                "{},{},{},{},{},{},{}\n",
+                record.hand_number,
+                escape_field(&record.player.to_string(), ','),
+                record.net,
+                record.cumulative_net,
+                record.rake_paid,
+                record.drop_paid,
+                record.showdown_outcome.as_str(),
+            ));
+        }
+        csv
+    }
+}
@@ -0,0 +1,279 @@
+//! Implements the aggregation of player statistics over played hands.
+//!
+//! Statistics are derived from the operation log of each hand. Operations are classified by
+//! street and, before the flop, by how many raises were made so far. Every statistic is stored
+//! as a count of occurrences along with the count of opportunities.
+
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::ops::AddAssign;
+
+use num_traits::Zero;
+
+use crate::analysis::lines::{label_lines, BettingLine};
+use crate::state::{Operation, State};
+use crate::utilities::Amount;
+
+/// Splits the operations by street.
+///
+/// A new street begins with the first dealing operation (burning, hole or board dealing, or
+/// drawing) that follows any betting. Consecutive streets without betting in between (e.g. when
+/// every player is all-in) are therefore merged.
+pub fn split_streets(operations: &[Operation]) -> Vec<&[Operation]> {
+    let mut streets = Vec::new();
+    let mut start = 0;
+    let mut betting = false;
+
+    for (i, operation) in operations.iter().enumerate() {
+        match operation {
+            Operation::CardBurning(_) | Operation::HoleDealing(_) | Operation::BoardDealing(_)
+            | Operation::StandingPatOrDiscarding(_) if betting => {
+                streets.push(&operations[start..i]);
+                start = i;
+                betting = false;
+            }
+            Operation::Folding(_) | Operation::CheckingOrCalling(_) | Operation::BringInPosting(_)
+            | Operation::CompletionBettingOrRaisingTo(_) => betting = true,
+            _ => {}
+        }
+    }
+    streets.push(&operations[start..]);
+
+    streets
+}
+
+/// Returns the players who went to showdown, that is, who never folded in a hand that ended
+/// with at least two such players.
+pub fn showdown_indices(state: &State) -> Vec<usize> {
+    let mut folded = vec![false; state.player_count];
+    for operation in state.operations.iter() {
+        if let Operation::Folding(op) = operation {
+            folded[op.player_index] = true;
+        }
+    }
+
+    let indices: Vec<usize> = (0..state.player_count).filter(|&i| !folded[i]).collect();
+    if indices.len() >= 2 { indices } else { Vec::new() }
+}
+
+/// Returns the amounts pushed to each player from the pots.
+pub fn won_amounts(state: &State) -> Vec<Amount> {
+    let mut amounts = vec![Amount::zero(); state.player_count];
+    for operation in state.operations.iter() {
+        if let Operation::ChipsPushing(op) = operation {
+            for (total, amount) in amounts.iter_mut().zip(&op.amounts) {
+                *total += *amount;
+            }
+        }
+    }
+    amounts
+}
+
+/// Returns the occurrences over the opportunities, or `None` without any opportunity.
+fn frequency(count: usize, opportunity_count: usize) -> Option<f64> {
+    if opportunity_count == 0 { None } else { Some(count as f64 / opportunity_count as f64) }
+}
+
+/// The aggregated statistics of a player.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PlayerStats {
+    pub hand_count: usize,
+    pub voluntarily_put_in_pot_count: usize,
+    pub preflop_raise_count: usize,
+    pub three_bet_opportunity_count: usize,
+    pub three_bet_count: usize,
+    pub fold_to_three_bet_opportunity_count: usize,
+    pub fold_to_three_bet_count: usize,
+    pub continuation_bet_opportunity_count: usize,
+    pub continuation_bet_count: usize,
+    pub fold_to_continuation_bet_opportunity_count: usize,
+    pub fold_to_continuation_bet_count: usize,
+    pub saw_flop_count: usize,
+    pub went_to_showdown_count: usize,
+    pub won_at_showdown_count: usize,
+    pub squeeze_count: usize,
+    pub check_raise_count: usize,
+    pub donk_bet_count: usize,
+    pub float_count: usize,
+}
+
+impl PlayerStats {
+    pub fn voluntarily_put_in_pot(&self) -> Option<f64> { frequency(self.voluntarily_put_in_pot_count, self.hand_count) }
+    pub fn preflop_raise(&self) -> Option<f64> { frequency(self.preflop_raise_count, self.hand_count) }
+    pub fn three_bet(&self) -> Option<f64> { frequency(self.three_bet_count, self.three_bet_opportunity_count) }
+    pub fn fold_to_three_bet(&self) -> Option<f64> { frequency(self.fold_to_three_bet_count, self.fold_to_three_bet_opportunity_count) }
+    pub fn continuation_bet(&self) -> Option<f64> { frequency(self.continuation_bet_count, self.continuation_bet_opportunity_count) }
+    pub fn fold_to_continuation_bet(&self) -> Option<f64> { frequency(self.fold_to_continuation_bet_count, self.fold_to_continuation_bet_opportunity_count) }
+    pub fn went_to_showdown(&self) -> Option<f64> { frequency(self.went_to_showdown_count, self.saw_flop_count) }
+    pub fn won_at_showdown(&self) -> Option<f64> { frequency(self.won_at_showdown_count, self.went_to_showdown_count) }
+    pub fn squeeze(&self) -> Option<f64> { frequency(self.squeeze_count, self.hand_count) }
+    pub fn check_raise(&self) -> Option<f64> { frequency(self.check_raise_count, self.saw_flop_count) }
+    pub fn donk_bet(&self) -> Option<f64> { frequency(self.donk_bet_count, self.saw_flop_count) }
+    pub fn float(&self) -> Option<f64> { frequency(self.float_count, self.saw_flop_count) }
+}
+
+impl AddAssign for PlayerStats {
+    fn add_assign(&mut self, other: Self) {
+        self.hand_count += other.hand_count;
+        self.voluntarily_put_in_pot_count += other.voluntarily_put_in_pot_count;
+        self.preflop_raise_count += other.preflop_raise_count;
+        self.three_bet_opportunity_count += other.three_bet_opportunity_count;
+        self.three_bet_count += other.three_bet_count;
+        self.fold_to_three_bet_opportunity_count += other.fold_to_three_bet_opportunity_count;
+        self.fold_to_three_bet_count += other.fold_to_three_bet_count;
+        self.continuation_bet_opportunity_count += other.continuation_bet_opportunity_count;
+        self.continuation_bet_count += other.continuation_bet_count;
+        self.fold_to_continuation_bet_opportunity_count += other.fold_to_continuation_bet_opportunity_count;
+        self.fold_to_continuation_bet_count += other.fold_to_continuation_bet_count;
+        self.saw_flop_count += other.saw_flop_count;
+        self.went_to_showdown_count += other.went_to_showdown_count;
+        self.won_at_showdown_count += other.won_at_showdown_count;
+        self.squeeze_count += other.squeeze_count;
+        self.check_raise_count += other.check_raise_count;
+        self.donk_bet_count += other.donk_bet_count;
+        self.float_count += other.float_count;
+    }
+}
+
+/// Aggregates the statistics of players, identified by keys of type `K`, over many hands.
+#[derive(Debug, Clone)]
+pub struct Aggregator<K: Eq + Hash + Clone> {
+    stats: HashMap<K, PlayerStats>,
+}
+
+impl<K: Eq + Hash + Clone> Default for Aggregator<K> {
+    fn default() -> Self {
+        Self { stats: HashMap::new() }
+    }
+}
+
+impl<K: Eq + Hash + Clone> Aggregator<K> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the statistics of the player, if any hand of theirs was added.
+    pub fn get(&self, player: &K) -> Option<&PlayerStats> {
+        self.stats.get(player)
+    }
+
+    /// Iterates over the players and their statistics.
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &PlayerStats)> {
+        self.stats.iter()
+    }
+
+    /// Adds a hand to the statistics. `players` maps each seat of the state to its player.
+    pub fn add(&mut self, state: &State, players: &[K]) -> Result<(), String> {
+        if players.len() != state.player_count {
+            return Err("A player must be supplied for every seat.".to_string());
+        }
+
+        let player_count = state.player_count;
+        let mut stats = vec![PlayerStats { hand_count: 1, ..PlayerStats::default() }; player_count];
+        let mut folded = vec![false; player_count];
+        let mut preflop_aggressor = None;
+        let streets = split_streets(&state.operations);
+
+        // Preflop: voluntary money, raises, 3-bets, and folds to 3-bets.
+        let mut raise_count = 0;
+        let mut opener = None;
+        let mut three_bet_considered = vec![false; player_count];
+        let mut opener_responded = false;
+        for operation in streets[0] {
+            let (player_index, raising) = match operation {
+                Operation::Folding(op) => (op.player_index, false),
+                Operation::CheckingOrCalling(op) => {
+                    if op.amount > Amount::zero() {
+                        stats[op.player_index].voluntarily_put_in_pot_count = 1;
+                    }
+                    (op.player_index, false)
+                }
+                Operation::CompletionBettingOrRaisingTo(op) => {
+                    stats[op.player_index].voluntarily_put_in_pot_count = 1;
+                    stats[op.player_index].preflop_raise_count = 1;
+                    (op.player_index, true)
+                }
+                _ => continue,
+            };
+
+            if raise_count == 1 && opener != Some(player_index) && !three_bet_considered[player_index] {
+                three_bet_considered[player_index] = true;
+                stats[player_index].three_bet_opportunity_count = 1;
+                stats[player_index].three_bet_count = raising as usize;
+            }
+            if raise_count == 2 && opener == Some(player_index) && !opener_responded {
+                opener_responded = true;
+                stats[player_index].fold_to_three_bet_opportunity_count = 1;
+                stats[player_index].fold_to_three_bet_count = matches!(operation, Operation::Folding(_)) as usize;
+            }
+            if let Operation::Folding(_) = operation {
+                folded[player_index] = true;
+            }
+            if raising {
+                raise_count += 1;
+                opener = opener.or(Some(player_index));
+                preflop_aggressor = Some(player_index);
+            }
+        }
+
+        // Flop: continuation bets and folds to them.
+        if let Some(flop) = streets.get(1) {
+            for i in (0..player_count).filter(|&i| !folded[i]) {
+                stats[i].saw_flop_count = 1;
+            }
+
+            let mut bet_made = false;
+            let mut continuation_bettor = None;
+            let mut responded = vec![false; player_count];
+            for operation in *flop {
+                let player_index = match operation {
+                    Operation::Folding(op) => op.player_index,
+                    Operation::CheckingOrCalling(op) => op.player_index,
+                    Operation::CompletionBettingOrRaisingTo(op) => op.player_index,
+                    _ => continue,
+                };
+
+                if !bet_made && preflop_aggressor == Some(player_index) {
+                    stats[player_index].continuation_bet_opportunity_count = 1;
+                    if let Operation::CompletionBettingOrRaisingTo(_) = operation {
+                        stats[player_index].continuation_bet_count = 1;
+                        continuation_bettor = Some(player_index);
+                    }
+                } else if continuation_bettor.is_some_and(|i| i != player_index) && !responded[player_index] {
+                    responded[player_index] = true;
+                    stats[player_index].fold_to_continuation_bet_opportunity_count = 1;
+                    stats[player_index].fold_to_continuation_bet_count = matches!(operation, Operation::Folding(_)) as usize;
+                }
+                if let Operation::CompletionBettingOrRaisingTo(_) = operation {
+                    bet_made = true;
+                }
+            }
+        }
+
+        let won_amounts = won_amounts(state);
+        for i in showdown_indices(state) {
+            if stats[i].saw_flop_count == 1 {
+                stats[i].went_to_showdown_count = 1;
+                stats[i].won_at_showdown_count = (won_amounts[i] > Amount::zero()) as usize;
+            }
+        }
+
+        // Betting lines, counted at most once per hand.
+        for labeled_line in label_lines(state).into_iter().flatten() {
+            let hand_stats = &mut stats[labeled_line.player_index];
+            match labeled_line.line {
+                BettingLine::ThreeBet => {}
+                BettingLine::Squeeze => hand_stats.squeeze_count = 1,
+                BettingLine::CheckRaise => hand_stats.check_raise_count = 1,
+                BettingLine::DonkBet => hand_stats.donk_bet_count = 1,
+                BettingLine::Float => hand_stats.float_count = 1,
+            }
+        }
+
+        for (player, hand_stats) in players.iter().zip(stats) {
+            *self.stats.entry(player.clone()).or_default() += hand_stats;
+        }
+
+        Ok(())
+    }
+}
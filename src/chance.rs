@@ -0,0 +1,92 @@
+//! Implements the enumeration and sampling of chance outcomes.
+//!
+//! When a state awaits a card to be burned or dealt, every set of cards that could come next is
+//! a chance outcome. These helpers list the outcomes, or sample one, from the cards still live
+//! after removing any dead cards the caller knows of, so external CFR implementations can walk
+//! chance nodes with the correct probabilities.
+
+use itertools::Itertools;
+use rand::seq::index::sample;
+use rand::Rng;
+
+use crate::state::{Action, State, StateError};
+use crate::utilities::Card;
+
+/// The dealing operation a state awaits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChanceEvent {
+    CardBurning,
+    HoleDealing { player_index: usize },
+    BoardDealing { card_count: usize },
+}
+
+impl ChanceEvent {
+    /// Returns the number of cards dealt at once.
+    pub fn card_count(&self) -> usize {
+        match self {
+            ChanceEvent::CardBurning | ChanceEvent::HoleDealing { .. } => 1,
+            ChanceEvent::BoardDealing { card_count } => *card_count,
+        }
+    }
+}
+
+/// A set of cards that may be dealt, and its probability.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChanceOutcome {
+    pub cards: Vec<Card>,
+    pub probability: f64,
+}
+
+/// Returns the dealing operation the state awaits, if any.
+pub fn chance_event(state: &State) -> Option<ChanceEvent> {
+    if !state.status || !state.actor_indices.is_empty() {
+        None
+    } else if state.card_burning_status {
+        Some(ChanceEvent::CardBurning)
+    } else if let Some(player_index) = state.hole_dealee_index() {
+        Some(ChanceEvent::HoleDealing { player_index })
+    } else {
+        state.board_dealing_index().map(|i| ChanceEvent::BoardDealing { card_count: state.board_dealing_counts[i] })
+    }
+}
+
+fn live_cards(state: &State, dead_cards: &[Card]) -> Vec<Card> {
+    state.deck_cards.iter().filter(|card| !dead_cards.contains(card)).cloned().collect()
+}
+
+/// Lists every outcome of the dealing operation the state awaits, each equally likely.
+///
+/// The cards are drawn from the rest of the deck, except the dead cards.
+pub fn chance_outcomes(state: &State, dead_cards: &[Card]) -> Vec<ChanceOutcome> {
+    let Some(event) = chance_event(state) else { return Vec::new() };
+    let combinations: Vec<Vec<Card>> = live_cards(state, dead_cards).into_iter().combinations(event.card_count()).collect();
+    let probability = 1.0 / combinations.len() as f64;
+
+    combinations.into_iter().map(|cards| ChanceOutcome { cards, probability }).collect()
+}
+
+/// Samples an outcome of the dealing operation the state awaits, without listing them all.
+pub fn sample_chance_outcome<R: Rng + ?Sized>(state: &State, dead_cards: &[Card], rng: &mut R) -> Option<ChanceOutcome> {
+    let event = chance_event(state)?;
+    let cards = live_cards(state, dead_cards);
+    if cards.len() < event.card_count() {
+        return None;
+    }
+
+    let outcome_count = (0..event.card_count()).fold(1.0, |count, i| count * (cards.len() - i) as f64 / (i + 1) as f64);
+    Some(ChanceOutcome {
+        cards: sample(rng, cards.len(), event.card_count()).into_iter().map(|i| cards[i]).collect(),
+        probability: 1.0 / outcome_count,
+    })
+}
+
+/// Carries out the dealing operation the state awaits with the cards of the outcome.
+pub fn apply_chance_outcome(state: &mut State, outcome: &ChanceOutcome) -> Result<(), StateError> {
+    match chance_event(state).ok_or(StateError::UnavailableOperation { operation: "dealing" })? {
+        ChanceEvent::CardBurning => state.burn_card(Some(outcome.cards[0]), None).map(|_| ()),
+        ChanceEvent::HoleDealing { player_index } => {
+            state.apply(Action::DealHole { player_index, cards: outcome.cards.clone() }).map(|_| ())
+        }
+        ChanceEvent::BoardDealing { .. } => state.apply(Action::DealBoard(outcome.cards.clone())).map(|_| ()),
+    }
+}
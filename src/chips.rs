@@ -0,0 +1,109 @@
+//! Implements chip denominations and the tournament color-up.
+//!
+//! Stacks are plain amounts everywhere else; when denominations are given, an amount is broken
+//! down into as few chips as possible. Coloring up retires the smallest denomination: every
+//! player's chips of it are exchanged for the next denomination, and the odd chips left over are
+//! raced off, one card per odd chip, with no player winning more than one chip.
+
+use std::collections::BTreeMap;
+
+use num_traits::{ToPrimitive, Zero};
+use rand::Rng;
+
+use crate::utilities::{to_amount, Amount};
+
+/// A breakdown of an amount into chips.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Chips {
+    /// The number of chips of each denomination.
+    pub counts: BTreeMap<Amount, u64>,
+}
+
+impl Chips {
+    /// Breaks the amount down into as few chips of the denominations as possible.
+    pub fn make_change(amount: Amount, denominations: &[Amount]) -> Result<Self, String> {
+        let mut denominations = denominations.to_vec();
+        denominations.sort_unstable_by(|a, b| b.cmp(a));
+        let mut counts = BTreeMap::new();
+        let mut remainder = amount;
+        for denomination in denominations {
+            if denomination <= Amount::zero() {
+                return Err("Denominations must be positive".to_string());
+            }
+            counts.insert(denomination, (remainder / denomination).to_u64().unwrap_or_default());
+            remainder %= denomination;
+        }
+        if remainder != Amount::zero() {
+            return Err(format!("The amount {} cannot be made of the denominations", amount));
+        }
+
+        Ok(Self { counts })
+    }
+
+    pub fn count(&self, denomination: Amount) -> u64 {
+        self.counts.get(&denomination).cloned().unwrap_or(0)
+    }
+
+    pub fn total(&self) -> Amount {
+        self.counts.iter().map(|(&denomination, &count)| denomination * to_amount(count as usize)).sum()
+    }
+}
+
+/// The outcome of a color-up.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ColorUp {
+    pub stacks: Vec<Amount>,
+    /// The denominations still in play.
+    pub denominations: Vec<Amount>,
+    /// The players who won a chip in the race-off.
+    pub race_winner_indices: Vec<usize>,
+    /// The total of the odd chips raced off.
+    pub raced_amount: Amount,
+    /// The total of the chips won in the race-off, which differs from the raced amount by the
+    /// rounding of the race.
+    pub won_amount: Amount,
+}
+
+/// Retires the smallest denomination from the stacks, racing off the odd chips.
+///
+/// The race awards one chip of the next denomination for every such chip's worth of odd chips,
+/// rounding half up.
+pub fn color_up<R: Rng + ?Sized>(stacks: &[Amount], denominations: &[Amount], rng: &mut R) -> Result<ColorUp, String> {
+    let mut denominations = denominations.to_vec();
+    denominations.sort_unstable();
+    let (retired, next) = match denominations[..] {
+        [retired, next, ..] if next % retired == Amount::zero() => (retired, next),
+        [_, _, ..] => return Err("Each denomination must be a multiple of the one it replaces".to_string()),
+        _ => return Err("A larger denomination is needed to color up".to_string()),
+    };
+
+    let mut new_stacks = Vec::new();
+    let mut cards = Vec::new();
+    for (i, &stack) in stacks.iter().enumerate() {
+        let odd_amount = to_amount(Chips::make_change(stack, &denominations)?.count(retired) as usize) * retired % next;
+        new_stacks.push(stack - odd_amount);
+        cards.extend((0..(odd_amount / retired).to_u64().unwrap_or_default()).map(|_| (rng.gen::<u64>(), i)));
+    }
+
+    let raced_amount = to_amount(cards.len()) * retired;
+    let race_chip_count = ((raced_amount + next / to_amount(2)) / next).to_usize().unwrap_or_default();
+    cards.sort_unstable_by(|a, b| b.cmp(a));
+    let mut race_winner_indices: Vec<usize> = Vec::new();
+    for (_, i) in cards {
+        if race_winner_indices.len() == race_chip_count {
+            break;
+        }
+        if !race_winner_indices.contains(&i) {
+            race_winner_indices.push(i);
+            new_stacks[i] += next;
+        }
+    }
+
+    Ok(ColorUp {
+        stacks: new_stacks,
+        denominations: denominations[1..].to_vec(),
+        won_amount: to_amount(race_winner_indices.len()) * next,
+        race_winner_indices,
+        raced_amount,
+    })
+}
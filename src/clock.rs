@@ -0,0 +1,38 @@
+//! Implements an action clock that acts on behalf of players who run out of time.
+//!
+//! Every player has a fixed time for each action, plus a time bank drawn on once that runs out.
+//! The state keeps these books itself once built with
+//! [`StateBuilder::time_bank`](crate::state::StateBuilder::time_bank), timing the turns with its
+//! clock source. The clock is only a driver ticked by the host: when the player in turn has used
+//! up both, it checks for them if it can, or folds otherwise (posting the bring-in if it is due),
+//! so that unattended seats do not stall the game.
+
+use std::time::Duration;
+
+use crate::state::{Operation, State, StateError};
+
+/// The commentary recorded with the actions taken on expiry.
+pub const EXPIRY_COMMENTARY: &str = "Time expired";
+
+/// Acts for the players of a timed hand whose time has expired.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ActionClock;
+
+impl ActionClock {
+    /// Returns the time left to the player in turn, time bank included, if the turns are timed.
+    pub fn remaining(&self, state: &State) -> Option<Duration> {
+        state.remaining_time()
+    }
+
+    /// Checks or folds for the player in turn if their time has expired, and returns the
+    /// operation taken, if any.
+    ///
+    /// The time a player spent beyond the time per action is charged to their time bank by the
+    /// state as soon as they act.
+    pub fn tick(&self, state: &mut State) -> Result<Option<Operation>, StateError> {
+        if self.remaining(state).is_none_or(|remaining| !remaining.is_zero()) {
+            return Ok(None);
+        }
+        state.timeout_check().map(Some)
+    }
+}
@@ -0,0 +1,201 @@
+//! Implements the generation of datasets from simulated hands.
+//!
+//! Hands of a variant are played out by scripted agents, each seeded from the generator seed and
+//! the hand number, so that the generated hands do not depend on how many threads are used. The
+//! resulting states can be exported with [`crate::export`].
+
+use std::str::FromStr;
+use std::thread;
+
+use num_traits::Zero;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+
+use crate::games::{FixedLimitTexasHoldem, NoLimitTexasHoldem, PotLimitOmahaHoldem};
+use crate::mcts::{sample_action, Policy};
+use crate::state::{Action, AntePlacement, Automation, BuildError, Mode, State, StateError};
+use crate::utilities::{to_amount, Amount};
+
+/// The variants that can be simulated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Variant {
+    FixedLimitTexasHoldem,
+    NoLimitTexasHoldem,
+    PotLimitOmahaHoldem,
+}
+
+impl FromStr for Variant {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "flhe" => Ok(Variant::FixedLimitTexasHoldem),
+            "nlhe" => Ok(Variant::NoLimitTexasHoldem),
+            "plo" => Ok(Variant::PotLimitOmahaHoldem),
+            _ => Err(format!("Unknown variant: {}", s)),
+        }
+    }
+}
+
+/// Scripted agents choosing among the candidate actions of [`crate::mcts::candidate_actions`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Agent {
+    /// Chooses uniformly at random.
+    Random,
+    /// Always checks or calls.
+    Passive,
+    /// Prefers betting and raising.
+    Aggressive,
+    /// Prefers folding when facing a bet and checking otherwise.
+    Tight,
+}
+
+impl FromStr for Agent {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "random" => Ok(Agent::Random),
+            "passive" => Ok(Agent::Passive),
+            "aggressive" => Ok(Agent::Aggressive),
+            "tight" => Ok(Agent::Tight),
+            _ => Err(format!("Unknown agent: {}", s)),
+        }
+    }
+}
+
+impl Policy for Agent {
+    fn weights(&self, _state: &State, actions: &[Action]) -> Vec<f64> {
+        actions.iter()
+            .map(|action| match (self, action) {
+                (Agent::Random, _) => 1.0,
+                (Agent::Passive, Action::CheckOrCall) => 1.0,
+                (Agent::Passive, _) => 0.0,
+                (Agent::Aggressive, Action::CompleteBetOrRaiseTo(_)) => 3.0,
+                (Agent::Aggressive, _) => 1.0,
+                (Agent::Tight, Action::CompleteBetOrRaiseTo(_)) => 1.0,
+                (Agent::Tight, _) => 3.0,
+            })
+            .collect()
+    }
+}
+
+/// Plays a hand out, dealing the cards and making the decisions of each player with its agent.
+///
+/// An agent with no action to choose from checks or calls. Returns an error if the hand reaches
+/// an operation the simulation cannot carry out.
+pub fn play_hand<R: Rng + ?Sized>(state: &mut State, agents: &[&dyn Policy], rng: &mut R) -> Result<(), StateError> {
+    state.play_out(|state, player_index| sample_action(state, agents[player_index], rng).unwrap_or(Action::CheckOrCall))
+}
+
+/// The reason a hand cannot be simulated.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SimulationError {
+    /// The state of the hand cannot be built.
+    Build(BuildError),
+    /// The hand cannot be played out.
+    State(StateError),
+}
+
+impl std::fmt::Display for SimulationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SimulationError::Build(error) => write!(f, "The hand cannot be built: {}", error),
+            SimulationError::State(error) => write!(f, "The hand cannot be played out: {}", error),
+        }
+    }
+}
+
+impl std::error::Error for SimulationError {}
+
+impl From<BuildError> for SimulationError {
+    fn from(error: BuildError) -> Self { SimulationError::Build(error) }
+}
+
+impl From<StateError> for SimulationError {
+    fn from(error: StateError) -> Self { SimulationError::State(error) }
+}
+
+impl From<SimulationError> for String {
+    fn from(error: SimulationError) -> Self { error.to_string() }
+}
+
+/// Generates simulated hands of a variant.
+pub struct Generator {
+    variant: Variant,
+    agents: Vec<Agent>,
+    starting_stack: Amount,
+    big_blind: Amount,
+    seed: u64,
+    thread_count: usize,
+}
+
+impl Generator {
+    /// Creates a generator with one agent per seat.
+    pub fn new(variant: Variant, agents: Vec<Agent>) -> Self {
+        Self { variant, agents, starting_stack: to_amount(200), big_blind: to_amount(2), seed: 0, thread_count: 1 }
+    }
+
+    pub fn starting_stack(mut self, starting_stack: Amount) -> Self { self.starting_stack = starting_stack; self }
+    pub fn big_blind(mut self, big_blind: Amount) -> Self { self.big_blind = big_blind; self }
+    pub fn seed(mut self, seed: u64) -> Self { self.seed = seed; self }
+    pub fn thread_count(mut self, thread_count: usize) -> Self { self.thread_count = thread_count.max(1); self }
+
+    fn create_state(&self) -> Result<State, BuildError> {
+        let player_count = self.agents.len();
+        let automations = [
+            Automation::AntePosting,
+            Automation::BetCollection,
+            Automation::BlindOrStraddlePosting,
+            Automation::HoleCardsShowingOrMucking,
+            Automation::HandKilling,
+            Automation::ChipsPushing,
+            Automation::ChipsPulling,
+        ];
+        let blinds = [self.big_blind / to_amount(2), self.big_blind];
+        let stacks = self.starting_stack;
+
+        match self.variant {
+            Variant::FixedLimitTexasHoldem => FixedLimitTexasHoldem::create_state(
+                &automations, true, Amount::zero(), AntePlacement::PerPlayer, blinds, self.big_blind, self.big_blind + self.big_blind, stacks,
+                player_count, Mode::CashGame,
+            ),
+            Variant::NoLimitTexasHoldem => NoLimitTexasHoldem::create_state(
+                &automations, true, Amount::zero(), AntePlacement::PerPlayer, blinds, self.big_blind, stacks, player_count, Mode::CashGame,
+            ),
+            Variant::PotLimitOmahaHoldem => PotLimitOmahaHoldem::create_state(
+                &automations, true, Amount::zero(), AntePlacement::PerPlayer, blinds, self.big_blind, stacks, player_count, Mode::CashGame,
+            ),
+        }
+    }
+
+    /// Simulates a single hand, seeded by its number.
+    pub fn simulate(&self, hand_number: u64) -> Result<State, SimulationError> {
+        let mut rng = StdRng::seed_from_u64(self.seed.wrapping_add(hand_number));
+        let mut state = self.create_state()?;
+        state.deck_cards.make_contiguous().shuffle(&mut rng);
+        let agents: Vec<&dyn Policy> = self.agents.iter().map(|agent| agent as &dyn Policy).collect();
+        play_hand(&mut state, &agents, &mut rng)?;
+
+        Ok(state)
+    }
+
+    /// Simulates the hands, in parallel, and returns the results in order of the hand numbers.
+    pub fn generate(&self, hand_count: u64) -> Vec<Result<State, SimulationError>> {
+        let chunk_size = hand_count.div_ceil(self.thread_count as u64).max(1);
+
+        thread::scope(|scope| {
+            let handles: Vec<_> = (0..hand_count)
+                .step_by(chunk_size as usize)
+                .map(|start| {
+                    scope.spawn(move || {
+                        (start..(start + chunk_size).min(hand_count)).map(|i| self.simulate(i)).collect::<Vec<_>>()
+                    })
+                })
+                .collect();
+
+            handles.into_iter().flat_map(|handle| handle.join().unwrap()).collect()
+        })
+    }
+}
@@ -0,0 +1,95 @@
+//! Implements the sourcing of the cards dealt by a state.
+//!
+//! By default a state deals from its own shuffled deck. A [`DeckSource`] lets the cards come
+//! from elsewhere instead, such as a fixed script or an external (e.g. mental poker) service, so
+//! that the randomness can live outside the game process. The state stays authoritative: every
+//! card supplied must still be in its deck.
+
+use std::collections::VecDeque;
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use crate::utilities::{shuffled, shuffled_with, Card};
+
+/// The reason a deck source cannot supply the cards to be dealt.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DeckError {
+    /// Too few cards are left to draw from.
+    Exhausted { count: usize, available: usize },
+    /// The source supplied a different number of cards than was drawn.
+    CardCount { count: usize, expected: usize },
+    /// The source is unavailable, e.g. an external service that cannot be reached.
+    Unavailable(String),
+}
+
+impl std::fmt::Display for DeckError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DeckError::Exhausted { count, available } => write!(f, "Only {} cards are left to draw {} from", available, count),
+            DeckError::CardCount { count, expected } => write!(f, "{} cards were supplied instead of {}", count, expected),
+            DeckError::Unavailable(error) => write!(f, "The deck source is unavailable: {}", error),
+        }
+    }
+}
+
+impl std::error::Error for DeckError {}
+
+impl From<DeckError> for String {
+    fn from(error: DeckError) -> Self { error.to_string() }
+}
+
+/// A source of the cards to be dealt.
+pub trait DeckSource: Send {
+    /// Draws the next cards to be dealt.
+    fn draw(&mut self, count: usize) -> Result<Vec<Card>, DeckError>;
+}
+
+/// Draws from a list of cards in order.
+fn draw_front(cards: &mut VecDeque<Card>, count: usize) -> Result<Vec<Card>, DeckError> {
+    if cards.len() < count {
+        return Err(DeckError::Exhausted { count, available: cards.len() });
+    }
+    Ok(cards.drain(..count).collect())
+}
+
+/// Deals from a locally shuffled deck.
+#[derive(Debug, Clone)]
+pub struct ShuffledDeck {
+    cards: VecDeque<Card>,
+}
+
+impl ShuffledDeck {
+    /// Shuffles the deck, reproducibly if a seed is given.
+    pub fn new(deck: &[Card], seed: Option<u64>) -> Self {
+        let cards = match seed {
+            Some(seed) => shuffled_with(deck, &mut StdRng::seed_from_u64(seed)),
+            None => shuffled(deck),
+        };
+        Self { cards: cards.into() }
+    }
+}
+
+impl DeckSource for ShuffledDeck {
+    fn draw(&mut self, count: usize) -> Result<Vec<Card>, DeckError> {
+        draw_front(&mut self.cards, count)
+    }
+}
+
+/// Deals a fixed script of cards, e.g. to reproduce a hand or test a scenario.
+#[derive(Debug, Clone)]
+pub struct ScriptedDeck {
+    cards: VecDeque<Card>,
+}
+
+impl ScriptedDeck {
+    pub fn new(cards: Vec<Card>) -> Self {
+        Self { cards: cards.into() }
+    }
+}
+
+impl DeckSource for ScriptedDeck {
+    fn draw(&mut self, count: usize) -> Result<Vec<Card>, DeckError> {
+        draw_front(&mut self.cards, count)
+    }
+}
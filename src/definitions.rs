@@ -0,0 +1,223 @@
+//! Implements declarative game definitions loaded from TOML or JSON.
+//!
+//! A definition mirrors the options of [`StateBuilder`], so new variants can be described in a
+//! configuration file instead of code. Raw values (antes, blinds or straddles, and starting
+//! stacks) are given as lists indexed by player. For example:
+//!
+//! ```toml
+//! deck = "standard"
+//! hand_types = ["StandardHighHand"]
+//! betting_structure = "NoLimit"
+//! blinds_or_straddles = [1, 2]
+//! starting_stacks = [200, 200, 200]
+//! player_count = 3
+//! automations = ["AntePosting", "BetCollection", "BlindOrStraddlePosting"]
+//!
+//! [[streets]]
+//! hole_dealing_statuses = [false, false]
+//! min_completion_betting_or_raising_amount = 2
+//!
+//! [[streets]]
+//! card_burning_status = true
+//! board_dealing_count = 3
+//! min_completion_betting_or_raising_amount = 2
+//! ```
+
+use serde::{Deserialize, Serialize};
+
+use crate::hands::HandType;
+use crate::state::{AntePlacement, Automation, BettingCap, BettingStructure, BuildError, HeadsUpConvention, JackpotDrop, Mode, OddChipRule, Opening, State, StateBuilder, Street};
+use crate::utilities::{Amount, Card, Deck};
+
+fn default_opening() -> Opening { Opening::Position }
+
+/// The reason a definition cannot be loaded, saved, or turned into a state.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DefinitionError {
+    /// The definition file could not be read.
+    Io(String),
+    /// The definition could not be parsed or written in its format.
+    Format(String),
+    /// The cards of the deck could not be parsed.
+    InvalidDeck(String),
+    /// The state could not be built as defined.
+    Build(BuildError),
+}
+
+impl std::fmt::Display for DefinitionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DefinitionError::Io(error) | DefinitionError::Format(error) | DefinitionError::InvalidDeck(error) => write!(f, "{}", error),
+            DefinitionError::Build(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl std::error::Error for DefinitionError {}
+
+impl From<BuildError> for DefinitionError {
+    fn from(error: BuildError) -> Self { DefinitionError::Build(error) }
+}
+
+impl From<DefinitionError> for String {
+    fn from(error: DefinitionError) -> Self { error.to_string() }
+}
+
+/// The definition of a street, mirroring [`Street::new`].
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StreetDefinition {
+    #[serde(default)]
+    pub card_burning_status: bool,
+    #[serde(default)]
+    pub hole_dealing_statuses: Vec<bool>,
+    #[serde(default)]
+    pub board_dealing_count: usize,
+    #[serde(default)]
+    pub draw_status: bool,
+    #[serde(default = "default_opening")]
+    pub opening: Opening,
+    pub min_completion_betting_or_raising_amount: Amount,
+    #[serde(default)]
+    pub max_completion_betting_or_raising_count: Option<usize>,
+}
+
+impl StreetDefinition {
+    pub fn to_street(&self) -> Result<Street, BuildError> {
+        Street::new(
+            self.card_burning_status,
+            self.hole_dealing_statuses.clone(),
+            self.board_dealing_count,
+            self.draw_status,
+            self.opening,
+            self.min_completion_betting_or_raising_amount,
+            self.max_completion_betting_or_raising_count,
+        )
+    }
+}
+
+fn default_deck() -> String { "standard".to_string() }
+fn default_starting_board_count() -> usize { 1 }
+fn default_mode() -> Mode { Mode::CashGame }
+
+/// The definition of a game, mirroring [`StateBuilder`].
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GameDefinition {
+    /// Either `standard`, `short_deck_holdem`, or the cards themselves (e.g. `AsKsQsJsTs`).
+    #[serde(default = "default_deck")]
+    pub deck: String,
+    pub hand_types: Vec<HandType>,
+    pub streets: Vec<StreetDefinition>,
+    pub betting_structure: BettingStructure,
+    #[serde(default)]
+    pub ante_trimming_status: bool,
+    #[serde(default)]
+    pub antes: Vec<Amount>,
+    #[serde(default)]
+    pub ante_placement: AntePlacement,
+    #[serde(default)]
+    pub blinds_or_straddles: Vec<Amount>,
+    #[serde(default)]
+    pub bring_in: Amount,
+    pub starting_stacks: Vec<Amount>,
+    pub player_count: usize,
+    #[serde(default = "default_mode")]
+    pub mode: Mode,
+    #[serde(default)]
+    pub heads_up_convention: HeadsUpConvention,
+    /// The number of boards dealt, e.g. 2 for double-board games.
+    #[serde(default = "default_starting_board_count")]
+    pub starting_board_count: usize,
+    #[serde(default)]
+    pub automations: Vec<Automation>,
+    #[serde(default)]
+    pub odd_chip_rule: OddChipRule,
+    #[serde(default)]
+    pub betting_cap: Option<BettingCap>,
+    #[serde(default)]
+    pub jackpot_drop: Option<JackpotDrop>,
+    /// The smallest amount of chips, defaulting to that of the builder.
+    #[serde(default)]
+    pub chip_unit: Option<Amount>,
+    #[serde(default)]
+    pub seed: Option<u64>,
+}
+
+impl GameDefinition {
+    pub fn from_toml(text: &str) -> Result<Self, DefinitionError> {
+        toml::from_str(text).map_err(|e| DefinitionError::Format(e.to_string()))
+    }
+
+    pub fn from_json(text: &str) -> Result<Self, DefinitionError> {
+        serde_json::from_str(text).map_err(|e| DefinitionError::Format(e.to_string()))
+    }
+
+    pub fn to_toml(&self) -> Result<String, DefinitionError> {
+        toml::to_string(self).map_err(|e| DefinitionError::Format(e.to_string()))
+    }
+
+    pub fn to_json(&self) -> Result<String, DefinitionError> {
+        serde_json::to_string_pretty(self).map_err(|e| DefinitionError::Format(e.to_string()))
+    }
+
+    /// Loads a definition from a file, choosing the format by its extension.
+    pub fn load(path: &str) -> Result<Self, DefinitionError> {
+        let text = std::fs::read_to_string(path).map_err(|e| DefinitionError::Io(e.to_string()))?;
+        if path.ends_with(".json") { Self::from_json(&text) } else { Self::from_toml(&text) }
+    }
+
+    fn deck_cards(&self) -> Result<Vec<Card>, DefinitionError> {
+        match self.deck.as_str() {
+            "standard" => Ok(Deck::standard()),
+            "short_deck_holdem" => Ok(Deck::short_deck_holdem()),
+            cards => Card::parse_cards(cards).map_err(DefinitionError::InvalidDeck),
+        }
+    }
+
+    /// Returns a builder configured as defined.
+    pub fn to_builder(&self) -> Result<StateBuilder, DefinitionError> {
+        let streets = self.streets.iter().map(StreetDefinition::to_street).collect::<Result<Vec<_>, _>>()?;
+        let builder = StateBuilder::new(self.player_count)
+            .automations(&self.automations)
+            .deck(self.deck_cards()?)
+            .hand_types(self.hand_types.clone())
+            .streets(streets)
+            .betting_structure(self.betting_structure)
+            .ante_trimming_status(self.ante_trimming_status)
+            .raw_antes(self.antes.as_slice())
+            .ante_placement(self.ante_placement)
+            .raw_blinds_or_straddles(self.blinds_or_straddles.as_slice())
+            .bring_in(self.bring_in)
+            .raw_starting_stacks(self.starting_stacks.as_slice())
+            .mode(self.mode)
+            .heads_up_convention(self.heads_up_convention)
+            .starting_board_count(self.starting_board_count)
+            .odd_chip_rule(self.odd_chip_rule);
+
+        let builder = match self.betting_cap {
+            Some(betting_cap) => builder.betting_cap(betting_cap),
+            None => builder,
+        };
+
+        let builder = match self.jackpot_drop {
+            Some(JackpotDrop { threshold, amount }) => builder.jackpot_drop(threshold, amount),
+            None => builder,
+        };
+
+        let builder = match self.chip_unit {
+            Some(chip_unit) => builder.chip_unit(chip_unit),
+            None => builder,
+        };
+
+        Ok(match self.seed {
+            Some(seed) => builder.seed(seed),
+            None => builder,
+        })
+    }
+
+    /// Creates a state as defined.
+    pub fn create_state(&self) -> Result<State, DefinitionError> {
+        Ok(self.to_builder()?.build()?)
+    }
+}
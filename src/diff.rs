@@ -0,0 +1,92 @@
+//! Implements the diffing of state snapshots.
+//!
+//! A [`StateDiff`] holds only what changed between two snapshots of the same hand, so a server
+//! can send it in place of the whole state and a debugger can see exactly what an automated
+//! transition did.
+
+use std::collections::BTreeMap;
+
+use num_traits::Zero;
+
+use crate::state::{Operation, State};
+use crate::utilities::{Amount, Card};
+
+/// The changes between two snapshots of a state.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StateDiff {
+    /// The change in the stack of each player whose stack changed.
+    pub stack_changes: BTreeMap<usize, Amount>,
+    /// The change in the bet of each player whose bet changed.
+    pub bet_changes: BTreeMap<usize, Amount>,
+    /// The new status of each player whose status flipped.
+    pub status_changes: BTreeMap<usize, bool>,
+    /// The hole cards newly dealt to each player.
+    pub hole_cards: BTreeMap<usize, Vec<Card>>,
+    /// The cards newly dealt to each board.
+    pub board_cards: BTreeMap<usize, Vec<Card>>,
+    /// The cards newly mucked.
+    pub mucked_cards: Vec<Card>,
+    /// The cards newly burned.
+    pub burn_cards: Vec<Card>,
+    /// The new street index, if the street changed.
+    pub street_index: Option<Option<usize>>,
+    /// The new status of the state, if it changed.
+    pub status: Option<bool>,
+    /// The operations performed since the last operation the snapshots share.
+    pub operations: Vec<Operation>,
+}
+
+impl StateDiff {
+    /// Returns whether nothing changed between the snapshots.
+    pub fn is_empty(&self) -> bool {
+        *self == StateDiff::default()
+    }
+}
+
+fn new_cards(old: &[Card], new: &[Card]) -> Vec<Card> {
+    new.iter().filter(|card| !old.contains(card)).cloned().collect()
+}
+
+fn changes<T: Copy, U>(old: &[T], new: &[T], change: impl Fn(T, T) -> Option<U>) -> BTreeMap<usize, U> {
+    old.iter()
+        .zip(new)
+        .enumerate()
+        .filter_map(|(i, (&old, &new))| change(old, new).map(|value| (i, value)))
+        .collect()
+}
+
+impl State {
+    /// Returns the changes that turn this state into the other.
+    ///
+    /// The other state is expected to be a later snapshot of the same hand.
+    pub fn diff(&self, other: &State) -> StateDiff {
+        let delta = |old: Amount, new: Amount| Some(new - old).filter(|&delta| delta != Amount::zero());
+        let shared_operation_count = self.operations.iter()
+            .zip(other.operations.iter())
+            .take_while(|(old, new)| old == new)
+            .count();
+
+        StateDiff {
+            stack_changes: changes(&self.stacks, &other.stacks, delta),
+            bet_changes: changes(&self.bets, &other.bets, delta),
+            status_changes: changes(&self.statuses, &other.statuses, |old, new| Some(new).filter(|&new| new != old)),
+            hole_cards: self.hole_cards.iter()
+                .zip(&other.hole_cards)
+                .map(|(old, new)| new_cards(old, new))
+                .enumerate()
+                .filter(|(_, cards)| !cards.is_empty())
+                .collect(),
+            board_cards: self.board_cards.iter()
+                .zip(&other.board_cards)
+                .map(|(old, new)| new_cards(old, new))
+                .enumerate()
+                .filter(|(_, cards)| !cards.is_empty())
+                .collect(),
+            mucked_cards: new_cards(&self.mucked_cards, &other.mucked_cards),
+            burn_cards: new_cards(&self.burn_cards, &other.burn_cards),
+            street_index: Some(other.street_index).filter(|&street_index| street_index != self.street_index),
+            status: Some(other.status).filter(|&status| status != self.status),
+            operations: other.operations[shared_operation_count..].to_vec(),
+        }
+    }
+}
@@ -0,0 +1,311 @@
+//! Implements a compact binary encoding of operation logs.
+//!
+//! Each operation is a tag byte followed by its fields. Indices and amounts are LEB128 varints
+//! (amounts zigzag-encoded), cards are single bytes, and commentary is only written when present,
+//! so a typical action takes two or three bytes. Hands are written back to back, each ended by a
+//! terminator tag, which lets an [`Encoder`] and a [`Decoder`] stream arbitrarily many hands.
+//!
+//! With the `decimal` feature, an amount is written as its scale followed by its zigzag-encoded
+//! mantissa.
+
+use std::io::{ErrorKind, Read, Write};
+
+use crate::state::*;
+use crate::utilities::{Amount, Card, Rank, Suit};
+
+const RANKS: [Rank; 14] = [
+    Rank::Ace, Rank::Deuce, Rank::Trey, Rank::Four, Rank::Five, Rank::Six, Rank::Seven,
+    Rank::Eight, Rank::Nine, Rank::Ten, Rank::Jack, Rank::Queen, Rank::King, Rank::Unknown,
+];
+const SUITS: [Suit; 5] = [Suit::Club, Suit::Diamond, Suit::Heart, Suit::Spade, Suit::Unknown];
+
+const COMMENTARY_FLAG: u8 = 0x80;
+const END_OF_HAND: u8 = 0x7F;
+/// The most hole card statuses a single dealing can carry, as they are packed into 64 bits.
+const MAX_STATUS_COUNT: usize = 64;
+
+/// Writes hands of operations to a byte stream.
+pub struct Encoder<W: Write> {
+    writer: W,
+    buffer: Vec<u8>,
+}
+
+impl<W: Write> Encoder<W> {
+    pub fn new(writer: W) -> Self {
+        Self { writer, buffer: Vec::new() }
+    }
+
+    /// Writes the operations of a hand, followed by the end-of-hand terminator.
+    pub fn write_hand(&mut self, operations: &[Operation]) -> Result<(), String> {
+        self.buffer.clear();
+        for operation in operations {
+            encode_operation(&mut self.buffer, operation)?;
+        }
+        self.buffer.push(END_OF_HAND);
+        self.writer.write_all(&self.buffer).map_err(|e| e.to_string())
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+/// Reads hands of operations from a byte stream.
+pub struct Decoder<R: Read> {
+    reader: R,
+}
+
+impl<R: Read> Decoder<R> {
+    pub fn new(reader: R) -> Self {
+        Self { reader }
+    }
+
+    /// Reads the operations of the next hand, or `None` at the end of the stream.
+    pub fn read_hand(&mut self) -> Result<Option<Vec<Operation>>, String> {
+        let mut operations = Vec::new();
+        loop {
+            let mut tag = [0];
+            match self.reader.read_exact(&mut tag) {
+                Err(e) if e.kind() == ErrorKind::UnexpectedEof && operations.is_empty() => return Ok(None),
+                result => result.map_err(|e| e.to_string())?,
+            }
+            if tag[0] == END_OF_HAND {
+                return Ok(Some(operations));
+            }
+            operations.push(decode_operation(&mut self.reader, tag[0])?);
+        }
+    }
+}
+
+impl<R: Read> Iterator for Decoder<R> {
+    type Item = Result<Vec<Operation>, String>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.read_hand().transpose()
+    }
+}
+
+/// Encodes the operations of a single hand.
+pub fn encode(operations: &[Operation]) -> Result<Vec<u8>, String> {
+    let mut encoder = Encoder::new(Vec::new());
+    encoder.write_hand(operations)?;
+    Ok(encoder.into_inner())
+}
+
+/// Decodes the operations of a single hand.
+pub fn decode(bytes: &[u8]) -> Result<Vec<Operation>, String> {
+    Decoder::new(bytes).read_hand()?.ok_or_else(|| "The encoding is empty".to_string())
+}
+
+fn put_unsigned(buffer: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        buffer.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+    buffer.push(value as u8);
+}
+
+fn put_index(buffer: &mut Vec<u8>, index: usize) { put_unsigned(buffer, index as u64); }
+fn put_optional_index(buffer: &mut Vec<u8>, index: Option<usize>) { put_unsigned(buffer, index.map_or(0, |i| i as u64 + 1)); }
+#[cfg(not(feature = "decimal"))]
+fn put_amount(buffer: &mut Vec<u8>, amount: Amount) { put_unsigned(buffer, ((amount << 1) ^ (amount >> 63)) as u64); }
+
+#[cfg(feature = "decimal")]
+fn put_amount(buffer: &mut Vec<u8>, amount: Amount) {
+    put_unsigned(buffer, amount.scale() as u64);
+    let mantissa = amount.mantissa();
+    let mut value = ((mantissa << 1) ^ (mantissa >> 127)) as u128;
+    while value >= 0x80 {
+        buffer.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+    buffer.push(value as u8);
+}
+
+fn put_amounts(buffer: &mut Vec<u8>, amounts: &[Amount]) {
+    put_index(buffer, amounts.len());
+    amounts.iter().for_each(|&amount| put_amount(buffer, amount));
+}
+
+fn put_card(buffer: &mut Vec<u8>, card: Card) { buffer.push(card.rank as u8 * SUITS.len() as u8 + card.suit as u8); }
+
+fn put_cards(buffer: &mut Vec<u8>, cards: &[Card]) {
+    put_index(buffer, cards.len());
+    cards.iter().for_each(|&card| put_card(buffer, card));
+}
+
+fn put_statuses(buffer: &mut Vec<u8>, statuses: &[bool]) -> Result<(), String> {
+    if statuses.len() > MAX_STATUS_COUNT {
+        return Err(format!("{} hole card statuses exceed the maximum of {}", statuses.len(), MAX_STATUS_COUNT));
+    }
+    put_unsigned(buffer, statuses.iter().rev().fold(0, |bits, &status| bits << 1 | status as u64));
+    Ok(())
+}
+
+fn encode_operation(buffer: &mut Vec<u8>, operation: &Operation) -> Result<(), String> {
+    let (tag, commentary) = match operation {
+        Operation::AntePosting(op) => (0, &op.commentary),
+        Operation::BetCollection(op) => (1, &op.commentary),
+        Operation::BlindOrStraddlePosting(op) => (2, &op.commentary),
+        Operation::CardBurning(op) => (3, &op.commentary),
+        Operation::HoleDealing(op) => (4, &op.commentary),
+        Operation::BoardDealing(op) => (5, &op.commentary),
+        Operation::StandingPatOrDiscarding(op) => (6, &op.commentary),
+        Operation::Folding(op) => (7, &op.commentary),
+        Operation::CheckingOrCalling(op) => (8, &op.commentary),
+        Operation::BringInPosting(op) => (9, &op.commentary),
+        Operation::CompletionBettingOrRaisingTo(op) => (10, &op.commentary),
+        Operation::RunoutCountSelection(op) => (11, &op.commentary),
+        Operation::HoleCardsShowingOrMucking(op) => (12, &op.commentary),
+        Operation::HandKilling(op) => (13, &op.commentary),
+        Operation::ChipsPushing(op) => (14, &op.commentary),
+        Operation::ChipsPulling(op) => (15, &op.commentary),
+        Operation::NoOperation(op) => (16, &op.commentary),
+    };
+    buffer.push(if commentary.is_some() { tag | COMMENTARY_FLAG } else { tag });
+
+    match operation {
+        Operation::AntePosting(op) => { put_index(buffer, op.player_index); put_amount(buffer, op.amount); }
+        Operation::BetCollection(op) => put_amounts(buffer, &op.bets),
+        Operation::BlindOrStraddlePosting(op) => { put_index(buffer, op.player_index); put_amount(buffer, op.amount); }
+        Operation::CardBurning(op) => put_card(buffer, op.card),
+        Operation::HoleDealing(op) => { put_index(buffer, op.player_index); put_cards(buffer, &op.cards); put_statuses(buffer, &op.statuses)?; }
+        Operation::BoardDealing(op) => put_cards(buffer, &op.cards),
+        Operation::StandingPatOrDiscarding(op) => { put_index(buffer, op.player_index); put_cards(buffer, &op.cards); }
+        Operation::Folding(op) => put_index(buffer, op.player_index),
+        Operation::CheckingOrCalling(op) => { put_index(buffer, op.player_index); put_amount(buffer, op.amount); }
+        Operation::BringInPosting(op) => { put_index(buffer, op.player_index); put_amount(buffer, op.amount); }
+        Operation::CompletionBettingOrRaisingTo(op) => { put_index(buffer, op.player_index); put_amount(buffer, op.amount); }
+        Operation::RunoutCountSelection(op) => { put_index(buffer, op.player_index); put_optional_index(buffer, op.runout_count); }
+        Operation::HoleCardsShowingOrMucking(op) => { put_index(buffer, op.player_index); put_cards(buffer, &op.hole_cards); }
+        Operation::HandKilling(op) => put_index(buffer, op.player_index),
+        Operation::ChipsPushing(op) => {
+            put_amounts(buffer, &op.amounts);
+            put_index(buffer, op.pot_index);
+            put_optional_index(buffer, op.board_index);
+            put_optional_index(buffer, op.hand_type_index);
+            put_amount(buffer, op.raked_amount);
+            put_amount(buffer, op.dropped_amount);
+        }
+        Operation::ChipsPulling(op) => { put_index(buffer, op.player_index); put_amount(buffer, op.amount); }
+        Operation::NoOperation(_) => {}
+    }
+
+    if let Some(commentary) = commentary {
+        put_index(buffer, commentary.len());
+        buffer.extend(commentary.as_bytes());
+    }
+    Ok(())
+}
+
+fn get_byte(reader: &mut impl Read) -> Result<u8, String> {
+    let mut byte = [0];
+    reader.read_exact(&mut byte).map_err(|e| e.to_string())?;
+    Ok(byte[0])
+}
+
+fn get_unsigned(reader: &mut impl Read) -> Result<u64, String> {
+    let mut value = 0;
+    for shift in (0..64).step_by(7) {
+        let byte = get_byte(reader)?;
+        value |= ((byte & 0x7F) as u64) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+    Err("The varint is too long".to_string())
+}
+
+fn get_index(reader: &mut impl Read) -> Result<usize, String> { Ok(get_unsigned(reader)? as usize) }
+fn get_optional_index(reader: &mut impl Read) -> Result<Option<usize>, String> { Ok(get_unsigned(reader)?.checked_sub(1).map(|i| i as usize)) }
+
+#[cfg(not(feature = "decimal"))]
+fn get_amount(reader: &mut impl Read) -> Result<Amount, String> {
+    let value = get_unsigned(reader)?;
+    Ok((value >> 1) as i64 ^ -((value & 1) as i64))
+}
+
+#[cfg(feature = "decimal")]
+fn get_amount(reader: &mut impl Read) -> Result<Amount, String> {
+    let scale = get_unsigned(reader)? as u32;
+    let mut value: u128 = 0;
+    for shift in (0..128).step_by(7) {
+        let byte = get_byte(reader)?;
+        value |= ((byte & 0x7F) as u128) << shift;
+        if byte & 0x80 == 0 {
+            let mantissa = (value >> 1) as i128 ^ -((value & 1) as i128);
+            return Amount::try_from_i128_with_scale(mantissa, scale).map_err(|e| e.to_string());
+        }
+    }
+    Err("The varint is too long".to_string())
+}
+
+fn get_amounts(reader: &mut impl Read) -> Result<Vec<Amount>, String> {
+    (0..get_index(reader)?).map(|_| get_amount(reader)).collect()
+}
+
+fn get_card(reader: &mut impl Read) -> Result<Card, String> {
+    let byte = get_byte(reader)? as usize;
+    let rank = RANKS.get(byte / SUITS.len()).ok_or_else(|| format!("Invalid card byte: {}", byte))?;
+    Ok(Card::new(*rank, SUITS[byte % SUITS.len()]))
+}
+
+fn get_cards(reader: &mut impl Read) -> Result<Vec<Card>, String> {
+    (0..get_index(reader)?).map(|_| get_card(reader)).collect()
+}
+
+fn get_statuses(reader: &mut impl Read, count: usize) -> Result<Vec<bool>, String> {
+    if count > MAX_STATUS_COUNT {
+        return Err(format!("{} hole card statuses exceed the maximum of {}", count, MAX_STATUS_COUNT));
+    }
+    let bits = get_unsigned(reader)?;
+    Ok((0..count).map(|i| bits >> i & 1 == 1).collect())
+}
+
+fn decode_operation(reader: &mut impl Read, tag: u8) -> Result<Operation, String> {
+    let mut operation = match tag & !COMMENTARY_FLAG {
+        0 => Operation::AntePosting(AntePosting { player_index: get_index(reader)?, amount: get_amount(reader)?, commentary: None }),
+        1 => Operation::BetCollection(BetCollection { bets: get_amounts(reader)?, commentary: None }),
+        2 => Operation::BlindOrStraddlePosting(BlindOrStraddlePosting { player_index: get_index(reader)?, amount: get_amount(reader)?, commentary: None }),
+        3 => Operation::CardBurning(CardBurning { card: get_card(reader)?, commentary: None }),
+        4 => {
+            let player_index = get_index(reader)?;
+            let cards = get_cards(reader)?;
+            let statuses = get_statuses(reader, cards.len())?;
+            Operation::HoleDealing(HoleDealing { player_index, cards, statuses, commentary: None })
+        }
+        5 => Operation::BoardDealing(BoardDealing { cards: get_cards(reader)?, commentary: None }),
+        6 => Operation::StandingPatOrDiscarding(StandingPatOrDiscarding { player_index: get_index(reader)?, cards: get_cards(reader)?, commentary: None }),
+        7 => Operation::Folding(Folding { player_index: get_index(reader)?, commentary: None }),
+        8 => Operation::CheckingOrCalling(CheckingOrCalling { player_index: get_index(reader)?, amount: get_amount(reader)?, commentary: None }),
+        9 => Operation::BringInPosting(BringInPosting { player_index: get_index(reader)?, amount: get_amount(reader)?, commentary: None }),
+        10 => Operation::CompletionBettingOrRaisingTo(CompletionBettingOrRaisingTo { player_index: get_index(reader)?, amount: get_amount(reader)?, commentary: None }),
+        11 => Operation::RunoutCountSelection(RunoutCountSelection { player_index: get_index(reader)?, runout_count: get_optional_index(reader)?, commentary: None }),
+        12 => Operation::HoleCardsShowingOrMucking(HoleCardsShowingOrMucking { player_index: get_index(reader)?, hole_cards: get_cards(reader)?, commentary: None }),
+        13 => Operation::HandKilling(HandKilling { player_index: get_index(reader)?, commentary: None }),
+        14 => Operation::ChipsPushing(ChipsPushing {
+            amounts: get_amounts(reader)?,
+            pot_index: get_index(reader)?,
+            board_index: get_optional_index(reader)?,
+            hand_type_index: get_optional_index(reader)?,
+            raked_amount: get_amount(reader)?,
+            dropped_amount: get_amount(reader)?,
+            commentary: None,
+        }),
+        15 => Operation::ChipsPulling(ChipsPulling { player_index: get_index(reader)?, amount: get_amount(reader)?, commentary: None }),
+        16 => Operation::NoOperation(NoOperation { commentary: None }),
+        _ => return Err(format!("Invalid operation tag: {}", tag)),
+    };
+    if tag & COMMENTARY_FLAG == 0 {
+        return Ok(operation);
+    }
+
+    let length = get_unsigned(reader)?;
+    let mut bytes = Vec::new();
+    reader.take(length).read_to_end(&mut bytes).map_err(|e| e.to_string())?;
+    if bytes.len() as u64 != length {
+        return Err(format!("The commentary is {} bytes long instead of {}", bytes.len(), length));
+    }
+    *operation.commentary_mut() = Some(String::from_utf8(bytes).map_err(|e| e.to_string())?);
+    Ok(operation)
+}
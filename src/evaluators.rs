@@ -0,0 +1,167 @@
+//! Implements fast hand evaluators.
+//!
+//! The lookups identify a hand by its exact cards, so finding the best hand among seven cards
+//! costs 21 lookups. The evaluators here pick the best five cards directly from rank and suit
+//! masks and then consult the lookup only once, yielding identical entries.
+//!
+//! New evaluation backends can be checked against a reference with [`differential_test`], which
+//! ranks random pairs of hands with both and reports every pair they order differently.
+
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+use itertools::Itertools;
+use num_traits::ToPrimitive;
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+use crate::lookups::{rank_multiplier, Entry, Lookup};
+use crate::utilities::{Card, RankOrder, Suit};
+
+const SUITS: [Suit; 4] = [Suit::Club, Suit::Diamond, Suit::Heart, Suit::Spade];
+
+/// An evaluator for standard high hands of five to seven cards.
+pub struct StandardEvaluator {
+    entries: HashMap<(u64, bool), Entry>,
+}
+
+impl StandardEvaluator {
+    /// Creates the evaluator from the entries of a lookup with the standard rank order.
+    pub fn new(lookup: &dyn Lookup) -> Self {
+        let entries = lookup.entries()
+            .iter()
+            .filter_map(|((hash, suitedness), entry)| Some(((hash.to_u64()?, *suitedness), *entry)))
+            .collect();
+        Self { entries }
+    }
+
+    /// Returns the entry of the best hand that can be formed from the cards, or `None` if there
+    /// are fewer than five or more than seven cards.
+    pub fn evaluate(&self, cards: &[Card]) -> Option<Entry> {
+        if !(5..=7).contains(&cards.len()) {
+            return None;
+        }
+
+        let mut counts = [0u8; 13];
+        let mut suit_masks = [0u16; 4];
+        for card in cards {
+            let rank = RankOrder::STANDARD.iter().position(|&r| r == card.rank)?;
+            let suit = SUITS.iter().position(|&s| s == card.suit)?;
+            counts[rank] += 1;
+            suit_masks[suit] |= 1 << rank;
+        }
+        let rank_mask = suit_masks.iter().fold(0, |mask, suit_mask| mask | suit_mask);
+        let flush_mask = suit_masks.iter().find(|mask| mask.count_ones() >= 5).cloned();
+        let full_house = counts.iter().any(|&count| count >= 3)
+            && counts.iter().filter(|&&count| count >= 2).count() >= 2;
+
+        let (ranks, suitedness) = match (flush_mask, flush_mask.and_then(straight)) {
+            (_, Some(top)) => (straight_ranks(top), true),
+            _ if counts.iter().any(|&count| count >= 4) || full_house => (grouped_ranks(&counts), false),
+            (Some(flush_mask), None) => ((0..13).rev().filter(|&i| flush_mask >> i & 1 == 1).take(5).collect(), true),
+            (None, None) => match straight(rank_mask) {
+                Some(top) => (straight_ranks(top), false),
+                None => (grouped_ranks(&counts), false),
+            },
+        };
+
+        let hash = ranks.iter().map(|&i| rank_multiplier(RankOrder::STANDARD[i])).product::<u64>();
+        self.entries.get(&(hash, suitedness)).cloned()
+    }
+}
+
+/// Returns the entry of the best five-card hand among the cards by looking up every combination,
+/// serving as the reference for the evaluators.
+pub fn evaluate_by_lookup(lookup: &dyn Lookup, cards: &[Card]) -> Option<Entry> {
+    cards.iter().cloned().combinations(5).filter_map(|cards| lookup.get_entry_from_cards(&cards)).max()
+}
+
+/// A pair of hands ranked differently by two evaluators.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Disagreement {
+    pub hands: [Vec<Card>; 2],
+    /// The ordering of the first hand relative to the second, according to the reference.
+    pub expected: Ordering,
+    /// The ordering of the first hand relative to the second, according to the candidate.
+    pub actual: Ordering,
+}
+
+/// The outcome of a differential test.
+#[derive(Debug, Clone, Default)]
+pub struct DifferentialReport {
+    pub comparison_count: usize,
+    pub disagreements: Vec<Disagreement>,
+}
+
+impl DifferentialReport {
+    pub fn is_consistent(&self) -> bool {
+        self.disagreements.is_empty()
+    }
+}
+
+/// Ranks random pairs of hands of the given size, drawn from the deck, with a reference and a
+/// candidate evaluator and reports the pairs they disagree on.
+///
+/// Only the relative order of the hands matters, so evaluators with different strength scales
+/// can be compared. A hand that cannot be evaluated ranks below every other hand.
+pub fn differential_test<A: Ord, B: Ord, R: Rng + ?Sized>(
+    reference: impl Fn(&[Card]) -> Option<A>,
+    candidate: impl Fn(&[Card]) -> Option<B>,
+    deck: &[Card],
+    card_count: usize,
+    comparison_count: usize,
+    rng: &mut R,
+) -> DifferentialReport {
+    let mut report = DifferentialReport { comparison_count, disagreements: Vec::new() };
+
+    for _ in 0..comparison_count {
+        let hands: [Vec<Card>; 2] = [
+            deck.choose_multiple(rng, card_count).cloned().collect(),
+            deck.choose_multiple(rng, card_count).cloned().collect(),
+        ];
+        let expected = reference(&hands[0]).cmp(&reference(&hands[1]));
+        let actual = candidate(&hands[0]).cmp(&candidate(&hands[1]));
+
+        if expected != actual {
+            report.disagreements.push(Disagreement { hands, expected, actual });
+        }
+    }
+
+    report
+}
+
+/// Returns the index of the top rank of the highest five-card straight in the rank mask.
+fn straight(mask: u16) -> Option<usize> {
+    let mask = (mask << 1) | (mask >> 12 & 1); // The ace also plays low.
+    (4..=13).rev().find(|&top| (mask >> (top - 4)) & 0b11111 == 0b11111).map(|top| top - 1)
+}
+
+/// Returns the indices of the ranks of the straight with the given top rank.
+fn straight_ranks(top: usize) -> Vec<usize> {
+    (0..5).map(|offset| (top + 13 - offset) % 13).collect()
+}
+
+/// Picks the five ranks of the best hand made of rank groups (quads, full houses, trips, pairs,
+/// and high cards), relying on the multiplicities of the ranks alone.
+fn grouped_ranks(counts: &[u8; 13]) -> Vec<usize> {
+    let mut remaining = *counts;
+    let mut ranks = Vec::with_capacity(5);
+
+    for group in [4, 3, 2, 2] {
+        if ranks.len() + group > 5 {
+            continue;
+        }
+        if let Some(i) = (0..13).rev().find(|&i| remaining[i] as usize >= group) {
+            ranks.extend(std::iter::repeat_n(i, group));
+            remaining[i] = 0;
+        }
+    }
+    for i in (0..13).rev() {
+        while ranks.len() < 5 && remaining[i] > 0 {
+            ranks.push(i);
+            remaining[i] -= 1;
+        }
+    }
+
+    ranks
+}
@@ -0,0 +1,272 @@
+//! Implements the export of operation logs to flat, tabular formats.
+//!
+//! Every operation becomes a row with the hand id, the street index, the acting player, the
+//! action name, the amount, and the cards involved. Operations that concern several players at
+//! once (bet collection and chips pushing) are expanded into one row per involved player, and the
+//! rake and jackpot drop taken from each pot are reported in rows of their own.
+//!
+//! For learning from engine output, every betting decision can also be exported as a row of
+//! features observed by the actor before acting, along with the action taken and the outcome of
+//! the hand for the actor. With the `parquet` feature, such rows are written to Parquet files.
+
+#[cfg(feature = "parquet")]
+pub mod parquet;
+
+use num_traits::Zero;
+
+use crate::analysis::stats::split_streets;
+use crate::state::{Operation, State};
+use crate::utilities::{to_amount, Amount, Card};
+
+/// The column names of the exported rows.
+pub const COLUMNS: [&str; 6] = ["hand_id", "street", "player", "action", "amount", "cards"];
+
+/// The column names of the exported decision rows.
+pub const DECISION_COLUMNS: [&str; 11] =
+    ["hand_id", "street", "player", "stack", "pot", "to_call", "hole_cards", "board_cards", "action", "amount", "payoff"];
+
+/// A single row of an exported operation log.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OperationRow {
+    pub hand_id: String,
+    /// The index of the street, as split by [`split_streets`].
+    pub street: usize,
+    pub player: Option<usize>,
+    pub action: &'static str,
+    pub amount: Option<Amount>,
+    /// The cards involved, concatenated (e.g. `AsKh`).
+    pub cards: String,
+}
+
+fn join_cards(cards: &[Card]) -> String {
+    cards.iter().map(|card| card.to_string()).collect()
+}
+
+/// Flattens the operations of a state into rows.
+pub fn operation_rows(hand_id: &str, state: &State) -> Vec<OperationRow> {
+    let mut rows = Vec::new();
+
+    for (street, operations) in split_streets(&state.operations).into_iter().enumerate() {
+        let mut push = |player: Option<usize>, action: &'static str, amount: Option<Amount>, cards: &[Card]| {
+            rows.push(OperationRow {
+                hand_id: hand_id.to_string(),
+                street,
+                player,
+                action,
+                amount,
+                cards: join_cards(cards),
+            });
+        };
+
+        for operation in operations {
+            match operation {
+                Operation::AntePosting(op) => push(Some(op.player_index), "ante_posting", Some(op.amount), &[]),
+                Operation::BetCollection(op) => {
+                    for (i, &bet) in op.bets.iter().enumerate().filter(|(_, &bet)| bet != Amount::zero()) {
+                        push(Some(i), "bet_collection", Some(bet), &[]);
+                    }
+                }
+                Operation::BlindOrStraddlePosting(op) => {
+                    push(Some(op.player_index), "blind_or_straddle_posting", Some(op.amount), &[])
+                }
+                Operation::CardBurning(op) => push(None, "card_burning", None, &[op.card]),
+                Operation::HoleDealing(op) => push(Some(op.player_index), "hole_dealing", None, &op.cards),
+                Operation::BoardDealing(op) => push(None, "board_dealing", None, &op.cards),
+                Operation::StandingPatOrDiscarding(op) => {
+                    push(Some(op.player_index), "standing_pat_or_discarding", None, &op.cards)
+                }
+                Operation::Folding(op) => push(Some(op.player_index), "folding", None, &[]),
+                Operation::CheckingOrCalling(op) => {
+                    push(Some(op.player_index), "checking_or_calling", Some(op.amount), &[])
+                }
+                Operation::BringInPosting(op) => push(Some(op.player_index), "bring_in_posting", Some(op.amount), &[]),
+                Operation::CompletionBettingOrRaisingTo(op) => {
+                    push(Some(op.player_index), "completion_betting_or_raising_to", Some(op.amount), &[])
+                }
+                Operation::RunoutCountSelection(op) => push(
+                    Some(op.player_index),
+                    "runout_count_selection",
+                    op.runout_count.map(to_amount),
+                    &[],
+                ),
+                Operation::HoleCardsShowingOrMucking(op) => {
+                    push(Some(op.player_index), "hole_cards_showing_or_mucking", None, &op.hole_cards)
+                }
+                Operation::HandKilling(op) => push(Some(op.player_index), "hand_killing", None, &[]),
+                Operation::ChipsPushing(op) => {
+                    for (i, &amount) in op.amounts.iter().enumerate().filter(|(_, &amount)| amount != Amount::zero()) {
+                        push(Some(i), "chips_pushing", Some(amount), &[]);
+                    }
+                    if op.raked_amount != Amount::zero() {
+                        push(None, "rake", Some(op.raked_amount), &[]);
+                    }
+                    if op.dropped_amount != Amount::zero() {
+                        push(None, "jackpot_drop", Some(op.dropped_amount), &[]);
+                    }
+                }
+                Operation::ChipsPulling(op) => push(Some(op.player_index), "chips_pulling", Some(op.amount), &[]),
+                Operation::NoOperation(_) => push(None, "no_operation", None, &[]),
+            }
+        }
+    }
+
+    rows
+}
+
+/// A single betting decision, as observed by the acting player.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecisionRow {
+    pub hand_id: String,
+    pub street: usize,
+    pub player: usize,
+    /// The stack of the player before acting.
+    pub stack: Amount,
+    /// The chips put in so far, including the uncollected bets.
+    pub pot: Amount,
+    /// The amount required to call.
+    pub to_call: Amount,
+    pub hole_cards: String,
+    pub board_cards: String,
+    pub action: &'static str,
+    pub amount: Option<Amount>,
+    /// The final payoff of the player in the hand.
+    pub payoff: Amount,
+}
+
+/// Extracts the betting decisions of a state from its operation log.
+pub fn decision_rows(hand_id: &str, state: &State) -> Vec<DecisionRow> {
+    let mut rows = Vec::new();
+    let mut stacks = state.starting_stacks.clone();
+    let mut bets = vec![Amount::zero(); state.player_count];
+    let mut pot = Amount::zero();
+    let mut hole_cards = vec![Vec::new(); state.player_count];
+    let mut board_cards = Vec::new();
+
+    for (street, operations) in split_streets(&state.operations).into_iter().enumerate() {
+        for operation in operations {
+            let (player, action, amount, committed) = match operation {
+                Operation::AntePosting(op) => (op.player_index, None, op.amount, op.amount),
+                Operation::BlindOrStraddlePosting(op) => (op.player_index, None, op.amount, op.amount),
+                Operation::BringInPosting(op) => (op.player_index, Some("bring_in_posting"), op.amount, op.amount),
+                Operation::Folding(op) => (op.player_index, Some("folding"), Amount::zero(), Amount::zero()),
+                Operation::CheckingOrCalling(op) => (op.player_index, Some("checking_or_calling"), op.amount, op.amount),
+                Operation::CompletionBettingOrRaisingTo(op) => (
+                    op.player_index,
+                    Some("completion_betting_or_raising_to"),
+                    op.amount,
+                    op.amount - bets[op.player_index],
+                ),
+                Operation::BetCollection(_) => {
+                    bets.iter_mut().for_each(|bet| *bet = Amount::zero());
+                    continue;
+                }
+                Operation::HoleDealing(op) => {
+                    hole_cards[op.player_index].extend(op.cards.iter().cloned());
+                    continue;
+                }
+                Operation::StandingPatOrDiscarding(op) => {
+                    hole_cards[op.player_index].retain(|card| !op.cards.contains(card));
+                    continue;
+                }
+                Operation::BoardDealing(op) => {
+                    board_cards.extend(op.cards.iter().cloned());
+                    continue;
+                }
+                _ => continue,
+            };
+
+            if let Some(action) = action {
+                rows.push(DecisionRow {
+                    hand_id: hand_id.to_string(),
+                    street,
+                    player,
+                    stack: stacks[player],
+                    pot,
+                    to_call: bets.iter().max().cloned().unwrap_or_default() - bets[player],
+                    hole_cards: join_cards(&hole_cards[player]),
+                    board_cards: join_cards(&board_cards),
+                    action,
+                    amount: if matches!(operation, Operation::Folding(_)) { None } else { Some(amount) },
+                    payoff: state.payoffs[player],
+                });
+            }
+
+            stacks[player] -= committed;
+            bets[player] += committed;
+            pot += committed;
+        }
+    }
+
+    rows
+}
+
+/// Quotes a field if it contains the delimiter, a quote, or a line break.
+pub(crate) fn escape_field(field: &str, delimiter: char) -> String {
+    if field.contains([delimiter, '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Writes the rows of several hands with a header row, separating fields by the delimiter.
+pub fn to_delimited<'a>(hands: impl IntoIterator<Item = (&'a str, &'a State)>, delimiter: char) -> String {
+    let separator = delimiter.to_string();
+    let mut text = COLUMNS.join(&separator);
+    text.push('\n');
+
+    for (hand_id, state) in hands {
+        for row in operation_rows(hand_id, state) {
+            let fields = [
+                escape_field(&row.hand_id, delimiter),
+                row.street.to_string(),
+                row.player.map(|player| player.to_string()).unwrap_or_default(),
+                row.action.to_string(),
+                row.amount.map(|amount| amount.to_string()).unwrap_or_default(),
+                row.cards,
+            ];
+            text.push_str(&fields.join(&separator));
+            text.push('\n');
+        }
+    }
+
+    text
+}
+
+/// Writes the rows of several hands as CSV.
+pub fn to_csv<'a>(hands: impl IntoIterator<Item = (&'a str, &'a State)>) -> String {
+    to_delimited(hands, ',')
+}
+
+/// Writes the rows of several hands as TSV.
+pub fn to_tsv<'a>(hands: impl IntoIterator<Item = (&'a str, &'a State)>) -> String {
+    to_delimited(hands, '\t')
+}
+
+/// Writes the decisions of several hands as CSV with a header row.
+pub fn decisions_to_csv<'a>(hands: impl IntoIterator<Item = (&'a str, &'a State)>) -> String {
+    let mut text = DECISION_COLUMNS.join(",");
+    text.push('\n');
+
+    for (hand_id, state) in hands {
+        for row in decision_rows(hand_id, state) {
+            let fields = [
+                escape_field(&row.hand_id, ','),
+                row.street.to_string(),
+                row.player.to_string(),
+                row.stack.to_string(),
+                row.pot.to_string(),
+                row.to_call.to_string(),
+                row.hole_cards,
+                row.board_cards,
+                row.action.to_string(),
+                row.amount.map(|amount| amount.to_string()).unwrap_or_default(),
+                row.payoff.to_string(),
+            ];
+            text.push_str(&fields.join(","));
+            text.push('\n');
+        }
+    }
+
+    text
+}
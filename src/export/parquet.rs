@@ -0,0 +1,97 @@
+//! Implements the export of betting decisions to Parquet files.
+//!
+//! Hands are converted to Arrow record batches of [`DecisionRow`]s and appended to a single
+//! Parquet file, so that arbitrarily many simulated hands can be written in bounded memory.
+//!
+//! Amounts are 64-bit integer columns, or 64-bit floating point columns with the `decimal`
+//! feature.
+
+use std::io::Write;
+use std::sync::Arc;
+
+use arrow_array::{ArrayRef, RecordBatch, StringArray, UInt64Array};
+use arrow_schema::{DataType, Field, Schema, SchemaRef};
+use parquet::arrow::ArrowWriter;
+
+use crate::export::{decision_rows, DecisionRow};
+use crate::state::State;
+use crate::utilities::Amount;
+
+#[cfg(not(feature = "decimal"))]
+const AMOUNT_TYPE: DataType = DataType::Int64;
+#[cfg(feature = "decimal")]
+const AMOUNT_TYPE: DataType = DataType::Float64;
+
+#[cfg(not(feature = "decimal"))]
+fn amount_array(amounts: impl Iterator<Item = Option<Amount>>) -> ArrayRef {
+    Arc::new(arrow_array::Int64Array::from_iter(amounts))
+}
+
+#[cfg(feature = "decimal")]
+fn amount_array(amounts: impl Iterator<Item = Option<Amount>>) -> ArrayRef {
+    use num_traits::ToPrimitive;
+
+    Arc::new(arrow_array::Float64Array::from_iter(amounts.map(|amount| amount.and_then(|amount| amount.to_f64()))))
+}
+
+/// Returns the Arrow schema of the decision rows.
+pub fn decision_schema() -> SchemaRef {
+    Arc::new(Schema::new(vec![
+        Field::new("hand_id", DataType::Utf8, false),
+        Field::new("street", DataType::UInt64, false),
+        Field::new("player", DataType::UInt64, false),
+        Field::new("stack", AMOUNT_TYPE, false),
+        Field::new("pot", AMOUNT_TYPE, false),
+        Field::new("to_call", AMOUNT_TYPE, false),
+        Field::new("hole_cards", DataType::Utf8, false),
+        Field::new("board_cards", DataType::Utf8, false),
+        Field::new("action", DataType::Utf8, false),
+        Field::new("amount", AMOUNT_TYPE, true),
+        Field::new("payoff", AMOUNT_TYPE, false),
+    ]))
+}
+
+/// Converts the decision rows to an Arrow record batch.
+pub fn decision_batch(rows: &[DecisionRow]) -> Result<RecordBatch, String> {
+    let columns: Vec<ArrayRef> = vec![
+        Arc::new(StringArray::from_iter_values(rows.iter().map(|row| row.hand_id.as_str()))),
+        Arc::new(UInt64Array::from_iter_values(rows.iter().map(|row| row.street as u64))),
+        Arc::new(UInt64Array::from_iter_values(rows.iter().map(|row| row.player as u64))),
+        amount_array(rows.iter().map(|row| Some(row.stack))),
+        amount_array(rows.iter().map(|row| Some(row.pot))),
+        amount_array(rows.iter().map(|row| Some(row.to_call))),
+        Arc::new(StringArray::from_iter_values(rows.iter().map(|row| row.hole_cards.as_str()))),
+        Arc::new(StringArray::from_iter_values(rows.iter().map(|row| row.board_cards.as_str()))),
+        Arc::new(StringArray::from_iter_values(rows.iter().map(|row| row.action))),
+        amount_array(rows.iter().map(|row| row.amount)),
+        amount_array(rows.iter().map(|row| Some(row.payoff))),
+    ];
+
+    RecordBatch::try_new(decision_schema(), columns).map_err(|e| e.to_string())
+}
+
+/// Writes batches of hands to a Parquet file.
+pub struct DecisionWriter<W: Write + Send> {
+    writer: ArrowWriter<W>,
+}
+
+impl<W: Write + Send> DecisionWriter<W> {
+    pub fn new(writer: W) -> Result<Self, String> {
+        let writer = ArrowWriter::try_new(writer, decision_schema(), None).map_err(|e| e.to_string())?;
+
+        Ok(Self { writer })
+    }
+
+    /// Appends the decisions of a batch of hands.
+    pub fn write_hands<'a>(&mut self, hands: impl IntoIterator<Item = (&'a str, &'a State)>) -> Result<(), String> {
+        let rows: Vec<DecisionRow> =
+            hands.into_iter().flat_map(|(hand_id, state)| decision_rows(hand_id, state)).collect();
+
+        self.writer.write(&decision_batch(&rows)?).map_err(|e| e.to_string())
+    }
+
+    /// Flushes the remaining rows and writes the file footer.
+    pub fn close(self) -> Result<W, String> {
+        self.writer.into_inner().map_err(|e| e.to_string())
+    }
+}
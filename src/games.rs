@@ -1,15 +1,13 @@
 //! Implements various poker game definitions, acting as factories for `State`.
 
-use std::collections::{BTreeMap, HashSet};
+use num_traits::Zero;
 
 use crate::hands::HandType;
 use crate::state::{
-    Automation, BettingStructure, Mode, Opening, State, StateBuilder, Street,
+    AntePlacement, Automation, BettingStructure, BuildError, Mode, OddChipRule, Opening, State,
+    StateBuilder, Street,
 };
-use crate::utilities::{div_mod, rake, Deck};
-
-// A helper type for raw values like antes, blinds, and stacks.
-type RawValues = BTreeMap<usize, i64>;
+use crate::utilities::{Amount, Deck, RawValues};
 
 // Each struct here represents a specific poker game variant.
 // They don't hold data themselves but provide a `create_state` method
@@ -18,17 +16,19 @@ type RawValues = BTreeMap<usize, i64>;
 pub struct FixedLimitTexasHoldem;
 
 impl FixedLimitTexasHoldem {
+    #[allow(clippy::too_many_arguments)]
     pub fn create_state(
         automations: &[Automation],
         ante_trimming_status: bool,
-        raw_antes: RawValues,
-        raw_blinds_or_straddles: RawValues,
-        small_bet: i64,
-        big_bet: i64,
-        raw_starting_stacks: RawValues,
+        raw_antes: impl Into<RawValues>,
+        ante_placement: AntePlacement,
+        raw_blinds_or_straddles: impl Into<RawValues>,
+        small_bet: Amount,
+        big_bet: Amount,
+        raw_starting_stacks: impl Into<RawValues>,
         player_count: usize,
         mode: Mode,
-    ) -> Result<State, String> {
+    ) -> Result<State, BuildError> {
         let streets = vec![
             Street::new(false, vec![false; 2], 0, false, Opening::Position, small_bet, Some(4))?,
             Street::new(true, vec![], 3, false, Opening::Position, small_bet, Some(4))?,
@@ -44,8 +44,9 @@ impl FixedLimitTexasHoldem {
             .betting_structure(BettingStructure::FixedLimit)
             .ante_trimming_status(ante_trimming_status)
             .raw_antes(raw_antes)
+            .ante_placement(ante_placement)
             .raw_blinds_or_straddles(raw_blinds_or_straddles)
-            .bring_in(0)
+            .bring_in(Amount::zero())
             .raw_starting_stacks(raw_starting_stacks)
             .mode(mode)
             .build()
@@ -55,16 +56,18 @@ impl FixedLimitTexasHoldem {
 pub struct NoLimitTexasHoldem;
 
 impl NoLimitTexasHoldem {
+    #[allow(clippy::too_many_arguments)]
     pub fn create_state(
         automations: &[Automation],
         ante_trimming_status: bool,
-        raw_antes: RawValues,
-        raw_blinds_or_straddles: RawValues,
-        min_bet: i64,
-        raw_starting_stacks: RawValues,
+        raw_antes: impl Into<RawValues>,
+        ante_placement: AntePlacement,
+        raw_blinds_or_straddles: impl Into<RawValues>,
+        min_bet: Amount,
+        raw_starting_stacks: impl Into<RawValues>,
         player_count: usize,
         mode: Mode,
-    ) -> Result<State, String> {
+    ) -> Result<State, BuildError> {
         let streets = vec![
             Street::new(false, vec![false; 2], 0, false, Opening::Position, min_bet, None)?,
             Street::new(true, vec![], 3, false, Opening::Position, min_bet, None)?,
@@ -80,8 +83,50 @@ impl NoLimitTexasHoldem {
             .betting_structure(BettingStructure::NoLimit)
             .ante_trimming_status(ante_trimming_status)
             .raw_antes(raw_antes)
+            .ante_placement(ante_placement)
             .raw_blinds_or_straddles(raw_blinds_or_straddles)
-            .bring_in(0)
+            .bring_in(Amount::zero())
+            .raw_starting_stacks(raw_starting_stacks)
+            .mode(mode)
+            .build()
+    }
+}
+
+/// Usually played with an ante from every player and a blind from the button alone, the last
+/// player, who then acts last before the flop.
+pub struct NoLimitShortDeckHoldem;
+
+impl NoLimitShortDeckHoldem {
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_state(
+        automations: &[Automation],
+        ante_trimming_status: bool,
+        raw_antes: impl Into<RawValues>,
+        ante_placement: AntePlacement,
+        raw_blinds_or_straddles: impl Into<RawValues>,
+        min_bet: Amount,
+        raw_starting_stacks: impl Into<RawValues>,
+        player_count: usize,
+        mode: Mode,
+    ) -> Result<State, BuildError> {
+        let streets = vec![
+            Street::new(false, vec![false; 2], 0, false, Opening::Position, min_bet, None)?,
+            Street::new(true, vec![], 3, false, Opening::Position, min_bet, None)?,
+            Street::new(true, vec![], 1, false, Opening::Position, min_bet, None)?,
+            Street::new(true, vec![], 1, false, Opening::Position, min_bet, None)?,
+        ];
+
+        StateBuilder::new(player_count)
+            .automations(automations)
+            .streets(streets)
+            .deck(Deck::short_deck_holdem())
+            .hand_types(vec![HandType::ShortDeckHoldemHand])
+            .betting_structure(BettingStructure::NoLimit)
+            .ante_trimming_status(ante_trimming_status)
+            .raw_antes(raw_antes)
+            .ante_placement(ante_placement)
+            .raw_blinds_or_straddles(raw_blinds_or_straddles)
+            .bring_in(Amount::zero())
             .raw_starting_stacks(raw_starting_stacks)
             .mode(mode)
             .build()
@@ -91,16 +136,18 @@ impl NoLimitTexasHoldem {
 pub struct PotLimitOmahaHoldem;
 
 impl PotLimitOmahaHoldem {
-     pub fn create_state(
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_state(
         automations: &[Automation],
         ante_trimming_status: bool,
-        raw_antes: RawValues,
-        raw_blinds_or_straddles: RawValues,
-        min_bet: i64,
-        raw_starting_stacks: RawValues,
+        raw_antes: impl Into<RawValues>,
+        ante_placement: AntePlacement,
+        raw_blinds_or_straddles: impl Into<RawValues>,
+        min_bet: Amount,
+        raw_starting_stacks: impl Into<RawValues>,
         player_count: usize,
         mode: Mode,
-    ) -> Result<State, String> {
+    ) -> Result<State, BuildError> {
         let streets = vec![
             Street::new(false, vec![false; 4], 0, false, Opening::Position, min_bet, None)?,
             Street::new(true, vec![], 3, false, Opening::Position, min_bet, None)?,
@@ -116,12 +163,213 @@ impl PotLimitOmahaHoldem {
             .betting_structure(BettingStructure::PotLimit)
             .ante_trimming_status(ante_trimming_status)
             .raw_antes(raw_antes)
+            .ante_placement(ante_placement)
+            .raw_blinds_or_straddles(raw_blinds_or_straddles)
+            .bring_in(Amount::zero())
+            .raw_starting_stacks(raw_starting_stacks)
+            .mode(mode)
+            .build()
+    }
+}
+
+pub struct PotLimitOmahaHoldemHighLowSplitEightOrBetter;
+
+impl PotLimitOmahaHoldemHighLowSplitEightOrBetter {
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_state(
+        automations: &[Automation],
+        ante_trimming_status: bool,
+        raw_antes: impl Into<RawValues>,
+        ante_placement: AntePlacement,
+        raw_blinds_or_straddles: impl Into<RawValues>,
+        min_bet: Amount,
+        raw_starting_stacks: impl Into<RawValues>,
+        player_count: usize,
+        mode: Mode,
+    ) -> Result<State, BuildError> {
+        let streets = vec![
+            Street::new(false, vec![false; 4], 0, false, Opening::Position, min_bet, None)?,
+            Street::new(true, vec![], 3, false, Opening::Position, min_bet, None)?,
+            Street::new(true, vec![], 1, false, Opening::Position, min_bet, None)?,
+            Street::new(true, vec![], 1, false, Opening::Position, min_bet, None)?,
+        ];
+
+        StateBuilder::new(player_count)
+            .automations(automations)
+            .streets(streets)
+            .deck(Deck::standard())
+            .hand_types(vec![HandType::OmahaHoldemHand, HandType::OmahaEightOrBetterLowHand])
+            .betting_structure(BettingStructure::PotLimit)
+            .ante_trimming_status(ante_trimming_status)
+            .raw_antes(raw_antes)
+            .ante_placement(ante_placement)
+            .raw_blinds_or_straddles(raw_blinds_or_straddles)
+            .bring_in(Amount::zero())
+            .raw_starting_stacks(raw_starting_stacks)
+            .mode(mode)
+            .build()
+    }
+}
+
+pub struct NoLimitDeuceToSevenLowballSingleDraw;
+
+impl NoLimitDeuceToSevenLowballSingleDraw {
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_state(
+        automations: &[Automation],
+        ante_trimming_status: bool,
+        raw_antes: impl Into<RawValues>,
+        ante_placement: AntePlacement,
+        raw_blinds_or_straddles: impl Into<RawValues>,
+        min_bet: Amount,
+        raw_starting_stacks: impl Into<RawValues>,
+        player_count: usize,
+        mode: Mode,
+    ) -> Result<State, BuildError> {
+        let streets = vec![
+            Street::new(false, vec![false; 5], 0, false, Opening::Position, min_bet, None)?,
+            Street::new(true, vec![], 0, true, Opening::Position, min_bet, None)?,
+        ];
+
+        StateBuilder::new(player_count)
+            .automations(automations)
+            .streets(streets)
+            .deck(Deck::standard())
+            .hand_types(vec![HandType::StandardLowHand])
+            .betting_structure(BettingStructure::NoLimit)
+            .ante_trimming_status(ante_trimming_status)
+            .raw_antes(raw_antes)
+            .ante_placement(ante_placement)
             .raw_blinds_or_straddles(raw_blinds_or_straddles)
-            .bring_in(0)
+            .bring_in(Amount::zero())
+            .raw_starting_stacks(raw_starting_stacks)
+            .mode(mode)
+            .build()
+    }
+}
+
+pub struct FixedLimitDeuceToSevenLowballTripleDraw;
+
+impl FixedLimitDeuceToSevenLowballTripleDraw {
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_state(
+        automations: &[Automation],
+        ante_trimming_status: bool,
+        raw_antes: impl Into<RawValues>,
+        ante_placement: AntePlacement,
+        raw_blinds_or_straddles: impl Into<RawValues>,
+        small_bet: Amount,
+        big_bet: Amount,
+        raw_starting_stacks: impl Into<RawValues>,
+        player_count: usize,
+        mode: Mode,
+    ) -> Result<State, BuildError> {
+        let streets = vec![
+            Street::new(false, vec![false; 5], 0, false, Opening::Position, small_bet, Some(4))?,
+            Street::new(true, vec![], 0, true, Opening::Position, small_bet, Some(4))?,
+            Street::new(true, vec![], 0, true, Opening::Position, big_bet, Some(4))?,
+            Street::new(true, vec![], 0, true, Opening::Position, big_bet, Some(4))?,
+        ];
+
+        StateBuilder::new(player_count)
+            .automations(automations)
+            .streets(streets)
+            .deck(Deck::standard())
+            .hand_types(vec![HandType::StandardLowHand])
+            .betting_structure(BettingStructure::FixedLimit)
+            .ante_trimming_status(ante_trimming_status)
+            .raw_antes(raw_antes)
+            .ante_placement(ante_placement)
+            .raw_blinds_or_straddles(raw_blinds_or_straddles)
+            .bring_in(Amount::zero())
+            .raw_starting_stacks(raw_starting_stacks)
+            .mode(mode)
+            .build()
+    }
+}
+
+pub struct FixedLimitSevenCardStud;
+
+impl FixedLimitSevenCardStud {
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_state(
+        automations: &[Automation],
+        ante_trimming_status: bool,
+        raw_antes: impl Into<RawValues>,
+        ante_placement: AntePlacement,
+        bring_in: Amount,
+        small_bet: Amount,
+        big_bet: Amount,
+        raw_starting_stacks: impl Into<RawValues>,
+        player_count: usize,
+        mode: Mode,
+    ) -> Result<State, BuildError> {
+        let streets = vec![
+            Street::new(false, vec![false, false, true], 0, false, Opening::LowCard, small_bet, Some(4))?,
+            Street::new(true, vec![true], 0, false, Opening::HighHand, small_bet, Some(4))?,
+            Street::new(true, vec![true], 0, false, Opening::HighHand, big_bet, Some(4))?,
+            Street::new(true, vec![true], 0, false, Opening::HighHand, big_bet, Some(4))?,
+            Street::new(true, vec![false], 0, false, Opening::HighHand, big_bet, Some(4))?,
+        ];
+
+        StateBuilder::new(player_count)
+            .automations(automations)
+            .streets(streets)
+            .deck(Deck::standard())
+            .hand_types(vec![HandType::StandardHighHand])
+            .betting_structure(BettingStructure::FixedLimit)
+            .ante_trimming_status(ante_trimming_status)
+            .raw_antes(raw_antes)
+            .ante_placement(ante_placement)
+            .raw_blinds_or_straddles(Amount::zero())
+            .bring_in(bring_in)
+            .raw_starting_stacks(raw_starting_stacks)
+            .mode(mode)
+            .odd_chip_rule(OddChipRule::HighCard)
+            .build()
+    }
+}
+
+pub struct FixedLimitSevenCardStudHighLowSplitEightOrBetter;
+
+impl FixedLimitSevenCardStudHighLowSplitEightOrBetter {
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_state(
+        automations: &[Automation],
+        ante_trimming_status: bool,
+        raw_antes: impl Into<RawValues>,
+        ante_placement: AntePlacement,
+        bring_in: Amount,
+        small_bet: Amount,
+        big_bet: Amount,
+        raw_starting_stacks: impl Into<RawValues>,
+        player_count: usize,
+        mode: Mode,
+    ) -> Result<State, BuildError> {
+        let streets = vec![
+            Street::new(false, vec![false, false, true], 0, false, Opening::LowCard, small_bet, Some(4))?,
+            Street::new(true, vec![true], 0, false, Opening::HighHand, small_bet, Some(4))?,
+            Street::new(true, vec![true], 0, false, Opening::HighHand, big_bet, Some(4))?,
+            Street::new(true, vec![true], 0, false, Opening::HighHand, big_bet, Some(4))?,
+            Street::new(true, vec![false], 0, false, Opening::HighHand, big_bet, Some(4))?,
+        ];
+
+        StateBuilder::new(player_count)
+            .automations(automations)
+            .streets(streets)
+            .deck(Deck::standard())
+            .hand_types(vec![HandType::StandardHighHand, HandType::EightOrBetterLowHand])
+            .betting_structure(BettingStructure::FixedLimit)
+            .ante_trimming_status(ante_trimming_status)
+            .raw_antes(raw_antes)
+            .ante_placement(ante_placement)
+            .raw_blinds_or_straddles(Amount::zero())
+            .bring_in(bring_in)
             .raw_starting_stacks(raw_starting_stacks)
             .mode(mode)
+            .odd_chip_rule(OddChipRule::HighCard)
             .build()
     }
 }
 
-// ... Implementations for other game types like Razz, Stud, Draw games etc. would follow a similar pattern.
+// ... Implementations for other game types like Razz would follow a similar pattern.
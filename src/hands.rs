@@ -3,23 +3,133 @@
 use std::cmp::Ordering;
 use std::fmt::{self, Debug, Display};
 use std::hash::{Hash, Hasher};
+use std::sync::Arc;
 use itertools::Itertools;
 use once_cell::sync::Lazy;
-
-use crate::lookups::{
-    BadugiLookup, EightOrBetterLookup, Entry, KuhnPokerLookup, Lookup, RegularLookup,
-    ShortDeckHoldemLookup, StandardBadugiLookup, StandardLookup,
-};
+use serde::{Deserialize, Serialize};
+
+use crate::evaluators::StandardEvaluator;
+#[cfg(feature = "badugi")]
+use crate::lookups::{BadugiLookup, StandardBadugiLookup};
+#[cfg(feature = "low-hands")]
+use crate::lookups::{EightOrBetterLookup, RegularLookup};
+#[cfg(feature = "kuhn")]
+use crate::lookups::KuhnPokerLookup;
+#[cfg(feature = "short-deck")]
+use crate::lookups::ShortDeckHoldemLookup;
+use crate::lookups::{Entry, Lookup, LookupError, StandardLookup};
 use crate::utilities::Card;
 
-// Create static, lazily-initialized instances of each lookup table.
-static STANDARD_LOOKUP: Lazy<StandardLookup> = Lazy::new(StandardLookup::new);
-static SHORT_DECK_HOLDEM_LOOKUP: Lazy<ShortDeckHoldemLookup> = Lazy::new(ShortDeckHoldemLookup::new);
-static EIGHT_OR_BETTER_LOOKUP: Lazy<EightOrBetterLookup> = Lazy::new(EightOrBetterLookup::new);
-static REGULAR_LOOKUP: Lazy<RegularLookup> = Lazy::new(RegularLookup::new);
-static BADUGI_LOOKUP: Lazy<BadugiLookup> = Lazy::new(BadugiLookup::new);
-static STANDARD_BADUGI_LOOKUP: Lazy<StandardBadugiLookup> = Lazy::new(StandardBadugiLookup::new);
-static KUHN_POKER_LOOKUP: Lazy<KuhnPokerLookup> = Lazy::new(KuhnPokerLookup::new);
+// Create static, lazily-initialized, shareable instances of each lookup table.
+static STANDARD_LOOKUP: Lazy<Arc<dyn Lookup>> = Lazy::new(|| Arc::new(StandardLookup::new()));
+#[cfg(feature = "short-deck")]
+static SHORT_DECK_HOLDEM_LOOKUP: Lazy<Arc<dyn Lookup>> = Lazy::new(|| Arc::new(ShortDeckHoldemLookup::new()));
+#[cfg(feature = "low-hands")]
+static EIGHT_OR_BETTER_LOOKUP: Lazy<Arc<dyn Lookup>> = Lazy::new(|| Arc::new(EightOrBetterLookup::new()));
+#[cfg(feature = "low-hands")]
+static REGULAR_LOOKUP: Lazy<Arc<dyn Lookup>> = Lazy::new(|| Arc::new(RegularLookup::new()));
+#[cfg(feature = "badugi")]
+static BADUGI_LOOKUP: Lazy<Arc<dyn Lookup>> = Lazy::new(|| Arc::new(BadugiLookup::new()));
+#[cfg(feature = "badugi")]
+static STANDARD_BADUGI_LOOKUP: Lazy<Arc<dyn Lookup>> = Lazy::new(|| Arc::new(StandardBadugiLookup::new()));
+#[cfg(feature = "kuhn")]
+static KUHN_POKER_LOOKUP: Lazy<Arc<dyn Lookup>> = Lazy::new(|| Arc::new(KuhnPokerLookup::new()));
+static STANDARD_EVALUATOR: Lazy<StandardEvaluator> = Lazy::new(|| StandardEvaluator::new(&**STANDARD_LOOKUP));
+
+/// The lookup tables, several hand types sharing some of them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LookupKind {
+    Standard,
+    ShortDeckHoldem,
+    EightOrBetter,
+    Regular,
+    Badugi,
+    StandardBadugi,
+    KuhnPoker,
+}
+
+impl LookupKind {
+    /// Returns the shared instance of the table, if its feature is enabled.
+    fn shared(self) -> Option<&'static Lazy<Arc<dyn Lookup>>> {
+        match self {
+            LookupKind::Standard => Some(&STANDARD_LOOKUP),
+            #[cfg(feature = "short-deck")]
+            LookupKind::ShortDeckHoldem => Some(&SHORT_DECK_HOLDEM_LOOKUP),
+            #[cfg(feature = "low-hands")]
+            LookupKind::EightOrBetter => Some(&EIGHT_OR_BETTER_LOOKUP),
+            #[cfg(feature = "low-hands")]
+            LookupKind::Regular => Some(&REGULAR_LOOKUP),
+            #[cfg(feature = "badugi")]
+            LookupKind::Badugi => Some(&BADUGI_LOOKUP),
+            #[cfg(feature = "badugi")]
+            LookupKind::StandardBadugi => Some(&STANDARD_BADUGI_LOOKUP),
+            #[cfg(feature = "kuhn")]
+            LookupKind::KuhnPoker => Some(&KUHN_POKER_LOOKUP),
+            #[allow(unreachable_patterns)]
+            _ => None,
+        }
+    }
+
+    /// Builds a new instance of the table, if its feature is enabled.
+    fn build(self) -> Option<Arc<dyn Lookup>> {
+        match self {
+            LookupKind::Standard => Some(Arc::new(StandardLookup::new())),
+            #[cfg(feature = "short-deck")]
+            LookupKind::ShortDeckHoldem => Some(Arc::new(ShortDeckHoldemLookup::new())),
+            #[cfg(feature = "low-hands")]
+            LookupKind::EightOrBetter => Some(Arc::new(EightOrBetterLookup::new())),
+            #[cfg(feature = "low-hands")]
+            LookupKind::Regular => Some(Arc::new(RegularLookup::new())),
+            #[cfg(feature = "badugi")]
+            LookupKind::Badugi => Some(Arc::new(BadugiLookup::new())),
+            #[cfg(feature = "badugi")]
+            LookupKind::StandardBadugi => Some(Arc::new(StandardBadugiLookup::new())),
+            #[cfg(feature = "kuhn")]
+            LookupKind::KuhnPoker => Some(Arc::new(KuhnPokerLookup::new())),
+            #[allow(unreachable_patterns)]
+            _ => None,
+        }
+    }
+}
+
+/// The reason a hand could not be formed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HandError {
+    /// The cards could not be parsed.
+    InvalidCards(String),
+    InvalidCardCount { hand: &'static str, count: usize },
+    /// The cards form no hand of the type, as reported by the lookup.
+    InvalidHand { hand: &'static str, error: LookupError },
+    /// No hand of the type can be formed from the hole and board cards.
+    NoHand { hand: &'static str },
+    /// The lookup of the hand type requires a disabled feature.
+    UnavailableLookup(HandType),
+}
+
+impl Display for HandError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HandError::InvalidCards(error) => write!(f, "{}", error),
+            HandError::InvalidCardCount { hand, count } => write!(f, "Invalid card count of {} for {}", count, hand),
+            HandError::InvalidHand { hand, error } => write!(f, "Invalid {} hand: {}", hand, error),
+            HandError::NoHand { hand } => write!(f, "No valid {} hand can be formed.", hand),
+            HandError::UnavailableLookup(hand_type) => write!(f, "The lookup of {:?} requires a disabled feature", hand_type),
+        }
+    }
+}
+
+impl std::error::Error for HandError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            HandError::InvalidHand { error, .. } => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<HandError> for String {
+    fn from(error: HandError) -> Self { error.to_string() }
+}
 
 /// A trait representing a poker hand.
 /// Stronger hands are considered greater than weaker hands.
@@ -35,14 +145,32 @@ pub trait Hand: Sized + Clone + Eq + Hash + Ord + Display + Debug {
     fn entry(&self) -> Entry;
 
     /// Creates a new hand from a vector of cards, using a specific lookup.
-    fn new(cards: Vec<Card>, lookup: &dyn Lookup) -> Result<Self, String>;
+    fn new(cards: Vec<Card>, lookup: &dyn Lookup) -> Result<Self, HandError>;
 
     /// Determines the best possible hand from a set of hole and board cards.
-    fn from_game(hole_cards_str: &str, board_cards_str: &str, lookup: &dyn Lookup) -> Result<Self, String>;
+    fn from_game(hole_cards_str: &str, board_cards_str: &str, lookup: &dyn Lookup) -> Result<Self, HandError>;
+}
+
+/// The semantics of a hand type, consulted by the state to evaluate hands at showdown.
+///
+/// Implement this to plug in hand types beyond [`HandType`] without modifying the crate.
+pub trait EvaluatorSpec: Send + Sync {
+    /// `true` if a lower entry is a better hand, `false` otherwise.
+    fn low(&self) -> bool;
+
+    /// Returns the entry of the best hand that can be formed from the cards, or `None` if no
+    /// valid hand can be formed.
+    fn evaluate(&self, hole_cards: &[Card], board_cards: &[Card]) -> Option<Entry>;
+
+    /// Compares two entries. Stronger hands are considered greater.
+    fn compare(&self, entry: &Entry, other: &Entry) -> Ordering {
+        if self.low() { other.cmp(entry) } else { entry.cmp(other) }
+    }
 }
 
 /// An enum to act as a factory for different hand types.
-#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum HandType {
     StandardHighHand,
     StandardLowHand,
@@ -57,53 +185,163 @@ pub enum HandType {
 }
 
 impl HandType {
-    /// Creates the best possible hand of the corresponding type from game cards.
-    pub fn from_game(&self, hole_cards_str: &str, board_cards_str: &str) -> Result<Box<impl Hand>, String> {
+    fn lookup_kind(&self) -> LookupKind {
         match self {
-            HandType::StandardHighHand => {
-                let hand = StandardHighHand::from_game(hole_cards_str, board_cards_str, &*STANDARD_LOOKUP)?;
-                Ok(Box::new(hand))
-            }
-            HandType::StandardLowHand => {
-                let hand = StandardHighHand::from_game(hole_cards_str, board_cards_str, &*STANDARD_LOOKUP)?;
-                Ok(Box::new(hand))
-            }
-            HandType::ShortDeckHoldemHand => {
-                let hand = StandardHighHand::from_game(hole_cards_str, board_cards_str, &*SHORT_DECK_HOLDEM_LOOKUP)?;
-                Ok(Box::new(hand))
-            }
-            HandType::EightOrBetterLowHand => {
-                let hand = StandardHighHand::from_game(hole_cards_str, board_cards_str, &*EIGHT_OR_BETTER_LOOKUP)?;
-                Ok(Box::new(hand))
-            }
-            HandType::RegularLowHand => {
-                let hand = StandardHighHand::from_game(hole_cards_str, board_cards_str, &*REGULAR_LOOKUP)?;
-                Ok(Box::new(hand))
-            }
-            HandType::OmahaHoldemHand => {
-                let hand = StandardHighHand::from_game(hole_cards_str, board_cards_str, &*STANDARD_LOOKUP)?;
-                Ok(Box::new(hand))
-            }
-            HandType::OmahaEightOrBetterLowHand => {
-                let hand = StandardHighHand::from_game(hole_cards_str, board_cards_str, &*EIGHT_OR_BETTER_LOOKUP)?;
-                Ok(Box::new(hand))
-            }
-            HandType::BadugiHand => {
-                let hand = StandardHighHand::from_game(hole_cards_str, board_cards_str, &*BADUGI_LOOKUP)?;
-                Ok(Box::new(hand))
-            }
-            HandType::StandardBadugiHand => {
-                let hand = StandardHighHand::from_game(hole_cards_str, board_cards_str, &*STANDARD_BADUGI_LOOKUP)?;
-                Ok(Box::new(hand))
-            }
-            HandType::KuhnPokerHand => {
-                let hand = StandardHighHand::from_game(hole_cards_str, board_cards_str, &*KUHN_POKER_LOOKUP)?;
-                Ok(Box::new(hand))
+            HandType::StandardHighHand | HandType::StandardLowHand | HandType::OmahaHoldemHand => LookupKind::Standard,
+            HandType::ShortDeckHoldemHand => LookupKind::ShortDeckHoldem,
+            HandType::EightOrBetterLowHand | HandType::OmahaEightOrBetterLowHand => LookupKind::EightOrBetter,
+            HandType::RegularLowHand => LookupKind::Regular,
+            HandType::BadugiHand => LookupKind::Badugi,
+            HandType::StandardBadugiHand => LookupKind::StandardBadugi,
+            HandType::KuhnPokerHand => LookupKind::KuhnPoker,
+        }
+    }
+
+    /// `true` if the feature providing the lookup of this hand type is enabled.
+    pub fn is_available(&self) -> bool {
+        self.lookup_kind().shared().is_some()
+    }
+
+    fn shared_lazy_lookup(&self) -> &'static Lazy<Arc<dyn Lookup>> {
+        self.lookup_kind().shared()
+            .unwrap_or_else(|| panic!("The lookup of {:?} requires a disabled feature", self))
+    }
+
+    /// Returns a shared handle to the lookup used to evaluate hands of this type.
+    ///
+    /// Panics if the feature providing the lookup is disabled.
+    pub fn shared_lookup(&self) -> Arc<dyn Lookup> {
+        Arc::clone(self.shared_lazy_lookup())
+    }
+
+    /// Returns the lookup used to evaluate hands of this type.
+    ///
+    /// Panics if the feature providing the lookup is disabled.
+    pub fn lookup(&self) -> &'static dyn Lookup {
+        &***self.shared_lazy_lookup()
+    }
+
+    /// `true` if a lower entry is a better hand of this type, `false` otherwise.
+    pub fn low(&self) -> bool {
+        match self {
+            HandType::StandardHighHand | HandType::ShortDeckHoldemHand | HandType::OmahaHoldemHand
+            | HandType::KuhnPokerHand => false,
+            HandType::StandardLowHand | HandType::EightOrBetterLowHand | HandType::RegularLowHand
+            | HandType::OmahaEightOrBetterLowHand | HandType::BadugiHand | HandType::StandardBadugiHand => true,
+        }
+    }
+
+    /// Compares two entries of this hand type. Stronger hands are considered greater.
+    pub fn compare(&self, entry: &Entry, other: &Entry) -> Ordering {
+        if self.low() { other.cmp(entry) } else { entry.cmp(other) }
+    }
+
+    /// Returns the entry of the best hand of this type that can be formed from the cards, or
+    /// `None` if no valid hand can be formed (e.g. no qualifying low).
+    ///
+    /// This works on parsed cards directly and is meant for enumeration-heavy callers.
+    pub fn evaluate(&self, hole_cards: &[Card], board_cards: &[Card]) -> Option<Entry> {
+        if let HandType::StandardHighHand = self {
+            if (5..=7).contains(&(hole_cards.len() + board_cards.len())) {
+                let cards: Vec<Card> = hole_cards.iter().chain(board_cards).cloned().collect();
+                return STANDARD_EVALUATOR.evaluate(&cards);
             }
         }
+
+        self.evaluate_with(self.lookup(), hole_cards, board_cards)
+    }
+
+    /// Returns the entry of the best hand of this type that can be formed from the cards, using
+    /// the given lookup instead of the default one (e.g. a table for a stripped deck).
+    pub fn evaluate_with(&self, lookup: &dyn Lookup, hole_cards: &[Card], board_cards: &[Card]) -> Option<Entry> {
+        let candidates: Box<dyn Iterator<Item = Vec<Card>>> = match self {
+            HandType::OmahaHoldemHand | HandType::OmahaEightOrBetterLowHand => Box::new(
+                hole_cards.iter().cloned().combinations(2)
+                    .cartesian_product(board_cards.iter().cloned().combinations(3).collect::<Vec<_>>())
+                    .map(|(hole, board)| hole.into_iter().chain(board).collect()),
+            ),
+            HandType::BadugiHand | HandType::StandardBadugiHand => Box::new(
+                (1..=4).rev().flat_map(move |count| hole_cards.iter().chain(board_cards).cloned().combinations(count)),
+            ),
+            HandType::KuhnPokerHand => Box::new(hole_cards.iter().chain(board_cards).map(|&card| vec![card])),
+            _ => Box::new(hole_cards.iter().chain(board_cards).cloned().combinations(5)),
+        };
+
+        candidates
+            .filter_map(|cards| lookup.get_entry_from_cards(&cards))
+            .max_by(|entry, other| self.compare(entry, other))
+    }
+
+    /// Creates the best possible hand of the corresponding type from game cards.
+    pub fn from_game(&self, hole_cards_str: &str, board_cards_str: &str) -> Result<Box<impl Hand>, HandError> {
+        self.from_game_with(hole_cards_str, board_cards_str, self.lookup())
     }
+
+    /// Creates the best possible hand from game cards, using the given lookup instead of the
+    /// default one.
+    pub fn from_game_with(&self, hole_cards_str: &str, board_cards_str: &str, lookup: &dyn Lookup) -> Result<Box<impl Hand>, HandError> {
+        let hand = StandardHighHand::from_game(hole_cards_str, board_cards_str, lookup)?;
+        Ok(Box::new(hand))
+    }
+}
+
+impl EvaluatorSpec for HandType {
+    fn low(&self) -> bool { HandType::low(self) }
+    fn evaluate(&self, hole_cards: &[Card], board_cards: &[Card]) -> Option<Entry> { HandType::evaluate(self, hole_cards, board_cards) }
+}
+
+/// A registry of lookups built up front for the hand types of a game, and only for them.
+///
+/// Unlike the shared lookups, which live for the rest of the program once first used, the
+/// tables are owned by the registry and freed with it. Hand types sharing a table share it here
+/// too.
+#[derive(Clone)]
+pub struct Evaluators {
+    lookups: Vec<(HandType, Arc<dyn Lookup>)>,
+}
+
+impl Evaluators {
+    /// Builds the lookups of the hand types, failing if the feature of one is disabled.
+    pub fn new(hand_types: &[HandType]) -> Result<Self, HandError> {
+        let mut lookups: Vec<(HandType, Arc<dyn Lookup>)> = Vec::new();
+        for &hand_type in hand_types {
+            let shared_lookup = lookups.iter()
+                .find(|(other, _)| other.lookup_kind() == hand_type.lookup_kind())
+                .map(|(_, lookup)| lookup.clone());
+            let lookup = match shared_lookup {
+                Some(lookup) => lookup,
+                None => hand_type.lookup_kind().build()
+                    .ok_or(HandError::UnavailableLookup(hand_type))?,
+            };
+            lookups.push((hand_type, lookup));
+        }
+
+        Ok(Self { lookups })
+    }
+
+    pub fn lookup(&self, hand_type: HandType) -> Option<Arc<dyn Lookup>> {
+        self.lookups.iter().find(|(other, _)| *other == hand_type).map(|(_, lookup)| lookup.clone())
+    }
+
+    /// Returns an evaluator of the hand type backed by the registry's lookup.
+    pub fn evaluator(&self, hand_type: HandType) -> Option<Arc<dyn EvaluatorSpec>> {
+        let lookup = self.lookup(hand_type)?;
+        Some(Arc::new(LookupEvaluator { hand_type, lookup }))
+    }
+}
+
+/// Evaluates hands of a type with a user-supplied lookup.
+pub struct LookupEvaluator {
+    pub hand_type: HandType,
+    pub lookup: Arc<dyn Lookup>,
 }
 
+impl EvaluatorSpec for LookupEvaluator {
+    fn low(&self) -> bool { self.hand_type.low() }
+    fn evaluate(&self, hole_cards: &[Card], board_cards: &[Card]) -> Option<Entry> {
+        self.hand_type.evaluate_with(&*self.lookup, hole_cards, board_cards)
+    }
+}
 
 /// A macro to implement common traits (`PartialEq`, `Ord`, `Hash`, `Display`, `Debug`) for a hand struct.
 macro_rules! impl_hand_boilerplate {
@@ -170,19 +408,19 @@ macro_rules! impl_combination_hand {
             fn cards(&self) -> &[Card] { &self.cards }
             fn entry(&self) -> Entry { self.entry }
 
-            fn new(cards: Vec<Card>, lookup: &dyn Lookup) -> Result<Self, String> {
+            fn new(cards: Vec<Card>, lookup: &dyn Lookup) -> Result<Self, HandError> {
                 let cards_str = cards.iter().map(|c| c.to_string()).collect::<String>();
                 if cards.len() != Self::CARD_COUNT.unwrap() {
-                    return Err(format!("Invalid card count for {}", $hand_name));
+                    return Err(HandError::InvalidCardCount { hand: $hand_name, count: cards.len() });
                 }
                 let entry = lookup.get_entry(&cards_str)
-                    .map_err(|e| format!("Invalid {} hand: {}", $hand_name, e))?;
+                    .map_err(|error| HandError::InvalidHand { hand: $hand_name, error })?;
                 Ok(Self { cards, entry })
             }
 
-            fn from_game(hole_cards_str: &str, board_cards_str: &str, lookup: &dyn Lookup) -> Result<Self, String> {
-                let hole_cards = Card::parse_cards(hole_cards_str)?;
-                let board_cards = Card::parse_cards(board_cards_str)?;
+            fn from_game(hole_cards_str: &str, board_cards_str: &str, lookup: &dyn Lookup) -> Result<Self, HandError> {
+                let hole_cards = Card::parse_cards(hole_cards_str).map_err(HandError::InvalidCards)?;
+                let board_cards = Card::parse_cards(board_cards_str).map_err(HandError::InvalidCards)?;
                 let all_cards: Vec<Card> = hole_cards.into_iter().chain(board_cards.into_iter()).collect();
 
                 all_cards
@@ -190,7 +428,7 @@ macro_rules! impl_combination_hand {
                     .combinations(Self::CARD_COUNT.unwrap())
                     .filter_map(|combo| Self::new(combo, lookup).ok())
                     .max()
-                    .ok_or_else(|| format!("No valid {} hand can be formed.", $hand_name))
+                    .ok_or(HandError::NoHand { hand: $hand_name })
             }
         }
     };
@@ -226,19 +464,19 @@ macro_rules! impl_hole_board_combination_hand {
             fn cards(&self) -> &[Card] { &self.cards }
             fn entry(&self) -> Entry { self.entry }
 
-            fn new(cards: Vec<Card>, lookup: &dyn Lookup) -> Result<Self, String> {
+            fn new(cards: Vec<Card>, lookup: &dyn Lookup) -> Result<Self, HandError> {
                 let cards_str = cards.iter().map(|c| c.to_string()).collect::<String>();
                 if cards.len() != Self::CARD_COUNT.unwrap() {
-                    return Err(format!("Invalid card count for {}", $hand_name));
+                    return Err(HandError::InvalidCardCount { hand: $hand_name, count: cards.len() });
                 }
                 let entry = lookup.get_entry(&cards_str)
-                    .map_err(|e| format!("Invalid {} hand: {}", $hand_name, e))?;
+                    .map_err(|error| HandError::InvalidHand { hand: $hand_name, error })?;
                 Ok(Self { cards, entry })
             }
 
-            fn from_game(hole_cards_str: &str, board_cards_str: &str, lookup: &dyn Lookup) -> Result<Self, String> {
-                let hole_cards = Card::parse_cards(hole_cards_str)?;
-                let board_cards = Card::parse_cards(board_cards_str)?;
+            fn from_game(hole_cards_str: &str, board_cards_str: &str, lookup: &dyn Lookup) -> Result<Self, HandError> {
+                let hole_cards = Card::parse_cards(hole_cards_str).map_err(HandError::InvalidCards)?;
+                let board_cards = Card::parse_cards(board_cards_str).map_err(HandError::InvalidCards)?;
 
                 hole_cards
                     .into_iter()
@@ -249,7 +487,7 @@ macro_rules! impl_hole_board_combination_hand {
                         Self::new(all_cards, lookup).ok()
                     })
                     .max()
-                    .ok_or_else(|| format!("No valid {} hand can be formed.", $hand_name))
+                    .ok_or(HandError::NoHand { hand: $hand_name })
             }
         }
     };
@@ -267,20 +505,20 @@ impl Hand for BadugiHand {
     fn cards(&self) -> &[Card] { &self.cards }
     fn entry(&self) -> Entry { self.entry }
 
-    fn new(cards: Vec<Card>, lookup: &dyn Lookup) -> Result<Self, String> {
+    fn new(cards: Vec<Card>, lookup: &dyn Lookup) -> Result<Self, HandError> {
         let cards_str = cards.iter().map(|c| c.to_string()).collect::<String>();
         let entry = lookup.get_entry(&cards_str)
-            .map_err(|_| format!("The cards '{}' form an invalid BadugiHand hand.", cards_str))?;
+            .map_err(|error| HandError::InvalidHand { hand: "BadugiHand", error })?;
         Ok(Self { cards, entry })
     }
-    fn from_game(hole_cards_str: &str, board_cards_str: &str, lookup: &dyn Lookup) -> Result<Self, String> {
-        let hole_cards = Card::parse_cards(hole_cards_str)?;
-        let all_cards: Vec<Card> = hole_cards.into_iter().chain(Card::parse_cards(board_cards_str)?).collect();
+    fn from_game(hole_cards_str: &str, board_cards_str: &str, lookup: &dyn Lookup) -> Result<Self, HandError> {
+        let hole_cards = Card::parse_cards(hole_cards_str).map_err(HandError::InvalidCards)?;
+        let all_cards: Vec<Card> = hole_cards.into_iter().chain(Card::parse_cards(board_cards_str).map_err(HandError::InvalidCards)?).collect();
         (1..=4).rev()
             .flat_map(|count| all_cards.iter().cloned().combinations(count))
             .filter_map(|combo| Self::new(combo, lookup).ok())
             .max()
-            .ok_or_else(|| "No valid BadugiHand hand can be formed".to_string())
+            .ok_or(HandError::NoHand { hand: "BadugiHand" })
     }
 }
 
@@ -293,20 +531,20 @@ impl Hand for StandardBadugiHand {
     fn cards(&self) -> &[Card] { &self.cards }
     fn entry(&self) -> Entry { self.entry }
 
-    fn new(cards: Vec<Card>, lookup: &dyn Lookup) -> Result<Self, String> {
+    fn new(cards: Vec<Card>, lookup: &dyn Lookup) -> Result<Self, HandError> {
         let cards_str = cards.iter().map(|c| c.to_string()).collect::<String>();
          let entry = lookup.get_entry(&cards_str)
-            .map_err(|_| format!("The cards '{}' form an invalid StandardBadugiHand hand.", cards_str))?;
+            .map_err(|error| HandError::InvalidHand { hand: "StandardBadugiHand", error })?;
         Ok(Self { cards, entry })
     }
-    fn from_game(hole_cards_str: &str, board_cards_str: &str, lookup: &dyn Lookup) -> Result<Self, String> {
-        let hole_cards = Card::parse_cards(hole_cards_str)?;
-        let all_cards: Vec<Card> = hole_cards.into_iter().chain(Card::parse_cards(board_cards_str)?).collect();
+    fn from_game(hole_cards_str: &str, board_cards_str: &str, lookup: &dyn Lookup) -> Result<Self, HandError> {
+        let hole_cards = Card::parse_cards(hole_cards_str).map_err(HandError::InvalidCards)?;
+        let all_cards: Vec<Card> = hole_cards.into_iter().chain(Card::parse_cards(board_cards_str).map_err(HandError::InvalidCards)?).collect();
         (1..=4).rev()
             .flat_map(|count| all_cards.iter().cloned().combinations(count))
             .filter_map(|combo| Self::new(combo, lookup).ok())
             .max()
-            .ok_or_else(|| "No valid StandardBadugiHand hand can be formed".to_string())
+            .ok_or(HandError::NoHand { hand: "StandardBadugiHand" })
     }
 }
 
@@ -319,19 +557,19 @@ impl Hand for KuhnPokerHand {
     fn cards(&self) -> &[Card] { &self.cards }
     fn entry(&self) -> Entry { self.entry }
 
-    fn new(cards: Vec<Card>, lookup: &dyn Lookup) -> Result<Self, String> {
+    fn new(cards: Vec<Card>, lookup: &dyn Lookup) -> Result<Self, HandError> {
         let cards_str = cards.iter().map(|c| c.to_string()).collect::<String>();
         let entry = lookup.get_entry(&cards_str)
-            .map_err(|_| format!("The cards '{}' form an invalid KuhnPokerHand hand.", cards_str))?;
+            .map_err(|error| HandError::InvalidHand { hand: "KuhnPokerHand", error })?;
         Ok(Self { cards, entry })
     }
-    fn from_game(hole_cards_str: &str, board_cards_str: &str, lookup: &dyn Lookup) -> Result<Self, String> {
-        let hole_cards = Card::parse_cards(hole_cards_str)?;
+    fn from_game(hole_cards_str: &str, board_cards_str: &str, lookup: &dyn Lookup) -> Result<Self, HandError> {
+        let hole_cards = Card::parse_cards(hole_cards_str).map_err(HandError::InvalidCards)?;
         hole_cards
             .into_iter()
-            .chain(Card::parse_cards(board_cards_str)?)
+            .chain(Card::parse_cards(board_cards_str).map_err(HandError::InvalidCards)?)
             .filter_map(|card| Self::new(vec![card], lookup).ok())
             .max()
-            .ok_or_else(|| "No valid KuhnPokerHand hand can be formed".to_string())
+            .ok_or(HandError::NoHand { hand: "KuhnPokerHand" })
     }
 }
@@ -0,0 +1,87 @@
+//! Implements kill pots, as played in fixed-limit games.
+//!
+//! A player who wins two pots in a row, or scoops a pot in a hi-lo game, kills the next hand: they
+//! post a kill blind and the stakes go up for that hand, by half in a half kill or double in a
+//! full kill. A [`KillTracker`] follows the winners from one hand to the next at a table, and the
+//! builder of the next hand is told of the kill with
+//! [`StateBuilder::kill`](crate::state::StateBuilder::kill).
+
+use num_traits::Zero;
+use serde::{Deserialize, Serialize};
+
+use crate::analysis::stats::won_amounts;
+use crate::state::State;
+use crate::utilities::Amount;
+
+/// How much the stakes go up in a kill pot.
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Kill {
+    /// The stakes go up by half.
+    Half,
+    /// The stakes double.
+    Full,
+}
+
+impl Kill {
+    pub fn multiplier(&self) -> f64 {
+        match self {
+            Kill::Half => 1.5,
+            Kill::Full => 2.0,
+        }
+    }
+}
+
+/// Returns the player who won every pot of the finished hand and the total amount pushed to them,
+/// or `None` if the pots were split between players.
+fn pot_winner(state: &State) -> Option<(usize, Amount)> {
+    let won_amounts = won_amounts(state);
+    let mut winner_indices = (0..state.player_count).filter(|&i| won_amounts[i] > Amount::zero());
+    let player_index = winner_indices.next()?;
+    winner_indices.next().is_none().then_some((player_index, won_amounts[player_index]))
+}
+
+/// Follows the winners of consecutive hands at a table to tell which of them kills the next hand.
+///
+/// Players are identified by seat, so that kills carry over as the button moves.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KillTracker {
+    pub kill: Kill,
+    /// The smallest pot whose winner is on the way to a kill, as small pots often do not count.
+    pub min_pot_amount: Amount,
+    /// The seat that won the last pot, if it was not split.
+    pub winner_index: Option<usize>,
+    /// The number of pots in a row the seat won.
+    pub win_count: usize,
+    /// The seat killing the next hand, if any.
+    pub killer_index: Option<usize>,
+}
+
+impl KillTracker {
+    pub fn new(kill: Kill) -> Self {
+        Self { kill, min_pot_amount: Amount::zero(), winner_index: None, win_count: 0, killer_index: None }
+    }
+
+    pub fn min_pot_amount(mut self, min_pot_amount: Amount) -> Self { self.min_pot_amount = min_pot_amount; self }
+
+    /// Records the winner of the finished hand, whose players sat in the seats in order, and works
+    /// out who kills the next hand.
+    ///
+    /// A split pot, or one smaller than the minimum, breaks the run of the last winner. In hi-lo
+    /// games, where only scoops count as wins, a single scoop is enough for a kill.
+    pub fn record(&mut self, state: &State, seat_indices: &[usize]) {
+        match pot_winner(state).filter(|&(_, amount)| amount >= self.min_pot_amount) {
+            Some((player_index, _)) => {
+                let seat_index = seat_indices[player_index];
+                self.win_count = if self.winner_index == Some(seat_index) { self.win_count + 1 } else { 1 };
+                self.winner_index = Some(seat_index);
+            }
+            None => {
+                self.win_count = 0;
+                self.winner_index = None;
+            }
+        }
+        let kill_win_count = if state.hand_types.len() > 1 { 1 } else { 2 };
+        self.killer_index = self.winner_index.filter(|_| self.win_count >= kill_win_count);
+    }
+}
@@ -3,5 +3,24 @@
 pub mod utilities;
 pub mod lookups;
 pub mod hands;
+pub mod evaluators;
 pub mod state;
-pub mod games;
\ No newline at end of file
+pub mod games;
+pub mod analysis;
+pub mod mcts;
+pub mod export;
+pub mod dataset;
+pub mod replay;
+pub mod definitions;
+pub mod phh;
+pub mod dealing;
+pub mod diff;
+pub mod encoding;
+pub mod tree;
+pub mod chance;
+pub mod table;
+pub mod chips;
+pub mod clock;
+pub mod positions;
+pub mod rake;
+pub mod kill;
\ No newline at end of file
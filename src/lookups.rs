@@ -1,5 +1,8 @@
 //! Implements classes related to poker hand lookups.
 //! Lookups are used by PokerKit's hand types to discern hand strengths.
+//!
+//! Only the standard lookup is always compiled; the others are behind the `short-deck`,
+//! `low-hands`, `badugi`, and `kuhn` features, all enabled by default.
 
 use std::collections::{BTreeMap, HashMap};
 use std::cmp::Ordering;
@@ -11,6 +14,11 @@ use crate::utilities::{Card, Rank, RankOrder}; // Assuming utilities.rs is in th
 // Include the generated PHF map
 include!(concat!(env!("OUT_DIR"), "/rank_multipliers.rs"));
 
+/// Returns the prime multiplier of the rank used for hashing rank multisets.
+pub(crate) fn rank_multiplier(rank: Rank) -> u64 {
+    *RANK_MULTIPLIERS.get(&rank.to_char()).unwrap_or(&1)
+}
+
 /// The enum for all hand classification labels.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Label {
@@ -42,7 +50,7 @@ impl std::fmt::Display for Label {
 }
 
 /// An entry in a hand lookup table, representing the strength of a hand.
-#[derive(Debug, Clone, Copy, Eq, Hash)]
+#[derive(Debug, Clone, Copy, Eq)]
 pub struct Entry {
     /// The strength index of the hand. Stronger hands have a greater index.
     pub index: i32,
@@ -56,6 +64,12 @@ impl PartialEq for Entry {
     }
 }
 
+impl std::hash::Hash for Entry {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.index.hash(state);
+    }
+}
+
 impl PartialOrd for Entry {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
         Some(self.cmp(other))
@@ -68,8 +82,35 @@ impl Ord for Entry {
     }
 }
 
+/// The reason cards could not be looked up.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LookupError {
+    /// The cards could not be parsed.
+    InvalidCards(String),
+    /// Badugi hands must be rainbow.
+    NotRainbow,
+    /// The cards form no hand of the lookup.
+    InvalidHand { cards: String },
+}
+
+impl std::fmt::Display for LookupError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LookupError::InvalidCards(error) => write!(f, "{}", error),
+            LookupError::NotRainbow => write!(f, "Badugi hands must be rainbow"),
+            LookupError::InvalidHand { cards } => write!(f, "The cards '{}' form an invalid hand.", cards),
+        }
+    }
+}
+
+impl std::error::Error for LookupError {}
+
+impl From<LookupError> for String {
+    fn from(error: LookupError) -> Self { error.to_string() }
+}
+
 /// A trait for hand lookup tables. This is now "dyn" safe.
-pub trait Lookup {
+pub trait Lookup: Send + Sync {
     /// Returns the rank order used by this lookup.
     fn rank_order(&self) -> &'static [Rank];
 
@@ -79,13 +120,17 @@ pub trait Lookup {
     /// Populates the lookup table with hand entries.
     fn add_entries(&mut self);
 
+    /// Returns the weakest entry of the label, if any, so that labels can be compared by the
+    /// strength of their hands, which differs between lookups (e.g. flushes beat full houses in
+    /// short-deck hold'em).
+    fn min_entry(&self, label: Label) -> Option<Entry> {
+        self.entries().values().filter(|entry| entry.label == label).min().copied()
+    }
+
     /// Hashes a collection of ranks into a unique product of primes.
     /// Changed `impl IntoIterator` to `&[Rank]` to make the trait object-safe.
     fn hash_ranks(&self, ranks: &[Rank]) -> BigUint {
-        ranks.iter().map(|r| {
-            let rank_char = r.to_string().chars().next().unwrap();
-            *RANK_MULTIPLIERS.get(&rank_char).unwrap_or(&1)
-        }).product()
+        ranks.iter().map(|&r| rank_multiplier(r)).product()
     }
 
     /// Recursively generates hashes for all possible hands given rank multiplicities.
@@ -101,7 +146,6 @@ pub trait Lookup {
         let mut hashes = Vec::new();
         let (multiplicity, &count) = counter.iter().next_back().unwrap();
         let multiplicity = *multiplicity;
-        let count = count;
         counter.remove(&multiplicity);
 
         for samples in ranks.iter().rev().combinations(count) {
@@ -120,21 +164,33 @@ pub trait Lookup {
     }
 
     /// Gets the lookup key for a set of cards.
-    fn get_key(&self, cards_str: &str) -> Result<(BigUint, bool), String> {
-        let cards = Card::parse_cards(cards_str)?;
-        let ranks: Vec<Rank> = Card::get_ranks(&cards).collect(); // Collect into a Vec
+    fn get_key(&self, cards_str: &str) -> Result<(BigUint, bool), LookupError> {
+        self.get_key_from_cards(&Card::parse_cards(cards_str).map_err(LookupError::InvalidCards)?)
+    }
+
+    /// Gets the lookup key for a set of already parsed cards.
+    fn get_key_from_cards(&self, cards: &[Card]) -> Result<(BigUint, bool), LookupError> {
+        let ranks: Vec<Rank> = Card::get_ranks(cards).collect(); // Collect into a Vec
         let hash = self.hash_ranks(&ranks); // Pass as a slice
-        let suitedness = Card::are_suited(&cards);
+        let suitedness = Card::are_suited(cards);
         Ok((hash, suitedness))
     }
+
+    /// Gets the entry for a given set of cards, or `None` if it's invalid.
+    ///
+    /// Unlike `get_entry_or_none`, this skips the string round trip, which matters in
+    /// enumeration-heavy code.
+    fn get_entry_from_cards(&self, cards: &[Card]) -> Option<Entry> {
+        self.get_key_from_cards(cards).ok().and_then(|key| self.entries().get(&key).cloned())
+    }
     
     /// Gets the entry for a given hand.
-    fn get_entry(&self, cards_str: &str) -> Result<Entry, String> {
+    fn get_entry(&self, cards_str: &str) -> Result<Entry, LookupError> {
         let key = self.get_key(cards_str)?;
         self.entries()
             .get(&key)
             .cloned()
-            .ok_or_else(|| format!("The cards '{}' form an invalid hand.", cards_str))
+            .ok_or_else(|| LookupError::InvalidHand { cards: cards_str.to_string() })
     }
 
     /// Gets the entry for a given hand, or `None` if it's invalid.
@@ -234,7 +290,9 @@ impl StandardLookup { pub fn new() -> Self { let mut lookup = Self { entries: Ha
 impl Default for StandardLookup { fn default() -> Self { Self::new() } }
 
 // --- ShortDeckHoldemLookup ---
+#[cfg(feature = "short-deck")]
 pub struct ShortDeckHoldemLookup { entries: HashMap<(BigUint, bool), Entry> }
+#[cfg(feature = "short-deck")]
 impl Lookup for ShortDeckHoldemLookup {
     fn rank_order(&self) -> &'static [Rank] { &RankOrder::SHORT_DECK_HOLDEM }
     fn entries(&self) -> &HashMap<(BigUint, bool), Entry> { &self.entries }
@@ -252,11 +310,15 @@ impl Lookup for ShortDeckHoldemLookup {
         self.entries = builder.build();
     }
 }
+#[cfg(feature = "short-deck")]
 impl ShortDeckHoldemLookup { pub fn new() -> Self { let mut lookup = Self { entries: HashMap::new() }; lookup.add_entries(); lookup } }
+#[cfg(feature = "short-deck")]
 impl Default for ShortDeckHoldemLookup { fn default() -> Self { Self::new() } }
 
 // --- EightOrBetterLookup ---
+#[cfg(feature = "low-hands")]
 pub struct EightOrBetterLookup { entries: HashMap<(BigUint, bool), Entry> }
+#[cfg(feature = "low-hands")]
 impl Lookup for EightOrBetterLookup {
     fn rank_order(&self) -> &'static [Rank] { &RankOrder::EIGHT_OR_BETTER_LOW }
     fn entries(&self) -> &HashMap<(BigUint, bool), Entry> { &self.entries }
@@ -266,11 +328,15 @@ impl Lookup for EightOrBetterLookup {
         self.entries = builder.build();
     }
 }
+#[cfg(feature = "low-hands")]
 impl EightOrBetterLookup { pub fn new() -> Self { let mut lookup = Self { entries: HashMap::new() }; lookup.add_entries(); lookup } }
+#[cfg(feature = "low-hands")]
 impl Default for EightOrBetterLookup { fn default() -> Self { Self::new() } }
 
 // --- RegularLookup ---
+#[cfg(feature = "low-hands")]
 pub struct RegularLookup { entries: HashMap<(BigUint, bool), Entry> }
+#[cfg(feature = "low-hands")]
 impl Lookup for RegularLookup {
     fn rank_order(&self) -> &'static [Rank] { &RankOrder::REGULAR }
     fn entries(&self) -> &HashMap<(BigUint, bool), Entry> { &self.entries }
@@ -285,11 +351,15 @@ impl Lookup for RegularLookup {
         self.entries = builder.build();
     }
 }
+#[cfg(feature = "low-hands")]
 impl RegularLookup { pub fn new() -> Self { let mut lookup = Self { entries: HashMap::new() }; lookup.add_entries(); lookup } }
+#[cfg(feature = "low-hands")]
 impl Default for RegularLookup { fn default() -> Self { Self::new() } }
 
 // --- BadugiLookup ---
+#[cfg(feature = "badugi")]
 pub struct BadugiLookup { entries: HashMap<(BigUint, bool), Entry> }
+#[cfg(feature = "badugi")]
 impl Lookup for BadugiLookup {
     fn rank_order(&self) -> &'static [Rank] { &RankOrder::REGULAR }
     fn entries(&self) -> &HashMap<(BigUint, bool), Entry> { &self.entries }
@@ -300,23 +370,26 @@ impl Lookup for BadugiLookup {
         }
         self.entries = builder.build();
     }
-    // Override get_key for Badugi-specific validation
-    fn get_key(&self, cards_str: &str) -> Result<(BigUint, bool), String> {
-        let cards = Card::parse_cards(cards_str)?;
-        if !Card::are_rainbow(&cards) {
-            return Err("Badugi hands must be rainbow".to_string());
+    // Override the key derivation for Badugi-specific validation
+    fn get_key_from_cards(&self, cards: &[Card]) -> Result<(BigUint, bool), LookupError> {
+        if !Card::are_rainbow(cards) {
+            return Err(LookupError::NotRainbow);
         }
-        let ranks: Vec<Rank> = Card::get_ranks(&cards).collect();
+        let ranks: Vec<Rank> = Card::get_ranks(cards).collect();
         let hash = self.hash_ranks(&ranks);
-        let suitedness = Card::are_suited(&cards);
+        let suitedness = Card::are_suited(cards);
         Ok((hash, suitedness))
     }
 }
+#[cfg(feature = "badugi")]
 impl BadugiLookup { pub fn new() -> Self { let mut lookup = Self { entries: HashMap::new() }; lookup.add_entries(); lookup } }
+#[cfg(feature = "badugi")]
 impl Default for BadugiLookup { fn default() -> Self { Self::new() } }
 
 // --- StandardBadugiLookup ---
+#[cfg(feature = "badugi")]
 pub struct StandardBadugiLookup { entries: HashMap<(BigUint, bool), Entry> }
+#[cfg(feature = "badugi")]
 impl Lookup for StandardBadugiLookup {
     fn rank_order(&self) -> &'static [Rank] { &RankOrder::STANDARD }
     fn entries(&self) -> &HashMap<(BigUint, bool), Entry> { &self.entries }
@@ -327,22 +400,25 @@ impl Lookup for StandardBadugiLookup {
         }
         self.entries = builder.build();
     }
-    fn get_key(&self, cards_str: &str) -> Result<(BigUint, bool), String> {
-        let cards = Card::parse_cards(cards_str)?;
-        if !Card::are_rainbow(&cards) {
-            return Err("Badugi hands must be rainbow".to_string());
+    fn get_key_from_cards(&self, cards: &[Card]) -> Result<(BigUint, bool), LookupError> {
+        if !Card::are_rainbow(cards) {
+            return Err(LookupError::NotRainbow);
         }
-        let ranks: Vec<Rank> = Card::get_ranks(&cards).collect();
+        let ranks: Vec<Rank> = Card::get_ranks(cards).collect();
         let hash = self.hash_ranks(&ranks);
-        let suitedness = Card::are_suited(&cards);
+        let suitedness = Card::are_suited(cards);
         Ok((hash, suitedness))
     }
 }
+#[cfg(feature = "badugi")]
 impl StandardBadugiLookup { pub fn new() -> Self { let mut lookup = Self { entries: HashMap::new() }; lookup.add_entries(); lookup } }
+#[cfg(feature = "badugi")]
 impl Default for StandardBadugiLookup { fn default() -> Self { Self::new() } }
 
 // --- KuhnPokerLookup ---
+#[cfg(feature = "kuhn")]
 pub struct KuhnPokerLookup { entries: HashMap<(BigUint, bool), Entry> }
+#[cfg(feature = "kuhn")]
 impl Lookup for KuhnPokerLookup {
     fn rank_order(&self) -> &'static [Rank] { &RankOrder::KUHN_POKER }
     fn entries(&self) -> &HashMap<(BigUint, bool), Entry> { &self.entries }
@@ -352,5 +428,7 @@ impl Lookup for KuhnPokerLookup {
         self.entries = builder.build();
     }
 }
+#[cfg(feature = "kuhn")]
 impl KuhnPokerLookup { pub fn new() -> Self { let mut lookup = Self { entries: HashMap::new() }; lookup.add_entries(); lookup } }
+#[cfg(feature = "kuhn")]
 impl Default for KuhnPokerLookup { fn default() -> Self { Self::new() } }
\ No newline at end of file
@@ -1,9 +1,17 @@
 use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use pokerkit::dataset::{Agent, Generator, Variant};
+use pokerkit::evaluators::{differential_test, evaluate_by_lookup, StandardEvaluator};
+use pokerkit::lookups::StandardLookup;
+use pokerkit::utilities::{to_amount, Deck};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
 use pokerkit::games::NoLimitTexasHoldem;
-use pokerkit::state::{Automation, Mode, State};
+use pokerkit::state::{AntePlacement, Automation, BuildError, Mode, State};
 
 /// Creates a new no-limit Texas Hold'em game state.
-fn create_nolimit(n_players: usize) -> Result<State, String> {
+fn create_nolimit(n_players: usize) -> Result<State, BuildError> {
     let automations = vec![
         Automation::AntePosting,
         Automation::BetCollection,
@@ -16,12 +24,12 @@ fn create_nolimit(n_players: usize) -> Result<State, String> {
     ];
 
     let mut blinds = BTreeMap::new();
-    blinds.insert(0, 4); // Small blind
-    blinds.insert(1, 8); // Big blind
+    blinds.insert(0, to_amount(4)); // Small blind
+    blinds.insert(1, to_amount(8)); // Big blind
 
     let mut starting_stacks = BTreeMap::new();
     for i in 0..n_players {
-        starting_stacks.insert(i, 800);
+        starting_stacks.insert(i, to_amount(800));
     }
     
     let antes = BTreeMap::new();
@@ -30,15 +38,129 @@ fn create_nolimit(n_players: usize) -> Result<State, String> {
         &automations,
         true,
         antes,
+        AntePlacement::PerPlayer,
         blinds,
-        8,
+        to_amount(8),
         starting_stacks,
         n_players,
         Mode::CashGame,
     )
 }
 
+/// Simulates hands and writes their decisions as a dataset.
+///
+/// Usage: `pokerkit generate --variant nlhe --agents random,tight --hands 1000 --seed 0 --threads 4
+/// --output hands.csv`. With the `parquet` feature, outputs ending in `.parquet` are written as
+/// Parquet files.
+fn generate(args: &[String]) -> Result<(), String> {
+    let mut variant = Variant::NoLimitTexasHoldem;
+    let mut agents = vec![Agent::Random; 2];
+    let mut hand_count = 1000;
+    let mut seed = 0;
+    let mut thread_count = 1;
+    let mut starting_stack = to_amount(200);
+    let mut big_blind = to_amount(2);
+    let mut output = "hands.csv".to_string();
+
+    for pair in args.chunks(2) {
+        let value = pair.get(1).ok_or(format!("Missing value for {}", pair[0]))?;
+        match pair[0].as_str() {
+            "--variant" => variant = value.parse()?,
+            "--agents" => agents = value.split(',').map(str::parse).collect::<Result<_, _>>()?,
+            "--hands" => hand_count = value.parse().map_err(|_| "Invalid hand count")?,
+            "--seed" => seed = value.parse().map_err(|_| "Invalid seed")?,
+            "--threads" => thread_count = value.parse().map_err(|_| "Invalid thread count")?,
+            "--stack" => starting_stack = value.parse().map_err(|_| "Invalid stack")?,
+            "--big-blind" => big_blind = value.parse().map_err(|_| "Invalid big blind")?,
+            "--output" => output = value.clone(),
+            option => return Err(format!("Unknown option: {}", option)),
+        }
+    }
+
+    let generator = Generator::new(variant, agents)
+        .starting_stack(starting_stack)
+        .big_blind(big_blind)
+        .seed(seed)
+        .thread_count(thread_count);
+    let results = generator.generate(hand_count);
+    let hand_ids: Vec<String> = (0..hand_count).map(|i| format!("{}-{}", seed, i)).collect();
+    let hands: Vec<(&str, &State)> = hand_ids.iter()
+        .zip(&results)
+        .filter_map(|(hand_id, result)| result.as_ref().ok().map(|state| (hand_id.as_str(), state)))
+        .collect();
+
+    if hands.len() < results.len() {
+        eprintln!("Skipped {} of {} hands that could not be played to completion.", results.len() - hands.len(), results.len());
+    }
+
+    #[cfg(feature = "parquet")]
+    if output.ends_with(".parquet") {
+        let file = fs::File::create(&output).map_err(|e| e.to_string())?;
+        let mut writer = pokerkit::export::parquet::DecisionWriter::new(file)?;
+        writer.write_hands(hands)?;
+        writer.close()?;
+        return Ok(());
+    }
+
+    fs::write(&output, pokerkit::export::decisions_to_csv(hands)).map_err(|e| e.to_string())
+}
+
+/// Compares the fast evaluator against the lookups on random 5, 6, and 7-card hands.
+///
+/// Usage: `pokerkit compare-evaluators --comparisons 100000 --seed 0`.
+fn compare_evaluators(args: &[String]) -> Result<(), String> {
+    let mut comparison_count = 100_000;
+    let mut seed = 0;
+
+    for pair in args.chunks(2) {
+        let value = pair.get(1).ok_or(format!("Missing value for {}", pair[0]))?;
+        match pair[0].as_str() {
+            "--comparisons" => comparison_count = value.parse().map_err(|_| "Invalid comparison count")?,
+            "--seed" => seed = value.parse().map_err(|_| "Invalid seed")?,
+            option => return Err(format!("Unknown option: {}", option)),
+        }
+    }
+
+    let lookup = StandardLookup::new();
+    let evaluator = StandardEvaluator::new(&lookup);
+    let deck = Deck::standard();
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut consistent = true;
+
+    for card_count in 5..=7 {
+        let report = differential_test(
+            |cards| evaluate_by_lookup(&lookup, cards),
+            |cards| evaluator.evaluate(cards),
+            &deck,
+            card_count,
+            comparison_count,
+            &mut rng,
+        );
+        println!("{} cards: {} disagreements in {} comparisons", card_count, report.disagreements.len(), report.comparison_count);
+        for disagreement in report.disagreements.iter().take(10) {
+            println!("  {:?}", disagreement);
+        }
+        consistent &= report.is_consistent();
+    }
+
+    if consistent { Ok(()) } else { Err("The evaluators disagree.".to_string()) }
+}
+
 fn main() {
+    let args: Vec<String> = env::args().collect();
+    let result = match args.get(1).map(String::as_str) {
+        Some("generate") => Some(generate(&args[2..])),
+        Some("compare-evaluators") => Some(compare_evaluators(&args[2..])),
+        _ => None,
+    };
+    if let Some(result) = result {
+        if let Err(e) = result {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+
     println!("Creating a 6-player No-Limit Texas Hold'em game...");
 
     match create_nolimit(6) {
@@ -67,7 +189,7 @@ fn main() {
             println!("Next to act: Player {:?}", state.actor_indices.front().unwrap());
             
             println!("\nPlayer 4 raises to 25.");
-            state.complete_bet_or_raise_to(25, None).unwrap();
+            state.complete_bet_or_raise_to(to_amount(25), None).unwrap();
             println!("Stacks: {:?}", state.stacks);
             println!("Bets: {:?}", state.bets);
             println!("Next to act: Player {:?}", state.actor_indices.front().unwrap());
@@ -84,7 +206,7 @@ fn main() {
             println!("Next to act: Player {:?}", state.actor_indices.front().unwrap());
             
             println!("\nPlayer 1 (BB) re-raises to 75.");
-            state.complete_bet_or_raise_to(75, None).unwrap();
+            state.complete_bet_or_raise_to(to_amount(75), None).unwrap();
             println!("Stacks: {:?}", state.stacks);
             println!("Bets: {:?}", state.bets);
             println!("Next to act: Player {:?}", state.actor_indices.front().unwrap());
@@ -100,7 +222,7 @@ fn main() {
             
             println!("\n--- End of Pre-flop Betting ---");
             println!("Final Stacks: {:?}", state.stacks);
-            println!("Total Pot: {}", state.pots().iter().map(|p| p.amount()).sum::<i64>());
+            println!("Total Pot: {}", state.total_pot_amount());
             println!("---------------------------------");
         }
         Err(e) => {
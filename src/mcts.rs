@@ -0,0 +1,145 @@
+//! Implements building blocks for Monte Carlo Tree Search agents.
+//!
+//! These helpers sample legal actions according to a policy, determinize the hidden cards from
+//! the point of view of a player, and play a state out to its terminal payoffs.
+
+use std::collections::HashSet;
+
+use num_traits::Zero;
+use rand::distributions::{Distribution, WeightedIndex};
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+use crate::state::{Action, Operation, State, StateError};
+use crate::utilities::{Amount, Card};
+
+/// A trait for policies guiding the action sampling of rollouts.
+pub trait Policy {
+    /// Returns a non-negative weight for each of the candidate actions.
+    fn weights(&self, state: &State, actions: &[Action]) -> Vec<f64>;
+}
+
+impl<F: Fn(&State, &[Action]) -> Vec<f64>> Policy for F {
+    fn weights(&self, state: &State, actions: &[Action]) -> Vec<f64> {
+        self(state, actions)
+    }
+}
+
+/// A policy that weighs every candidate action equally.
+pub struct UniformPolicy;
+
+impl Policy for UniformPolicy {
+    fn weights(&self, _state: &State, actions: &[Action]) -> Vec<f64> {
+        vec![1.0; actions.len()]
+    }
+}
+
+/// Returns the minimum and maximum amounts the player in turn may complete, bet, or raise to, if
+/// they may do so at all.
+pub fn completion_betting_or_raising_bounds(state: &State) -> Option<(Amount, Amount)> {
+    Some((state.min_completion_betting_or_raising_to_amount()?, state.max_completion_betting_or_raising_to_amount()?))
+}
+
+/// Lists the candidate actions of the player in turn.
+///
+/// Besides folding (only when facing a bet) and checking or calling, or posting the bring-in, the
+/// minimum, pot-sized, and all-in completions, bets, or raises are considered, within the limits of
+/// the betting structure. A player to draw either stands pat or discards every hole card.
+pub fn candidate_actions(state: &State) -> Vec<Action> {
+    if let Some(player_index) = state.stand_pat_or_discarder_index() {
+        return vec![Action::StandPatOrDiscard(Vec::new()), Action::StandPatOrDiscard(state.hole_cards[player_index].clone())];
+    }
+    let Some(checking_or_calling_amount) = state.checking_or_calling_amount() else { return Vec::new() };
+    let mut actions = Vec::new();
+
+    if state.can_post_bring_in() {
+        actions.push(Action::PostBringIn);
+    } else {
+        if checking_or_calling_amount > Amount::zero() {
+            actions.push(Action::Fold);
+        }
+        actions.push(Action::CheckOrCall);
+    }
+
+    if let (Some((min_amount, max_amount)), Some(pot_amount)) = (completion_betting_or_raising_bounds(state), state.pot_raise_to()) {
+        for amount in [min_amount, pot_amount, max_amount] {
+            if !actions.contains(&Action::CompleteBetOrRaiseTo(amount)) {
+                actions.push(Action::CompleteBetOrRaiseTo(amount));
+            }
+        }
+    }
+
+    actions
+}
+
+/// Samples one of the candidate actions according to the policy weights.
+pub fn sample_action<R: Rng + ?Sized>(state: &State, policy: &dyn Policy, rng: &mut R) -> Option<Action> {
+    let actions = candidate_actions(state);
+    let distribution = WeightedIndex::new(policy.weights(state, &actions)).ok()?;
+    Some(actions[distribution.sample(rng)].clone())
+}
+
+/// Returns a copy of the state whose hidden cards are resampled from the point of view of the
+/// player.
+///
+/// Cards the player cannot see (the face-down hole cards of others, burned and mucked cards, and
+/// the remaining deck) are shuffled together and redistributed, so the copy is consistent with
+/// everything the player has observed.
+pub fn determinize<R: Rng + ?Sized>(state: &State, player_index: usize, rng: &mut R) -> State {
+    let mut state = state.clone();
+    let shown_cards: HashSet<Card> = state.operations.iter()
+        .filter_map(|operation| match operation {
+            Operation::HoleCardsShowingOrMucking(op) => Some(op.hole_cards.iter().cloned()),
+            _ => None,
+        })
+        .flatten()
+        .collect();
+
+    let mut unseen_cards: Vec<Card> = state.deck_cards.iter().cloned().collect();
+    unseen_cards.extend(state.burn_cards.iter().cloned());
+    unseen_cards.extend(state.mucked_cards.iter().filter(|card| !shown_cards.contains(card)).cloned());
+    for i in (0..state.player_count).filter(|&i| i != player_index) {
+        for (card, &status) in state.hole_cards[i].iter().zip(&state.hole_card_statuses[i]) {
+            if !status {
+                unseen_cards.push(*card);
+            }
+        }
+    }
+    unseen_cards.shuffle(rng);
+
+    let mut unseen_cards = unseen_cards.into_iter();
+    for i in (0..state.player_count).filter(|&i| i != player_index) {
+        for (card, &status) in state.hole_cards[i].iter_mut().zip(&state.hole_card_statuses[i]) {
+            if !status {
+                *card = unseen_cards.next().unwrap();
+            }
+        }
+    }
+    for card in state.burn_cards.iter_mut() {
+        *card = unseen_cards.next().unwrap();
+    }
+    for card in state.mucked_cards.iter_mut().filter(|card| !shown_cards.contains(card)) {
+        *card = unseen_cards.next().unwrap();
+    }
+    state.deck_cards = unseen_cards.collect();
+
+    state
+}
+
+/// Returns the payoffs of each player if the state is terminal.
+pub fn terminal_payoffs(state: &State) -> Option<Vec<Amount>> {
+    if state.status { None } else { Some(state.payoffs.clone()) }
+}
+
+/// Plays the state out to completion with actions sampled from the policy and returns the
+/// terminal payoffs.
+///
+/// Every non-player operation must be automated, as the rollout only makes player decisions.
+pub fn rollout<R: Rng + ?Sized>(state: &mut State, policy: &dyn Policy, rng: &mut R) -> Result<Vec<Amount>, StateError> {
+    while state.status {
+        let action = sample_action(state, policy, rng).ok_or(StateError::UnattendedOperation)?;
+        state.apply(action)?;
+    }
+
+    Ok(state.payoffs.clone())
+}
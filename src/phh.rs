@@ -0,0 +1,146 @@
+//! Implements the poker hand history (PHH) action notation.
+//!
+//! Actions are written as an actor followed by a command and its arguments, e.g. `p3 cbr 25`,
+//! `d dh p1 AcKd`, or `d db 7h8h9h`. Players are numbered from 1 and the dealer is `d`.
+//! Anything after a `#` is a comment.
+//!
+//! Operations format back to this notation, so the log of a state round-trips to a compact list
+//! of actions. Operations without a notation of their own (e.g. ante posting or bet collection)
+//! are implied by the game and format as comments.
+
+use std::fmt;
+
+use crate::state::{Action, Operation, State};
+use crate::utilities::Card;
+
+fn join_cards(cards: &[Card]) -> String {
+    cards.iter().map(|card| card.to_string()).collect()
+}
+
+/// The performer of an action.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Actor {
+    Dealer,
+    Player(usize),
+}
+
+impl Actor {
+    fn from_phh_str(s: &str) -> Result<Self, String> {
+        match s {
+            "d" => Ok(Actor::Dealer),
+            _ => s.strip_prefix('p')
+                .and_then(|number| number.parse::<usize>().ok())
+                .filter(|&number| number >= 1)
+                .map(|number| Actor::Player(number - 1))
+                .ok_or_else(|| format!("Invalid actor: '{}'", s)),
+        }
+    }
+}
+
+impl Action {
+    /// Parses an action in PHH notation, along with its actor.
+    pub fn from_phh_str(s: &str) -> Result<(Actor, Action), String> {
+        let s = s.split('#').next().unwrap_or_default();
+        let tokens: Vec<&str> = s.split_whitespace().collect();
+        let (actor, command, arguments) = match tokens.as_slice() {
+            [actor, command, arguments @ ..] => (Actor::from_phh_str(actor)?, *command, arguments),
+            _ => return Err(format!("Incomplete action: '{}'", s.trim())),
+        };
+
+        let action = match (actor, command, arguments) {
+            (Actor::Player(_), "f", []) => Action::Fold,
+            (Actor::Player(_), "cc", []) => Action::CheckOrCall,
+            (Actor::Player(_), "cbr", [amount]) => Action::CompleteBetOrRaiseTo(
+                amount.parse().map_err(|_| format!("Invalid amount: '{}'", amount))?,
+            ),
+            (Actor::Player(_), "pb", []) => Action::PostBringIn,
+            (Actor::Player(_), "sd", []) => Action::StandPatOrDiscard(Vec::new()),
+            (Actor::Player(_), "sd", [cards]) => Action::StandPatOrDiscard(Card::parse_cards(cards)?),
+            (Actor::Player(_), "sm", []) => Action::ShowOrMuckHoleCards(Some(Vec::new())),
+            (Actor::Player(_), "sm", ["-"]) => Action::ShowOrMuckHoleCards(None),
+            (Actor::Player(_), "sm", [cards]) => Action::ShowOrMuckHoleCards(Some(Card::parse_cards(cards)?)),
+            (Actor::Dealer, "dh", [player, cards]) => match Actor::from_phh_str(player)? {
+                Actor::Player(player_index) => Action::DealHole { player_index, cards: Card::parse_cards(cards)? },
+                Actor::Dealer => return Err("Hole cards must be dealt to a player.".to_string()),
+            },
+            (Actor::Dealer, "db", [cards]) => Action::DealBoard(Card::parse_cards(cards)?),
+            _ => return Err(format!("Invalid action: '{}'", s.trim())),
+        };
+
+        Ok((actor, action))
+    }
+}
+
+impl State {
+    /// Parses an action in PHH notation, checking that its actor may act now.
+    pub fn parse_action(&self, s: &str) -> Result<Action, String> {
+        let (actor, action) = Action::from_phh_str(s)?;
+
+        if let Actor::Player(player_index) = actor {
+            if player_index >= self.player_count {
+                return Err(format!("There is no player {}.", player_index + 1));
+            }
+            let in_turn = match action {
+                Action::StandPatOrDiscard(_) => self.standing_pat_or_discarding_statuses[player_index],
+                Action::ShowOrMuckHoleCards(_) => self.showdown_indices.front() == Some(&player_index),
+                _ => self.actor_indices.front() == Some(&player_index),
+            };
+            if !in_turn {
+                return Err(format!("Player {} is not in turn.", player_index + 1));
+            }
+        }
+
+        Ok(action)
+    }
+}
+
+impl Operation {
+    /// Formats the operation as a PHH action, or returns `None` if it is implied by the game.
+    pub fn to_phh_string(&self) -> Option<String> {
+        let (action, commentary) = match self {
+            Operation::HoleDealing(op) => (format!("d dh p{} {}", op.player_index + 1, join_cards(&op.cards)), &op.commentary),
+            Operation::BoardDealing(op) => (format!("d db {}", join_cards(&op.cards)), &op.commentary),
+            Operation::StandingPatOrDiscarding(op) if op.cards.is_empty() => (format!("p{} sd", op.player_index + 1), &op.commentary),
+            Operation::StandingPatOrDiscarding(op) => (format!("p{} sd {}", op.player_index + 1, join_cards(&op.cards)), &op.commentary),
+            Operation::Folding(op) => (format!("p{} f", op.player_index + 1), &op.commentary),
+            Operation::CheckingOrCalling(op) => (format!("p{} cc", op.player_index + 1), &op.commentary),
+            Operation::BringInPosting(op) => (format!("p{} pb", op.player_index + 1), &op.commentary),
+            Operation::CompletionBettingOrRaisingTo(op) => (format!("p{} cbr {}", op.player_index + 1, op.amount), &op.commentary),
+            Operation::HoleCardsShowingOrMucking(op) if op.hole_cards.is_empty() => (format!("p{} sm -", op.player_index + 1), &op.commentary),
+            Operation::HoleCardsShowingOrMucking(op) => (format!("p{} sm {}", op.player_index + 1, join_cards(&op.hole_cards)), &op.commentary),
+            _ => return None,
+        };
+
+        Some(match commentary {
+            Some(commentary) => format!("{} # {}", action, commentary),
+            None => action,
+        })
+    }
+}
+
+impl fmt::Display for Operation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(action) = self.to_phh_string() {
+            return write!(f, "{}", action);
+        }
+
+        match self {
+            Operation::AntePosting(op) => write!(f, "# p{} posts an ante of {}", op.player_index + 1, op.amount),
+            Operation::BetCollection(op) => write!(f, "# bets of {:?} are collected", op.bets),
+            Operation::BlindOrStraddlePosting(op) => write!(f, "# p{} posts a blind or straddle of {}", op.player_index + 1, op.amount),
+            Operation::CardBurning(op) => write!(f, "# {} is burned", op.card),
+            Operation::RunoutCountSelection(op) => write!(f, "# p{} selects {:?} runouts", op.player_index + 1, op.runout_count),
+            Operation::HandKilling(op) => write!(f, "# the hand of p{} is killed", op.player_index + 1),
+            Operation::ChipsPushing(op) => write!(f, "# pot {} of {:?} is pushed with a rake of {} and a drop of {}", op.pot_index + 1, op.amounts, op.raked_amount, op.dropped_amount),
+            Operation::ChipsPulling(op) => write!(f, "# p{} pulls {}", op.player_index + 1, op.amount),
+            _ => write!(f, "#"),
+        }
+    }
+}
+
+impl State {
+    /// Returns the operations so far as PHH actions, leaving out those implied by the game.
+    pub fn phh_actions(&self) -> Vec<String> {
+        self.operations.iter().filter_map(Operation::to_phh_string).collect()
+    }
+}
@@ -0,0 +1,93 @@
+//! Implements the positional bookkeeping of consecutive hands under the dead button rule.
+//!
+//! The big blind moves forward one occupied seat every hand, the small blind goes to the seat
+//! that was the big blind, and the button to the seat that was the small blind. When players bust
+//! out, the small blind or the button may fall on an empty seat and be dead, so that no one skips
+//! the big blind or posts it twice in a row. Heads up, the button posts the small blind.
+
+use num_traits::Zero;
+use serde::{Deserialize, Serialize};
+
+use crate::utilities::Amount;
+
+/// Returns the first occupied seat after the seat, if any.
+fn next_occupied_index(occupied: &[bool], seat_index: usize) -> Option<usize> {
+    (1..=occupied.len()).map(|i| (seat_index + i) % occupied.len()).find(|&i| occupied[i])
+}
+
+/// The seats of the button and the blinds for a hand.
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Positions {
+    /// The seat of the button, which is dead if empty.
+    pub button_index: usize,
+    /// The seat of the small blind, which is dead if empty.
+    pub small_blind_index: usize,
+    pub big_blind_index: usize,
+}
+
+impl Positions {
+    /// Places the button on the seat and the blinds on the occupied seats after it, or returns
+    /// `None` if fewer than two seats are occupied.
+    pub fn new(button_index: usize, occupied: &[bool]) -> Option<Self> {
+        if occupied.iter().filter(|&&status| status).count() < 2 {
+            return None;
+        }
+        let button_index = if occupied[button_index] { button_index } else { next_occupied_index(occupied, button_index)? };
+        Self::heads_up(occupied, next_occupied_index(occupied, button_index)?).or_else(|| {
+            let small_blind_index = next_occupied_index(occupied, button_index)?;
+            Some(Self { button_index, small_blind_index, big_blind_index: next_occupied_index(occupied, small_blind_index)? })
+        })
+    }
+
+    /// Returns the positions of two players, the one on the button posting the small blind.
+    fn heads_up(occupied: &[bool], big_blind_index: usize) -> Option<Self> {
+        if occupied.iter().filter(|&&status| status).count() != 2 {
+            return None;
+        }
+        let button_index = next_occupied_index(occupied, big_blind_index)?;
+        Some(Self { button_index, small_blind_index: button_index, big_blind_index })
+    }
+
+    /// Moves the big blind forward to the next occupied seat, and the small blind and the button
+    /// after it, or returns `None` if fewer than two seats are occupied.
+    pub fn next(&self, occupied: &[bool]) -> Option<Self> {
+        if occupied.iter().filter(|&&status| status).count() < 2 {
+            return None;
+        }
+        let big_blind_index = next_occupied_index(occupied, self.big_blind_index)?;
+        Self::heads_up(occupied, big_blind_index).or(Some(Self {
+            button_index: self.small_blind_index,
+            small_blind_index: self.big_blind_index,
+            big_blind_index,
+        }))
+    }
+
+    pub fn is_button_dead(&self, occupied: &[bool]) -> bool { !occupied[self.button_index] }
+    pub fn is_small_blind_dead(&self, occupied: &[bool]) -> bool { !occupied[self.small_blind_index] }
+
+    /// Returns the occupied seats in the order of the players of the hand, starting left of the
+    /// button.
+    pub fn seat_indices(&self, occupied: &[bool]) -> Vec<usize> {
+        (1..=occupied.len()).map(|i| (self.button_index + i) % occupied.len()).filter(|&i| occupied[i]).collect()
+    }
+
+    /// Returns the blinds posted by the players of the hand, in the order of
+    /// [`Positions::seat_indices`], leaving out a dead small blind.
+    ///
+    /// Heads up, the blinds are returned as the small and big blind, as the state has the button
+    /// post the small blind by itself.
+    pub fn blinds(&self, occupied: &[bool], small_blind: Amount, big_blind: Amount) -> Vec<Amount> {
+        let seat_indices = self.seat_indices(occupied);
+        if seat_indices.len() == 2 {
+            return vec![small_blind, big_blind];
+        }
+        seat_indices.iter()
+            .map(|&i| match i {
+                i if i == self.big_blind_index => big_blind,
+                i if i == self.small_blind_index => small_blind,
+                _ => Amount::zero(),
+            })
+            .collect()
+    }
+}
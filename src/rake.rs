@@ -0,0 +1,181 @@
+//! Implements the models of the rake taken from the pots.
+//!
+//! A state asks its [`Rake`] for the rake of every pot, main pot first, whenever the pots are
+//! worked out. The models shipped here take nothing ([`NoRake`]), a percentage of each pot up to
+//! a cap ([`PercentageRake`]), a fixed amount per hand ([`FixedDrop`]), or an amount depending on
+//! the size of the pot ([`TieredRake`]). Rake capped or fixed per hand is taken from the main pot
+//! first, and from the side pots only once the main pot is exhausted.
+//!
+//! As the rake is worked out from the state, it may also depend on how far the hand went. A
+//! [`NoFlopNoDrop`] takes no rake from hands that end before the flop, and a [`StreetRake`]
+//! follows a different schedule depending on the street the hand ended on.
+
+use num_traits::Zero;
+
+use crate::state::State;
+use crate::utilities::{scale_amount, Amount};
+
+/// A model of the rake taken from the pots.
+pub trait Rake: Send + Sync {
+    /// Returns the rake taken from each pot, given the amounts of the pots, main pot first.
+    fn rake(&self, state: &State, pot_amounts: &[Amount]) -> Vec<Amount>;
+}
+
+impl<F: Fn(&State, &[Amount]) -> Vec<Amount> + Send + Sync> Rake for F {
+    fn rake(&self, state: &State, pot_amounts: &[Amount]) -> Vec<Amount> {
+        self(state, pot_amounts)
+    }
+}
+
+/// Takes the amount from the pots in order, as much of it as each pot holds.
+fn take_in_order(pot_amounts: &[Amount], mut amount: Amount) -> Vec<Amount> {
+    pot_amounts.iter()
+        .map(|&pot_amount| {
+            let taken = pot_amount.min(amount).max(Amount::zero());
+            amount -= taken;
+            taken
+        })
+        .collect()
+}
+
+/// Takes no rake, the default.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoRake;
+
+impl Rake for NoRake {
+    fn rake(&self, _state: &State, pot_amounts: &[Amount]) -> Vec<Amount> {
+        vec![Amount::zero(); pot_amounts.len()]
+    }
+}
+
+/// Takes a percentage of each pot, rounded to the chip unit, until the total rake reaches the cap,
+/// if any.
+#[derive(Debug, Clone, Copy)]
+pub struct PercentageRake {
+    /// The fraction of each pot taken, e.g. `0.05` for 5%.
+    pub percentage: f64,
+    pub cap: Option<Amount>,
+}
+
+impl PercentageRake {
+    pub fn new(percentage: f64) -> Self {
+        Self { percentage, cap: None }
+    }
+
+    pub fn cap(mut self, cap: Amount) -> Self { self.cap = Some(cap); self }
+}
+
+impl Rake for PercentageRake {
+    fn rake(&self, state: &State, pot_amounts: &[Amount]) -> Vec<Amount> {
+        let mut remaining_cap = self.cap;
+        pot_amounts.iter()
+            .map(|&pot_amount| {
+                let mut raked_amount = scale_amount(pot_amount, self.percentage, state.chip_unit).clamp(Amount::zero(), pot_amount);
+                if let Some(cap) = remaining_cap.as_mut() {
+                    raked_amount = raked_amount.min(*cap);
+                    *cap -= raked_amount;
+                }
+                raked_amount
+            })
+            .collect()
+    }
+}
+
+/// Takes a fixed amount from every hand, or as much of it as the pots hold.
+#[derive(Debug, Clone, Copy)]
+pub struct FixedDrop {
+    pub amount: Amount,
+}
+
+impl FixedDrop {
+    pub fn new(amount: Amount) -> Self {
+        Self { amount }
+    }
+}
+
+impl Rake for FixedDrop {
+    fn rake(&self, _state: &State, pot_amounts: &[Amount]) -> Vec<Amount> {
+        take_in_order(pot_amounts, self.amount)
+    }
+}
+
+/// Takes a fixed amount depending on the total size of the pots, such as 1 chip from pots of at
+/// least 10 and 2 chips from pots of at least 20.
+#[derive(Debug, Clone)]
+pub struct TieredRake {
+    /// The tiers, as the minimum total pot amount and the rake taken from pots at least as large.
+    pub tiers: Vec<(Amount, Amount)>,
+}
+
+impl TieredRake {
+    pub fn new(tiers: Vec<(Amount, Amount)>) -> Self {
+        Self { tiers }
+    }
+
+    /// Returns the rake of the highest tier the total pot amount reaches, if any.
+    pub fn tier_amount(&self, total_pot_amount: Amount) -> Amount {
+        self.tiers.iter()
+            .filter(|&&(threshold, _)| total_pot_amount >= threshold)
+            .max_by_key(|&&(threshold, _)| threshold)
+            .map_or(Amount::zero(), |&(_, amount)| amount)
+    }
+}
+
+impl Rake for TieredRake {
+    fn rake(&self, _state: &State, pot_amounts: &[Amount]) -> Vec<Amount> {
+        take_in_order(pot_amounts, self.tier_amount(pot_amounts.iter().cloned().sum()))
+    }
+}
+
+/// Takes no rake unless the hand reached the flop, the first street dealing board cards, as in
+/// the common "no flop, no drop" policy. Games without such a street are always raked.
+#[derive(Debug, Clone, Copy)]
+pub struct NoFlopNoDrop<R: Rake> {
+    pub rake: R,
+}
+
+impl<R: Rake> NoFlopNoDrop<R> {
+    pub fn new(rake: R) -> Self {
+        Self { rake }
+    }
+}
+
+impl<R: Rake> Rake for NoFlopNoDrop<R> {
+    fn rake(&self, state: &State, pot_amounts: &[Amount]) -> Vec<Amount> {
+        match state.flop_street_index() {
+            Some(street_index) if !state.has_reached_street(street_index) => NoRake.rake(state, pot_amounts),
+            _ => self.rake.rake(state, pot_amounts),
+        }
+    }
+}
+
+/// Takes rake according to the street the hand ended on, e.g. with a lower cap for hands that end
+/// on the flop. Streets after the last one given follow the last schedule.
+pub struct StreetRake {
+    pub rakes: Vec<Box<dyn Rake>>,
+}
+
+impl StreetRake {
+    pub fn new() -> Self {
+        Self { rakes: Vec::new() }
+    }
+
+    /// Adds the schedule of the next street.
+    pub fn street(mut self, rake: impl Rake + 'static) -> Self { self.rakes.push(Box::new(rake)); self }
+}
+
+impl Default for StreetRake {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Rake for StreetRake {
+    fn rake(&self, state: &State, pot_amounts: &[Amount]) -> Vec<Amount> {
+        let street_index = state.street_index.unwrap_or_default().min(self.rakes.len().saturating_sub(1));
+        match self.rakes.get(street_index) {
+            Some(rake) => rake.rake(state, pot_amounts),
+            None => NoRake.rake(state, pot_amounts),
+        }
+    }
+}
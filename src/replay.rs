@@ -0,0 +1,308 @@
+//! Implements the deterministic replay of hands from a seed and the player actions.
+//!
+//! A state built with a seed shuffles its deck identically every time, so recording the seed and
+//! the decisions of the players suffices to reproduce the whole hand, including the dealt cards.
+//! Dealing that is not automated is carried out from the top of the deck while replaying.
+//!
+//! A [`Replayer`] instead steps through a recorded operation log, dealing the recorded cards, so
+//! review tools can walk a hand street by street or jump to the moments of interest.
+
+use crate::state::{Action, Automation, BuildError, Operation, State, StateBuilder, StateError};
+
+/// The reason a hand cannot be replayed.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ReplayError {
+    /// The state to replay the hand on could not be built.
+    Build(BuildError),
+    /// Only states built with a seed can be replayed from the player actions.
+    Unseeded,
+    /// Dealing a pending card failed.
+    Dealing(StateError),
+    /// A player action could not be applied.
+    Action { index: usize, action: Action, error: StateError },
+    /// A recorded operation could not be performed.
+    Operation { index: usize, operation: Box<Operation>, error: StateError },
+    /// The operation cannot be replayed yet.
+    UnsupportedOperation(Box<Operation>),
+    /// The replay performed an operation other than the recorded one.
+    Divergence { index: usize, expected: Box<Operation>, found: Option<Box<Operation>> },
+    /// The replay performed more operations than were recorded.
+    ExtraOperations,
+}
+
+impl std::fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReplayError::Build(error) => write!(f, "{}", error),
+            ReplayError::Unseeded => write!(f, "Only states built with a seed can be replayed."),
+            ReplayError::Dealing(error) => write!(f, "{}", error),
+            ReplayError::Action { index, action, error } => write!(f, "Action {} ({:?}) failed: {}", index, action, error),
+            ReplayError::Operation { index, operation, error } => write!(f, "Operation {} ({:?}) failed: {}", index, operation, error),
+            ReplayError::UnsupportedOperation(operation) => write!(f, "Replaying {:?} is not supported yet", operation),
+            ReplayError::Divergence { index, expected, found } => {
+                write!(f, "The replay diverges at operation {}: expected {:?}, found {:?}", index, expected, found)
+            }
+            ReplayError::ExtraOperations => write!(f, "The replay has more operations than recorded."),
+        }
+    }
+}
+
+impl std::error::Error for ReplayError {}
+
+impl From<BuildError> for ReplayError {
+    fn from(error: BuildError) -> Self { ReplayError::Build(error) }
+}
+
+impl From<ReplayError> for String {
+    fn from(error: ReplayError) -> Self { error.to_string() }
+}
+
+/// Returns the decisions made by the players, in order, including the selections of runouts and
+/// the showing or mucking of hole cards unless the state automates them.
+pub fn player_actions(state: &State) -> Vec<Action> {
+    let runout_count_selection_status = !state.automations.contains(&Automation::RunoutCountSelection);
+    let hole_cards_showing_or_mucking_status = !state.automations.contains(&Automation::HoleCardsShowingOrMucking);
+    state.operations.iter()
+        .filter_map(|operation| match operation {
+            Operation::Folding(_) => Some(Action::Fold),
+            Operation::CheckingOrCalling(_) => Some(Action::CheckOrCall),
+            Operation::BringInPosting(_) => Some(Action::PostBringIn),
+            Operation::CompletionBettingOrRaisingTo(op) => Some(Action::CompleteBetOrRaiseTo(op.amount)),
+            Operation::StandingPatOrDiscarding(op) => Some(Action::StandPatOrDiscard(op.cards.clone())),
+            Operation::RunoutCountSelection(op) if runout_count_selection_status => {
+                Some(Action::SelectRunoutCount { player_index: Some(op.player_index), runout_count: op.runout_count })
+            }
+            Operation::HoleCardsShowingOrMucking(op) if hole_cards_showing_or_mucking_status => {
+                Some(Action::ShowOrMuckHoleCards((!op.hole_cards.is_empty()).then(|| op.hole_cards.clone())))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// Burns or deals the next card awaiting to be dealt, from the top of the deck, and returns
+/// whether there was one.
+fn deal_pending_card(state: &mut State) -> Result<bool, StateError> {
+    if !state.actor_indices.is_empty() {
+        Ok(false)
+    } else if state.card_burning_status {
+        state.burn_card(None, None).map(|_| true)
+    } else if state.hole_dealee_index().is_some() {
+        state.deal_hole(None, None, None).map(|_| true)
+    } else if state.can_deal_board() {
+        state.deal_board(None, None).map(|_| true)
+    } else {
+        Ok(false)
+    }
+}
+
+/// Builds a seeded state and applies the player actions to it.
+pub fn replay(builder: StateBuilder, actions: &[Action]) -> Result<State, ReplayError> {
+    let mut state = builder.build()?;
+    if state.seed.is_none() {
+        return Err(ReplayError::Unseeded);
+    }
+
+    for (i, action) in actions.iter().enumerate() {
+        while deal_pending_card(&mut state).map_err(ReplayError::Dealing)? {}
+        state.apply(action.clone())
+            .map_err(|error| ReplayError::Action { index: i, action: action.clone(), error })?;
+    }
+
+    Ok(state)
+}
+
+/// Verifies that replaying the player actions of the state reproduces its operations exactly.
+///
+/// The builder must be configured as the state was, including its seed.
+pub fn verify_replay(state: &State, builder: StateBuilder) -> Result<(), ReplayError> {
+    let mut replayed_state = replay(builder, &player_actions(state))?;
+    while replayed_state.operations.len() < state.operations.len() && deal_pending_card(&mut replayed_state).map_err(ReplayError::Dealing)? {}
+
+    if let Some(i) = (0..state.operations.len()).find(|&i| state.operations.get(i) != replayed_state.operations.get(i)) {
+        return Err(ReplayError::Divergence {
+            index: i,
+            expected: Box::new(state.operations[i].clone()),
+            found: replayed_state.operations.get(i).cloned().map(Box::new),
+        });
+    }
+    if replayed_state.operations.len() != state.operations.len() {
+        return Err(ReplayError::ExtraOperations);
+    }
+
+    Ok(())
+}
+
+/// Performs the operation on the state, as recorded.
+fn apply_operation(state: &mut State, operation: &Operation) -> Result<(), ReplayError> {
+    let result = match operation.clone() {
+        Operation::AntePosting(op) => state.post_ante(Some(op.player_index), op.commentary).map(|_| ()),
+        Operation::BetCollection(op) => state.collect_bets(op.commentary).map(|_| ()),
+        Operation::BlindOrStraddlePosting(op) if state.can_post_straddle(Some(op.player_index)) => state.post_straddle(Some(op.player_index), op.commentary).map(|_| ()),
+        Operation::BlindOrStraddlePosting(op) => state.post_blind_or_straddle(Some(op.player_index), op.commentary).map(|_| ()),
+        Operation::CardBurning(op) => state.burn_card(Some(op.card), op.commentary).map(|_| ()),
+        Operation::HoleDealing(op) => state.deal_hole(Some(op.cards), Some(op.player_index), op.commentary).map(|_| ()),
+        Operation::BoardDealing(op) => state.deal_board(Some(op.cards), op.commentary).map(|_| ()),
+        Operation::StandingPatOrDiscarding(op) => state.stand_pat_or_discard(op.cards, op.commentary).map(|_| ()),
+        Operation::Folding(op) => state.fold(op.commentary).map(|_| ()),
+        Operation::CheckingOrCalling(op) => state.check_or_call(op.commentary).map(|_| ()),
+        Operation::BringInPosting(op) => state.post_bring_in(op.commentary).map(|_| ()),
+        Operation::CompletionBettingOrRaisingTo(op) => state.complete_bet_or_raise_to(op.amount, op.commentary).map(|_| ()),
+        Operation::RunoutCountSelection(op) => state.select_runout_count(Some(op.player_index), op.runout_count, op.commentary).map(|_| ()),
+        Operation::HoleCardsShowingOrMucking(op) => {
+            let cards = (!op.hole_cards.is_empty()).then_some(op.hole_cards);
+            state.show_or_muck_hole_cards(cards, op.commentary).map(|_| ())
+        }
+        Operation::HandKilling(op) => state.kill_hand(Some(op.player_index), op.commentary).map(|_| ()),
+        Operation::ChipsPushing(op) => state.push_chips(op.commentary).map(|_| ()),
+        Operation::ChipsPulling(op) => state.pull_chips(Some(op.player_index), op.commentary).map(|_| ()),
+        operation => return Err(ReplayError::UnsupportedOperation(Box::new(operation))),
+    };
+    let index = state.operations.len();
+    result.map_err(|error| ReplayError::Operation { index, operation: Box::new(operation.clone()), error })
+}
+
+/// Steps a fresh state through a recorded operation log.
+///
+/// Operations the state carries out by itself through its automations are not performed again,
+/// but they must match the recorded ones.
+pub struct Replayer {
+    state: State,
+    operations: Vec<Operation>,
+}
+
+impl Replayer {
+    /// Creates a replayer of the operations, starting from the state the builder builds.
+    ///
+    /// The builder must be configured as the recorded state was, including its seed if dealing
+    /// is automated.
+    pub fn new(builder: StateBuilder, operations: Vec<Operation>) -> Result<Self, ReplayError> {
+        let replayer = Replayer { state: builder.build()?, operations };
+        replayer.check()?;
+        Ok(replayer)
+    }
+
+    /// Returns the reconstructed state at the current position.
+    pub fn state(&self) -> &State {
+        &self.state
+    }
+
+    /// Returns the next recorded operation yet to be replayed.
+    pub fn next_operation(&self) -> Option<&Operation> {
+        self.operations.get(self.state.operations.len())
+    }
+
+    fn check(&self) -> Result<(), ReplayError> {
+        let operations = &self.state.operations;
+        match operations.iter().zip(self.operations.iter()).position(|(actual, expected)| actual != expected) {
+            Some(i) => Err(ReplayError::Divergence { index: i, expected: Box::new(self.operations[i].clone()), found: Some(Box::new(operations[i].clone())) }),
+            None if operations.len() > self.operations.len() => Err(ReplayError::ExtraOperations),
+            None => Ok(()),
+        }
+    }
+
+    /// Replays the next operation, along with the automated ones that follow, and returns
+    /// whether there was one.
+    pub fn step(&mut self) -> Result<bool, ReplayError> {
+        let Some(operation) = self.next_operation().cloned() else { return Ok(false) };
+        apply_operation(&mut self.state, &operation)?;
+        self.check()?;
+        Ok(true)
+    }
+
+    /// Replays operations for as long as the next one satisfies the predicate.
+    pub fn advance_while(&mut self, predicate: impl Fn(&Operation) -> bool) -> Result<&State, ReplayError> {
+        while self.next_operation().is_some_and(&predicate) {
+            self.step()?;
+        }
+        Ok(&self.state)
+    }
+
+    /// Replays up to, but not including, the first fold, check, call, bet, or raise.
+    pub fn skip_to_first_voluntary_action(&mut self) -> Result<&State, ReplayError> {
+        self.advance_while(|operation| !matches!(operation,
+            Operation::Folding(_) | Operation::CheckingOrCalling(_) | Operation::CompletionBettingOrRaisingTo(_)))
+    }
+
+    /// Replays up to, but not including, the showing or mucking of hole cards or the pushing of
+    /// chips.
+    pub fn fast_forward_to_showdown(&mut self) -> Result<&State, ReplayError> {
+        self.advance_while(|operation| !matches!(operation,
+            Operation::HoleCardsShowingOrMucking(_) | Operation::ChipsPushing(_)))
+    }
+
+    /// Returns an iterator over the states after each replayed operation.
+    pub fn steps(self) -> Steps {
+        Steps { replayer: self }
+    }
+
+    /// Returns an iterator over the states as each street begins, starting from the current
+    /// one, followed by the final state.
+    pub fn streets(self) -> Streets {
+        Streets { replayer: self, started: false }
+    }
+}
+
+impl State {
+    /// Rebuilds a hand from its operation log, dealing the recorded cards and checking that
+    /// every operation is legal at its point in the hand.
+    ///
+    /// The builder must be configured as the recorded state was, including its seed if dealing
+    /// is automated.
+    pub fn from_operations(builder: StateBuilder, operations: &[Operation]) -> Result<State, ReplayError> {
+        let mut replayer = Replayer::new(builder, operations.to_vec())?;
+        while replayer.step()? {}
+        Ok(replayer.state)
+    }
+}
+
+/// An iterator over the states after each replayed operation.
+pub struct Steps {
+    replayer: Replayer,
+}
+
+impl Iterator for Steps {
+    type Item = Result<State, ReplayError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.replayer.step() {
+            Ok(true) => Some(Ok(self.replayer.state.clone())),
+            Ok(false) => None,
+            Err(e) => {
+                self.replayer.operations.truncate(self.replayer.state.operations.len());
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+/// An iterator over the states as each street begins, followed by the final state.
+pub struct Streets {
+    replayer: Replayer,
+    started: bool,
+}
+
+impl Iterator for Streets {
+    type Item = Result<State, ReplayError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if !self.started {
+            self.started = true;
+            return Some(Ok(self.replayer.state.clone()));
+        }
+
+        let street_index = self.replayer.state.street_index;
+        let mut stepped = false;
+        loop {
+            match self.replayer.step() {
+                Ok(true) if self.replayer.state.street_index == street_index => stepped = true,
+                Ok(true) => return Some(Ok(self.replayer.state.clone())),
+                Ok(false) => return Some(Ok(self.replayer.state.clone())).filter(|_| stepped),
+                Err(e) => {
+                    self.replayer.operations.truncate(self.replayer.state.operations.len());
+                    return Some(Err(e));
+                }
+            }
+        }
+    }
+}
@@ -1,21 +1,27 @@
 // Implements the core poker state machine and related data structures.
 
-use std::collections::{BTreeMap, HashSet, VecDeque};
-use std::fmt;
-
-use crate::hands::{Hand, HandType};
-use crate::lookups::{Label, Lookup};
-use crate::utilities::{
-    clean_values, div_mod, max_or_none, min_or_none, rake, shuffled, sign, Card, Deck, RankOrder,
-    Suit,
-};
-use itertools::Itertools;
-use rand::seq::SliceRandom;
-use rand::thread_rng;
-use strum_macros::{Display, EnumString};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+
+use num_traits::{ToPrimitive, Zero};
+use rand::rngs::StdRng;
+use rand::{RngCore, SeedableRng};
+
+use crate::clock::EXPIRY_COMMENTARY;
+use crate::dealing::{DeckError, DeckSource};
+use crate::hands::{EvaluatorSpec, Evaluators, HandType, LookupEvaluator};
+use crate::kill::Kill;
+use crate::lookups::{Entry, Lookup};
+use crate::rake::{NoRake, Rake};
+use crate::utilities::{canonicalize_suits, default_chip_unit, div_mod, scale_amount, shuffled, shuffled_with, to_amount, Amount, Card, Deck, RankOrder, RawValues};
+use serde::{Deserialize, Serialize};
+use strum::IntoEnumIterator;
+use strum_macros::{Display, EnumIter, EnumString};
 
 // Enums defining game parameters
-#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumString, Display)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumString, Display, Serialize, Deserialize)]
 pub enum BettingStructure {
     #[strum(serialize = "Fixed-limit")]
     FixedLimit,
@@ -25,7 +31,8 @@ pub enum BettingStructure {
     NoLimit,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumString, Display)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumString, Display, Serialize, Deserialize)]
 pub enum Opening {
     Position,
     LowCard,
@@ -34,7 +41,8 @@ pub enum Opening {
     HighHand,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EnumString, Display)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EnumString, EnumIter, Display, Serialize, Deserialize)]
 pub enum Automation {
     AntePosting,
     BetCollection,
@@ -43,28 +51,48 @@ pub enum Automation {
     HoleDealing,
     BoardDealing,
     RunoutCountSelection,
+    /// Burns and deals the cards of the remaining streets once every active player but at most
+    /// one is all in, even if burning and dealing are not otherwise automated.
+    AllInRunout,
     HoleCardsShowingOrMucking,
     HandKilling,
     ChipsPushing,
     ChipsPulling,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumString, Display)]
+/// An automation of the betting decisions of a single seat, e.g. for bots or absent players.
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EnumString, Display, Serialize, Deserialize)]
+pub enum SeatAutomation {
+    /// Folds, even when checking is possible.
+    Fold,
+    /// Checks when possible and folds otherwise.
+    CheckOrFold,
+    /// Always checks or calls.
+    CheckOrCall,
+}
+
+/// The setting of the game, which decides how hands all in are exposed.
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumString, Display, Serialize, Deserialize)]
 pub enum Mode {
+    /// Hands still in are turned face up once the betting is closed by players all in.
     Tournament,
+    /// Showing is optional, even for players all in.
     #[strum(serialize = "Cash-game")]
     CashGame,
 }
 
 /// Represents a single street (betting round) in a poker game.
-#[derive(Debug, Clone)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Street {
     pub card_burning_status: bool,
     pub hole_dealing_statuses: Vec<bool>,
     pub board_dealing_count: usize,
     pub draw_status: bool,
     pub opening: Opening,
-    pub min_completion_betting_or_raising_amount: i64,
+    pub min_completion_betting_or_raising_amount: Amount,
     pub max_completion_betting_or_raising_count: Option<usize>,
 }
 
@@ -75,14 +103,14 @@ impl Street {
         board_dealing_count: usize,
         draw_status: bool,
         opening: Opening,
-        min_completion_betting_or_raising_amount: i64,
+        min_completion_betting_or_raising_amount: Amount,
         max_completion_betting_or_raising_count: Option<usize>,
-    ) -> Result<Self, String> {
+    ) -> Result<Self, BuildError> {
         if !hole_dealing_statuses.is_empty() && draw_status {
-            return Err("Only one of hole dealing or drawing is permitted.".to_string());
+            return Err(BuildError::DealingAndDrawing);
         }
-        if min_completion_betting_or_raising_amount <= 0 {
-            return Err("Non-positive minimum bet/raise amount supplied.".to_string());
+        if min_completion_betting_or_raising_amount <= Amount::zero() {
+            return Err(BuildError::NonPositiveMinBet);
         }
         Ok(Self {
             card_burning_status,
@@ -96,22 +124,157 @@ impl Street {
     }
 }
 
+/// A callback invoked by the state machine.
+pub type Hook = Arc<dyn Fn(&mut State) + Send + Sync>;
+
+/// A source of the current time, stamped on every operation when set.
+pub type ClockSource = Arc<dyn Fn() -> SystemTime + Send + Sync>;
+
+/// Optional callbacks invoked at certain points of a hand, so that house rules (e.g. paying to
+/// see the flop or progressive antes) can be implemented without patching the state machine.
+///
+/// The street in question is available as `state.street_index` when a hook is invoked.
+#[derive(Clone, Default)]
+pub struct Hooks {
+    /// Invoked when a street begins, before any of its cards are dealt.
+    pub street_begin: Option<Hook>,
+    /// Invoked when the betting of a street ends, before the bets are collected.
+    pub street_end: Option<Hook>,
+    /// Invoked before the pots are awarded.
+    pub before_awarding: Option<Hook>,
+}
+
+impl Hooks {
+    pub fn on_street_begin(mut self, hook: impl Fn(&mut State) + Send + Sync + 'static) -> Self { self.street_begin = Some(Arc::new(hook)); self }
+    pub fn on_street_end(mut self, hook: impl Fn(&mut State) + Send + Sync + 'static) -> Self { self.street_end = Some(Arc::new(hook)); self }
+    pub fn on_before_awarding(mut self, hook: impl Fn(&mut State) + Send + Sync + 'static) -> Self { self.before_awarding = Some(Arc::new(hook)); self }
+}
+
+/// How the blinds and the action are arranged when only two players are dealt in.
+///
+/// By default, the button (the last seat) posts the small blind and acts first before the flop,
+/// as in both cash games and tournaments under the usual rules.
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HeadsUpConvention {
+    /// Whether the button posts the small blind (and the small blind's ante), rather than the
+    /// big blind.
+    pub button_posts_small_blind: bool,
+    /// Whether the small blind acts first before the flop, rather than the big blind.
+    pub small_blind_acts_first: bool,
+}
+
+impl Default for HeadsUpConvention {
+    fn default() -> Self {
+        Self { button_posts_small_blind: true, small_blind_acts_first: true }
+    }
+}
+
+/// Who posts the antes.
+///
+/// Under a big blind or button ante, the single poster pays the largest of the antes given, and
+/// the ante is dead money in the main pot, so a poster all in for less is not entitled to win it
+/// from each opponent. A short big blind posts the blind before the ante.
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum AntePlacement {
+    /// Every player posts their own ante.
+    #[default]
+    PerPlayer,
+    BigBlind,
+    Button,
+}
+
+/// Who receives the odd chips of a pot portion split unevenly between several winners.
+///
+/// The odd chips of a pot split between boards or between the high and the low hands go to the
+/// first board and to the high hand regardless.
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum OddChipRule {
+    /// The winner first in position, i.e. closest to the left of the button in games with blinds.
+    #[default]
+    Position,
+    /// The winner holding the highest card, by rank and then by suit from clubs up to spades, as
+    /// in stud games.
+    HighCard,
+}
+
+/// The most each player may wager, after which they are treated as all in, as in cap games.
+///
+/// The chips a player holds beyond the cap are set aside, and returned to their stack when the
+/// hand ends.
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BettingCap {
+    /// The most each player may wager over the whole hand, forced bets included.
+    Hand(Amount),
+    /// The most each player may wager on each street, with forced bets counted in the first.
+    Street(Amount),
+}
+
+impl BettingCap {
+    pub fn amount(&self) -> Amount {
+        match *self {
+            BettingCap::Hand(amount) | BettingCap::Street(amount) => amount,
+        }
+    }
+}
+
+/// A completion, bet, or raise, to the amount.
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RaiseTo {
+    pub player_index: usize,
+    pub amount: Amount,
+}
+
+/// A jackpot (e.g. bad-beat) drop: a fixed amount taken from the main pot, alongside the rake,
+/// once the pot exceeds a threshold.
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JackpotDrop {
+    pub threshold: Amount,
+    pub amount: Amount,
+}
+
 /// Represents a pot or a side pot.
 #[derive(Debug, Clone)]
 pub struct Pot {
-    pub raked_amount: i64,
-    pub unraked_amount: i64,
+    pub raked_amount: Amount,
+    /// The amount dropped into the jackpot.
+    pub dropped_amount: Amount,
+    pub unraked_amount: Amount,
     pub player_indices: Vec<usize>,
 }
 
 impl Pot {
-    pub fn amount(&self) -> i64 {
-        self.raked_amount + self.unraked_amount
+    pub fn amount(&self) -> Amount {
+        self.raked_amount + self.dropped_amount + self.unraked_amount
+    }
+}
+
+/// The discrepancies between the configured deck and the cards found across the state.
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CardAudit {
+    /// The cards found more often than the deck holds them, each listed once per extra copy.
+    pub duplicate_cards: Vec<Card>,
+    /// The cards of the deck found nowhere, each listed once per lost copy.
+    pub missing_cards: Vec<Card>,
+    /// The cards found that are not part of the deck at all.
+    pub foreign_cards: Vec<Card>,
+}
+
+impl CardAudit {
+    pub fn is_clean(&self) -> bool {
+        self.duplicate_cards.is_empty() && self.missing_cards.is_empty() && self.foreign_cards.is_empty()
     }
 }
 
 // Represents all possible operations within a game state.
-#[derive(Debug, Clone)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum Operation {
     AntePosting(AntePosting),
     BetCollection(BetCollection),
@@ -132,42 +295,405 @@ pub enum Operation {
     NoOperation(NoOperation),
 }
 
-#[derive(Debug, Clone)] pub struct AntePosting { pub player_index: usize, pub amount: i64, pub commentary: Option<String> }
-#[derive(Debug, Clone)] pub struct BetCollection { pub bets: Vec<i64>, pub commentary: Option<String> }
-#[derive(Debug, Clone)] pub struct BlindOrStraddlePosting { pub player_index: usize, pub amount: i64, pub commentary: Option<String> }
-#[derive(Debug, Clone)] pub struct CardBurning { pub card: Card, pub commentary: Option<String> }
-#[derive(Debug, Clone)] pub struct HoleDealing { pub player_index: usize, pub cards: Vec<Card>, pub statuses: Vec<bool>, pub commentary: Option<String> }
-#[derive(Debug, Clone)] pub struct BoardDealing { pub cards: Vec<Card>, pub commentary: Option<String> }
-#[derive(Debug, Clone)] pub struct StandingPatOrDiscarding { pub player_index: usize, pub cards: Vec<Card>, pub commentary: Option<String> }
-#[derive(Debug, Clone)] pub struct Folding { pub player_index: usize, pub commentary: Option<String> }
-#[derive(Debug, Clone)] pub struct CheckingOrCalling { pub player_index: usize, pub amount: i64, pub commentary: Option<String> }
-#[derive(Debug, Clone)] pub struct BringInPosting { pub player_index: usize, pub amount: i64, pub commentary: Option<String> }
-#[derive(Debug, Clone)] pub struct CompletionBettingOrRaisingTo { pub player_index: usize, pub amount: i64, pub commentary: Option<String> }
-#[derive(Debug, Clone)] pub struct RunoutCountSelection { pub player_index: usize, pub runout_count: Option<usize>, pub commentary: Option<String> }
-#[derive(Debug, Clone)] pub struct HoleCardsShowingOrMucking { pub player_index: usize, pub hole_cards: Vec<Card>, pub commentary: Option<String> }
-#[derive(Debug, Clone)] pub struct HandKilling { pub player_index: usize, pub commentary: Option<String> }
-#[derive(Debug, Clone)] pub struct ChipsPushing { pub amounts: Vec<i64>, pub pot_index: usize, pub board_index: Option<usize>, pub hand_type_index: Option<usize>, pub commentary: Option<String> }
-#[derive(Debug, Clone)] pub struct ChipsPulling { pub player_index: usize, pub amount: i64, pub commentary: Option<String> }
-#[derive(Debug, Clone)] pub struct NoOperation { pub commentary: Option<String> }
+impl Operation {
+    /// Returns the commentary of the operation for reading or replacing.
+    pub fn commentary_mut(&mut self) -> &mut Option<String> {
+        match self {
+            Operation::AntePosting(op) => &mut op.commentary,
+            Operation::BetCollection(op) => &mut op.commentary,
+            Operation::BlindOrStraddlePosting(op) => &mut op.commentary,
+            Operation::CardBurning(op) => &mut op.commentary,
+            Operation::HoleDealing(op) => &mut op.commentary,
+            Operation::BoardDealing(op) => &mut op.commentary,
+            Operation::StandingPatOrDiscarding(op) => &mut op.commentary,
+            Operation::Folding(op) => &mut op.commentary,
+            Operation::CheckingOrCalling(op) => &mut op.commentary,
+            Operation::BringInPosting(op) => &mut op.commentary,
+            Operation::CompletionBettingOrRaisingTo(op) => &mut op.commentary,
+            Operation::RunoutCountSelection(op) => &mut op.commentary,
+            Operation::HoleCardsShowingOrMucking(op) => &mut op.commentary,
+            Operation::HandKilling(op) => &mut op.commentary,
+            Operation::ChipsPushing(op) => &mut op.commentary,
+            Operation::ChipsPulling(op) => &mut op.commentary,
+            Operation::NoOperation(op) => &mut op.commentary,
+        }
+    }
+}
+
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)] pub struct AntePosting { pub player_index: usize, pub amount: Amount, pub commentary: Option<String> }
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)] pub struct BetCollection { pub bets: Vec<Amount>, pub commentary: Option<String> }
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)] pub struct BlindOrStraddlePosting { pub player_index: usize, pub amount: Amount, pub commentary: Option<String> }
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)] pub struct CardBurning { pub card: Card, pub commentary: Option<String> }
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)] pub struct HoleDealing { pub player_index: usize, pub cards: Vec<Card>, pub statuses: Vec<bool>, pub commentary: Option<String> }
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)] pub struct BoardDealing { pub cards: Vec<Card>, pub commentary: Option<String> }
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)] pub struct StandingPatOrDiscarding { pub player_index: usize, pub cards: Vec<Card>, pub commentary: Option<String> }
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)] pub struct Folding { pub player_index: usize, pub commentary: Option<String> }
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)] pub struct CheckingOrCalling { pub player_index: usize, pub amount: Amount, pub commentary: Option<String> }
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)] pub struct BringInPosting { pub player_index: usize, pub amount: Amount, pub commentary: Option<String> }
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)] pub struct CompletionBettingOrRaisingTo { pub player_index: usize, pub amount: Amount, pub commentary: Option<String> }
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)] pub struct RunoutCountSelection { pub player_index: usize, pub runout_count: Option<usize>, pub commentary: Option<String> }
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)] pub struct HoleCardsShowingOrMucking { pub player_index: usize, pub hole_cards: Vec<Card>, pub commentary: Option<String> }
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)] pub struct HandKilling { pub player_index: usize, pub commentary: Option<String> }
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)] pub struct ChipsPushing { pub amounts: Vec<Amount>, pub pot_index: usize, pub board_index: Option<usize>, pub hand_type_index: Option<usize>, pub raked_amount: Amount, pub dropped_amount: Amount, pub commentary: Option<String> }
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)] pub struct ChipsPulling { pub player_index: usize, pub amount: Amount, pub commentary: Option<String> }
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)] pub struct NoOperation { pub commentary: Option<String> }
+
+/// The reason an operation cannot be carried out, so callers can match on the cause of a
+/// failure.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StateError {
+    /// The operation is not part of the current phase of the hand.
+    UnavailableOperation { operation: &'static str },
+    /// The player is not the one, or not among those, to carry out the operation.
+    NotYourTurn { player_index: usize },
+    /// No player is in turn to act.
+    NoActor,
+    /// The bring-in must be posted or completed before anything else.
+    BringInPending,
+    /// The player acted since the last full raise, so they may only call or fold.
+    ActionNotReopened,
+    /// The street's cap on completions, bets, or raises has been reached.
+    CapReached { count: usize },
+    AboveStack { amount: Amount, all_in_amount: Amount },
+    AboveMaximum { amount: Amount, max_amount: Amount, betting_structure: BettingStructure },
+    NotAboveBet { amount: Amount, max_bet: Amount },
+    BelowMinimum { amount: Amount, min_amount: Amount },
+    /// The card is not in the deck, not a hole card of the player, or given twice.
+    InvalidCard { card: Card },
+    InvalidCardCount { count: usize, expected: usize },
+    NotEnoughCards { count: usize, available: usize },
+    /// The card must be burned before the board is dealt.
+    BurnPending,
+    /// Cards cannot be dealt before every player has stood pat or discarded.
+    DiscardsPending,
+    InvalidRunoutCount { runout_count: usize },
+    /// The hand was turned face up all in at a tournament, so it cannot be mucked.
+    HandExposed,
+    /// The deck source failed to supply the cards.
+    DeckSource(DeckError),
+    UndoDisabled,
+    NothingToUndo,
+    /// The hand awaits an operation that cannot be carried out unattended.
+    UnattendedOperation,
+    /// The player in turn still has time to act.
+    TimeRemaining { remaining: Duration },
+}
+
+impl StateError {
+    /// Returns the error for an operation that cannot be carried out, blaming the player if one
+    /// was named.
+    fn unavailable(operation: &'static str, player_index: Option<usize>) -> Self {
+        match player_index {
+            Some(player_index) => StateError::NotYourTurn { player_index },
+            None => StateError::UnavailableOperation { operation },
+        }
+    }
+}
+
+impl std::fmt::Display for StateError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StateError::UnavailableOperation { operation } => write!(f, "There is no {} to be done", operation),
+            StateError::NotYourTurn { player_index } => write!(f, "Player {} is not in turn", player_index),
+            StateError::NoActor => write!(f, "There is no player to act."),
+            StateError::BringInPending => write!(f, "The bring-in must be posted or completed first"),
+            StateError::ActionNotReopened => write!(f, "The action was not reopened to the player by a full raise"),
+            StateError::CapReached { count } => write!(f, "The street is capped at {} completions, bets, or raises", count),
+            StateError::AboveStack { amount, all_in_amount } => {
+                write!(f, "The amount {} exceeds the stack of the player, who can be all in for {}", amount, all_in_amount)
+            }
+            StateError::AboveMaximum { amount, max_amount, betting_structure } => {
+                write!(f, "The amount {} exceeds the {} maximum of {}", amount, betting_structure.to_string().to_lowercase(), max_amount)
+            }
+            StateError::NotAboveBet { amount, max_bet } => write!(f, "The amount {} does not exceed the bet of {} to call", amount, max_bet),
+            StateError::BelowMinimum { amount, min_amount } => write!(f, "The amount {} is below the minimum of {}", amount, min_amount),
+            StateError::InvalidCard { card } => write!(f, "The card {} is not available to the operation", card),
+            StateError::InvalidCardCount { count, expected } => write!(f, "{} cards were given instead of at most {}", count, expected),
+            StateError::NotEnoughCards { count, available } => write!(f, "Only {} cards are left to draw {} from", available, count),
+            StateError::BurnPending => write!(f, "The card must be burned before the board is dealt"),
+            StateError::DiscardsPending => write!(f, "Cards cannot be dealt before every player has stood pat or discarded"),
+            StateError::InvalidRunoutCount { runout_count } => write!(f, "The runout count {} is invalid", runout_count),
+            StateError::HandExposed => write!(f, "The hand was exposed all in and cannot be mucked"),
+            StateError::DeckSource(error) => write!(f, "The deck source failed: {}", error),
+            StateError::UndoDisabled => write!(f, "Undoing is not enabled for this state"),
+            StateError::NothingToUndo => write!(f, "There is no operation to undo"),
+            StateError::UnattendedOperation => write!(f, "The hand awaits an operation that cannot be simulated."),
+            StateError::TimeRemaining { remaining } => write!(f, "The player in turn has {:?} left to act", remaining),
+        }
+    }
+}
+
+impl std::error::Error for StateError {}
+
+impl From<StateError> for String {
+    fn from(error: StateError) -> Self { error.to_string() }
+}
+
+/// The reason a street or a state cannot be built as configured.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BuildError {
+    /// A street can deal hole cards or let the players draw, but not both.
+    DealingAndDrawing,
+    /// The minimum completion, bet, or raise of a street is not positive.
+    NonPositiveMinBet,
+    PlayerCount { player_count: usize },
+    NoStreets,
+    NoBoards,
+    NonPositiveBettingCap,
+    NonPositiveChipUnit,
+    /// Fewer than two players are dealt in once those sitting out are left out.
+    DealtPlayerCount { dealt_player_count: usize },
+    /// An evaluator or lookup was given for a hand type the game does not have.
+    EvaluatorIndex { index: usize, hand_type_count: usize },
+    /// The lookup of the hand type requires a disabled feature.
+    UnavailableLookup(HandType),
+    /// The killer is not one of the players dealt in.
+    InvalidKiller { player_index: usize },
+    /// The snapshot could not be deserialized.
+    InvalidSnapshot(String),
+}
+
+impl std::fmt::Display for BuildError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BuildError::DealingAndDrawing => write!(f, "Only one of hole dealing or drawing is permitted."),
+            BuildError::NonPositiveMinBet => write!(f, "Non-positive minimum bet/raise amount supplied."),
+            BuildError::PlayerCount { player_count } => write!(f, "Player count must be at least 2, not {}", player_count),
+            BuildError::NoStreets => write!(f, "Streets cannot be empty"),
+            BuildError::NoBoards => write!(f, "Starting board count must be at least 1"),
+            BuildError::NonPositiveBettingCap => write!(f, "Betting cap must be positive"),
+            BuildError::NonPositiveChipUnit => write!(f, "Chip unit must be positive"),
+            BuildError::DealtPlayerCount { dealt_player_count } => {
+                write!(f, "At least 2 players must not sit out, but {} are dealt in", dealt_player_count)
+            }
+            BuildError::EvaluatorIndex { index, hand_type_count } => {
+                write!(f, "The evaluator index {} is out of range for {} hand types", index, hand_type_count)
+            }
+            BuildError::UnavailableLookup(hand_type) => write!(f, "The lookup of {:?} requires a disabled feature", hand_type),
+            BuildError::InvalidKiller { player_index } => write!(f, "The killer {} must be one of the players dealt in", player_index),
+            BuildError::InvalidSnapshot(error) => write!(f, "The snapshot is invalid: {}", error),
+        }
+    }
+}
+
+impl std::error::Error for BuildError {}
+
+impl From<BuildError> for String {
+    fn from(error: BuildError) -> Self { error.to_string() }
+}
+
+/// An action that the player in turn or the dealer can take.
+///
+/// Every operation of the state has an action, so [`State::apply`] is the single entry point
+/// for bots, servers, and serializers alike. A player index of `None` lets the state pick the
+/// player next in line, as the corresponding methods do.
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Action {
+    Fold,
+    CheckOrCall,
+    CompleteBetOrRaiseTo(Amount),
+    PostBringIn,
+    /// Stands pat if no cards are given, and discards the cards otherwise.
+    StandPatOrDiscard(Vec<Card>),
+    /// Mucks if `None`, shows every hole card if empty, and shows the cards otherwise.
+    ShowOrMuckHoleCards(Option<Vec<Card>>),
+    DealHole { player_index: usize, cards: Vec<Card> },
+    DealBoard(Vec<Card>),
+    PostAnte(Option<usize>),
+    CollectBets,
+    PostBlindOrStraddle(Option<usize>),
+    PostStraddle(Option<usize>),
+    /// Burns the card, or the top card of the deck if `None`.
+    BurnCard(Option<Card>),
+    /// Selects the number of runouts, or states no preference if `runout_count` is `None`.
+    SelectRunoutCount { player_index: Option<usize>, runout_count: Option<usize> },
+    KillHand(Option<usize>),
+    PushChips,
+    PullChips(Option<usize>),
+}
+
+/// The operation a state awaits next, as returned by [`State::next_step`].
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum NextStep {
+    AwaitingAntePosting(usize),
+    AwaitingBetCollection,
+    AwaitingBlindOrStraddlePosting(usize),
+    AwaitingCardBurning,
+    AwaitingHoleDealing(usize),
+    AwaitingBoardDealing,
+    AwaitingStandingPatOrDiscarding(usize),
+    AwaitingRunoutCountSelection(usize),
+    /// A betting decision of the player: folding, checking or calling, posting the bring-in, or
+    /// completing, betting, or raising.
+    AwaitingAction(usize),
+    AwaitingShowdown(usize),
+    AwaitingHandKilling(usize),
+    AwaitingChipsPushing,
+    AwaitingChipsPulling(usize),
+    HandComplete,
+}
+
+/// The mutable state of a hand, without its configuration, as captured by
+/// [`State::hand_snapshot`].
+///
+/// Restoring a snapshot is much cheaper than replaying the operation log, so solvers can branch
+/// from the same node repeatedly.
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HandSnapshot {
+    pub deck_cards: VecDeque<Card>,
+    pub board_cards: Vec<Vec<Card>>,
+    pub mucked_cards: Vec<Card>,
+    pub burn_cards: Vec<Card>,
+    pub statuses: Vec<bool>,
+    pub bets: Vec<Amount>,
+    pub stacks: Vec<Amount>,
+    /// The chips each player holds beyond the betting cap, out of play until the hand ends.
+    #[serde(default)]
+    pub capped_amounts: Vec<Amount>,
+    pub payoffs: Vec<Amount>,
+    pub hole_cards: Vec<Vec<Card>>,
+    pub hole_card_statuses: Vec<Vec<bool>>,
+    pub discarded_cards: Vec<Vec<Card>>,
+    pub street_index: Option<usize>,
+    /// The total amount committed to the pot when each street began, forced bets included.
+    pub street_pot_amounts: Vec<Amount>,
+    /// The amount each player committed during each street, with forced bets counted in the first.
+    pub street_investments: Vec<Vec<Amount>>,
+    /// The completions, bets, and raises made on each street, in order.
+    pub street_raises: Vec<Vec<RaiseTo>>,
+    /// The players who posted a live straddle, in order.
+    #[serde(default)]
+    pub straddler_indices: Vec<usize>,
+    pub status: bool,
+    pub operations: Arc<Vec<Operation>>,
+    /// The time each operation was applied at, if the state has a clock source.
+    #[serde(default)]
+    pub operation_timestamps: Arc<Vec<SystemTime>>,
+
+    pub ante_posting_statuses: Vec<bool>,
+    pub bet_collection_status: bool,
+    pub blind_or_straddle_posting_statuses: Vec<bool>,
+    pub card_burning_status: bool,
+    pub hole_dealing_statuses: Vec<VecDeque<bool>>,
+    pub board_dealing_counts: Vec<usize>,
+    pub standing_pat_or_discarding_statuses: Vec<bool>,
+    pub actor_indices: VecDeque<usize>,
+    /// When the turn of the player first in the actor queue began, if the state has time banks.
+    #[serde(default)]
+    pub turn_started_at: Option<SystemTime>,
+    /// The time bank left to each player, if the state has time banks.
+    #[serde(default)]
+    pub time_banks: Vec<Duration>,
+    pub opener_index: Option<usize>,
+    pub bring_in_status: bool,
+    pub completion_status: bool,
+    pub completion_betting_or_raising_amount: Amount,
+    pub completion_betting_or_raising_count: usize,
+    /// The players who have acted since the last full completion, bet, or raise.
+    pub acted_player_indices: HashSet<usize>,
+    /// The number of runouts, once selected by the players all in.
+    pub runout_count: Option<usize>,
+    pub runout_count_selector_statuses: Vec<bool>,
+    /// The number of runouts each player selected, if they had a preference.
+    pub runout_count_selections: Vec<Option<usize>>,
+    pub showdown_indices: VecDeque<usize>,
+    pub hand_killing_statuses: Vec<bool>,
+    /// The pushes of the pots yet to be awarded, worked out when chips pushing begins.
+    pub chips_pushing_queue: VecDeque<ChipsPushing>,
+    pub chips_pulling_statuses: Vec<bool>,
+}
+
+fn default_divmod() -> fn(Amount, Amount) -> (Amount, Amount) { div_mod }
+fn default_rake() -> Arc<dyn Rake> { Arc::new(NoRake) }
 
 /// The main struct representing the state of a poker game.
+///
+/// Members that cannot be serialized (custom evaluators, the division function, the rake, the
+/// deck source, the random number generator, the clock source, and the hooks) are skipped, and
+/// [`State::resume`] takes them from a builder.
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct State {
-    // Configuration
+    // Configuration, with the larger immutable members shared between clones
     pub automations: HashSet<Automation>,
-    pub deck: Vec<Card>,
-    pub hand_types: Vec<HandType>,
-    pub streets: Vec<Street>,
+    pub seat_automations: Vec<Option<SeatAutomation>>,
+    pub deck: Arc<[Card]>,
+    pub hand_types: Arc<[HandType]>,
+    /// The specs consulted to evaluate hands at showdown, one per hand type.
+    #[serde(skip)]
+    pub evaluators: Vec<Arc<dyn EvaluatorSpec>>,
+    pub streets: Arc<[Street]>,
     pub betting_structure: BettingStructure,
     pub ante_trimming_status: bool,
-    pub antes: Vec<i64>,
-    pub blinds_or_straddles: Vec<i64>,
-    pub bring_in: i64,
-    pub starting_stacks: Vec<i64>,
+    #[serde(default)]
+    pub ante_placement: AntePlacement,
+    /// Whether unknown cards supplied for dealing are replaced by cards drawn from the deck.
+    pub unknown_card_replacement_status: bool,
+    /// Whether each operation records the state it started from, so that it can be undone.
+    #[serde(default)]
+    pub undo_status: bool,
+    pub antes: Vec<Amount>,
+    pub blinds_or_straddles: Vec<Amount>,
+    pub bring_in: Amount,
+    pub starting_stacks: Vec<Amount>,
     pub player_count: usize,
+    /// Whether each seat is occupied by a player sitting out, who is dealt no cards, posts no
+    /// forced bets, and never acts, but keeps their seat index.
+    #[serde(default)]
+    pub sitting_out_statuses: Vec<bool>,
     pub mode: Mode,
+    pub heads_up_convention: HeadsUpConvention,
+    /// The number of boards dealt, before the runouts selected multiply them.
     pub starting_board_count: usize,
-    pub divmod: fn(i64, i64) -> (i64, i64),
-    pub rake: fn(&State, i64) -> (i64, i64),
+    #[serde(skip, default = "default_divmod")]
+    pub divmod: fn(Amount, Amount) -> (Amount, Amount),
+    /// The smallest amount of chips, to which split pots, rakes, and scaled bets are rounded.
+    #[serde(default = "default_chip_unit")]
+    pub chip_unit: Amount,
+    /// The model of the rake taken from the pots.
+    #[serde(skip, default = "default_rake")]
+    pub rake: Arc<dyn Rake>,
+    #[serde(default)]
+    pub odd_chip_rule: OddChipRule,
+    #[serde(default)]
+    pub betting_cap: Option<BettingCap>,
+    pub jackpot_drop: Option<JackpotDrop>,
+    /// The external source of the dealt cards, if the state does not deal from its own deck.
+    #[serde(skip)]
+    pub deck_source: Option<Arc<Mutex<dyn DeckSource>>>,
+    #[serde(skip)]
+    pub hooks: Hooks,
+    /// The seed the deck was shuffled with, if any.
+    pub seed: Option<u64>,
+    /// The random number generator shuffling the cards, if injected, shared between clones.
+    #[serde(skip)]
+    pub rng: Option<Arc<Mutex<dyn RngCore + Send>>>,
+    /// The source of the operation timestamps, if any.
+    #[serde(skip)]
+    pub clock_source: Option<ClockSource>,
+    /// The time each player has to act before drawing on their time bank, if the turns are timed.
+    #[serde(default)]
+    pub time_per_action: Option<Duration>,
 
     // Game state
     pub deck_cards: VecDeque<Card>,
@@ -175,15 +701,30 @@ pub struct State {
     pub mucked_cards: Vec<Card>,
     pub burn_cards: Vec<Card>,
     pub statuses: Vec<bool>,
-    pub bets: Vec<i64>,
-    pub stacks: Vec<i64>,
-    pub payoffs: Vec<i64>,
+    pub bets: Vec<Amount>,
+    pub stacks: Vec<Amount>,
+    /// The chips each player holds beyond the betting cap, out of play until the hand ends.
+    #[serde(default)]
+    pub capped_amounts: Vec<Amount>,
+    pub payoffs: Vec<Amount>,
     pub hole_cards: Vec<Vec<Card>>,
     pub hole_card_statuses: Vec<Vec<bool>>,
     pub discarded_cards: Vec<Vec<Card>>,
     pub street_index: Option<usize>,
+    /// The total amount committed to the pot when each street began, forced bets included.
+    pub street_pot_amounts: Vec<Amount>,
+    /// The amount each player committed during each street, with forced bets counted in the first.
+    pub street_investments: Vec<Vec<Amount>>,
+    /// The completions, bets, and raises made on each street, in order.
+    pub street_raises: Vec<Vec<RaiseTo>>,
+    /// The players who posted a live straddle, in order.
+    #[serde(default)]
+    pub straddler_indices: Vec<usize>,
     pub status: bool,
-    pub operations: Vec<Operation>,
+    pub operations: Arc<Vec<Operation>>,
+    /// The time each operation was applied at, if the state has a clock source.
+    #[serde(default)]
+    pub operation_timestamps: Arc<Vec<SystemTime>>,
 
     // Phase-specific state
     pub ante_posting_statuses: Vec<bool>,
@@ -194,105 +735,348 @@ pub struct State {
     pub board_dealing_counts: Vec<usize>,
     pub standing_pat_or_discarding_statuses: Vec<bool>,
     pub actor_indices: VecDeque<usize>,
+    /// When the turn of the player first in the actor queue began, if the state has time banks.
+    #[serde(default)]
+    pub turn_started_at: Option<SystemTime>,
+    /// The time bank left to each player, if the state has time banks.
+    #[serde(default)]
+    pub time_banks: Vec<Duration>,
     pub opener_index: Option<usize>,
     pub bring_in_status: bool,
     pub completion_status: bool,
-    pub completion_betting_or_raising_amount: i64,
+    pub completion_betting_or_raising_amount: Amount,
     pub completion_betting_or_raising_count: usize,
+    /// The players who have acted since the last full completion, bet, or raise.
     pub acted_player_indices: HashSet<usize>,
+    /// The number of runouts, once selected by the players all in.
     pub runout_count: Option<usize>,
+    pub runout_count_selector_statuses: Vec<bool>,
+    /// The number of runouts each player selected, if they had a preference.
+    pub runout_count_selections: Vec<Option<usize>>,
     pub showdown_indices: VecDeque<usize>,
+    pub hand_killing_statuses: Vec<bool>,
+    /// The pushes of the pots yet to be awarded, worked out when chips pushing begins.
+    pub chips_pushing_queue: VecDeque<ChipsPushing>,
+    pub chips_pulling_statuses: Vec<bool>,
+
+    #[serde(skip)]
+    hand_cache: HandCache,
+    /// The states the operations started from, most recent last, if undoing is enabled.
+    #[serde(skip)]
+    undo_history: Arc<Vec<HandSnapshot>>,
+}
+
+/// The best hands evaluated so far, keyed by player, board, and hand type.
+#[derive(Default)]
+struct HandCache(Mutex<HashMap<(usize, usize, usize), CachedHand>>);
+
+/// A best hand, along with the hole cards and the board length it was evaluated for.
+#[derive(Clone)]
+struct CachedHand {
+    hole_cards: Vec<Card>,
+    board_length: usize,
+    entry: Option<Entry>,
+}
+
+/// Clones start with an empty cache, which is only a shortcut, rather than copying it.
+impl Clone for HandCache {
+    fn clone(&self) -> Self {
+        Self::default()
+    }
 }
 
+/// An override of the evaluation of a hand type, resolved once the hand types are known.
+#[derive(Clone)]
+enum EvaluatorOverride {
+    Evaluator(Arc<dyn EvaluatorSpec>),
+    Lookup(Arc<dyn Lookup>),
+}
+
+#[derive(Clone)]
 pub struct StateBuilder {
     automations: HashSet<Automation>,
+    seat_automations: BTreeMap<usize, SeatAutomation>,
+    excepted_seat_automation: Option<(usize, SeatAutomation)>,
     deck: Vec<Card>,
     hand_types: Vec<HandType>,
+    evaluators: BTreeMap<usize, EvaluatorOverride>,
+    registry: Option<Evaluators>,
     streets: Vec<Street>,
     betting_structure: BettingStructure,
     ante_trimming_status: bool,
-    raw_antes: BTreeMap<usize, i64>,
-    raw_blinds_or_straddles: BTreeMap<usize, i64>,
-    bring_in: i64,
-    raw_starting_stacks: BTreeMap<usize, i64>,
+    ante_placement: AntePlacement,
+    unknown_card_replacement_status: bool,
+    undo_status: bool,
+    raw_antes: RawValues,
+    raw_blinds_or_straddles: RawValues,
+    bring_in: Amount,
+    raw_starting_stacks: RawValues,
     player_count: usize,
+    sitting_out_indices: BTreeSet<usize>,
     mode: Mode,
+    heads_up_convention: HeadsUpConvention,
     starting_board_count: usize,
-    divmod: fn(i64, i64) -> (i64, i64),
-    rake: fn(&State, i64) -> (i64, i64),
+    divmod: fn(Amount, Amount) -> (Amount, Amount),
+    chip_unit: Amount,
+    rake: Arc<dyn Rake>,
+    odd_chip_rule: OddChipRule,
+    betting_cap: Option<BettingCap>,
+    kill: Option<(usize, Kill)>,
+    jackpot_drop: Option<JackpotDrop>,
+    deck_source: Option<Arc<Mutex<dyn DeckSource>>>,
+    hooks: Hooks,
+    seed: Option<u64>,
+    rng: Option<Arc<Mutex<dyn RngCore + Send>>>,
+    clock_source: Option<ClockSource>,
+    time_per_action: Option<Duration>,
+    time_bank: Duration,
 }
 
 impl StateBuilder {
     pub fn new(player_count: usize) -> Self {
         Self {
             automations: HashSet::new(),
+            seat_automations: BTreeMap::new(),
+            excepted_seat_automation: None,
             deck: Deck::standard(),
             hand_types: vec![HandType::StandardHighHand],
+            evaluators: BTreeMap::new(),
+            registry: None,
             streets: Vec::new(),
             betting_structure: BettingStructure::NoLimit,
             ante_trimming_status: false,
-            raw_antes: BTreeMap::new(),
-            raw_blinds_or_straddles: BTreeMap::new(),
-            bring_in: 0,
-            raw_starting_stacks: BTreeMap::new(),
+            ante_placement: AntePlacement::PerPlayer,
+            unknown_card_replacement_status: false,
+            undo_status: false,
+            raw_antes: RawValues::default(),
+            raw_blinds_or_straddles: RawValues::default(),
+            bring_in: Amount::zero(),
+            raw_starting_stacks: RawValues::default(),
             player_count,
+            sitting_out_indices: BTreeSet::new(),
             mode: Mode::Tournament,
+            heads_up_convention: HeadsUpConvention::default(),
             starting_board_count: 1,
             divmod: div_mod,
-            rake,
+            chip_unit: default_chip_unit(),
+            rake: Arc::new(NoRake),
+            odd_chip_rule: OddChipRule::Position,
+            betting_cap: None,
+            kill: None,
+            jackpot_drop: None,
+            deck_source: None,
+            hooks: Hooks::default(),
+            seed: None,
+            rng: None,
+            clock_source: None,
+            time_per_action: None,
+            time_bank: Duration::ZERO,
         }
     }
 
+    /// Changes the number of players, e.g. when players leave a table between hands.
+    pub fn player_count(mut self, player_count: usize) -> Self { self.player_count = player_count; self }
     pub fn automations(mut self, automations: &[Automation]) -> Self { self.automations = automations.iter().cloned().collect(); self }
+    pub fn seat_automation(mut self, player_index: usize, seat_automation: SeatAutomation) -> Self { self.seat_automations.insert(player_index, seat_automation); self }
+    /// Automates every non-player operation and the decisions of every seat but one, as a
+    /// server hosting a single local player would. The seats are resolved when building, so the
+    /// player count may still change.
+    pub fn automate_all_except(mut self, player_index: usize, seat_automation: SeatAutomation) -> Self {
+        self.automations = Automation::iter().collect();
+        self.seat_automations.clear();
+        self.excepted_seat_automation = Some((player_index, seat_automation));
+        self
+    }
     pub fn deck(mut self, deck: Vec<Card>) -> Self { self.deck = deck; self }
     pub fn hand_types(mut self, hand_types: Vec<HandType>) -> Self { self.hand_types = hand_types; self }
+    /// Overrides the evaluation of the hand type at the index with custom hand semantics.
+    pub fn evaluator(mut self, hand_type_index: usize, evaluator: Arc<dyn EvaluatorSpec>) -> Self { self.evaluators.insert(hand_type_index, EvaluatorOverride::Evaluator(evaluator)); self }
+    /// Overrides the lookup of the hand type at the index, e.g. with a table for a stripped deck.
+    pub fn lookup(mut self, hand_type_index: usize, lookup: Arc<dyn Lookup>) -> Self { self.evaluators.insert(hand_type_index, EvaluatorOverride::Lookup(lookup)); self }
+    /// Evaluates the hand types with the lookups of the registry instead of the shared ones,
+    /// unless an evaluator or lookup overrides them.
+    pub fn evaluators(mut self, evaluators: &Evaluators) -> Self { self.registry = Some(evaluators.clone()); self }
     pub fn streets(mut self, streets: Vec<Street>) -> Self { self.streets = streets; self }
     pub fn betting_structure(mut self, betting_structure: BettingStructure) -> Self { self.betting_structure = betting_structure; self }
     pub fn ante_trimming_status(mut self, ante_trimming_status: bool) -> Self { self.ante_trimming_status = ante_trimming_status; self }
-    pub fn raw_antes(mut self, raw_antes: BTreeMap<usize, i64>) -> Self { self.raw_antes = raw_antes; self }
-    pub fn raw_blinds_or_straddles(mut self, raw_blinds_or_straddles: BTreeMap<usize, i64>) -> Self { self.raw_blinds_or_straddles = raw_blinds_or_straddles; self }
-    pub fn bring_in(mut self, bring_in: i64) -> Self { self.bring_in = bring_in; self }
-    pub fn raw_starting_stacks(mut self, raw_starting_stacks: BTreeMap<usize, i64>) -> Self { self.raw_starting_stacks = raw_starting_stacks; self }
+    pub fn ante_placement(mut self, ante_placement: AntePlacement) -> Self { self.ante_placement = ante_placement; self }
+    /// Replaces unknown cards supplied for dealing with cards drawn from the deck.
+    pub fn unknown_card_replacement_status(mut self, unknown_card_replacement_status: bool) -> Self { self.unknown_card_replacement_status = unknown_card_replacement_status; self }
+    /// Records the state before each operation, so that operations can be undone at the cost of
+    /// a copy of the state per operation.
+    pub fn undo_status(mut self, undo_status: bool) -> Self { self.undo_status = undo_status; self }
+    pub fn raw_antes(mut self, raw_antes: impl Into<RawValues>) -> Self { self.raw_antes = raw_antes.into(); self }
+    pub fn raw_blinds_or_straddles(mut self, raw_blinds_or_straddles: impl Into<RawValues>) -> Self { self.raw_blinds_or_straddles = raw_blinds_or_straddles.into(); self }
+    pub fn bring_in(mut self, bring_in: Amount) -> Self { self.bring_in = bring_in; self }
+    pub fn raw_starting_stacks(mut self, raw_starting_stacks: impl Into<RawValues>) -> Self { self.raw_starting_stacks = raw_starting_stacks.into(); self }
+    /// Keeps the player in their seat without dealing them in.
+    pub fn sit_out(mut self, player_index: usize) -> Self { self.sitting_out_indices.insert(player_index); self }
     pub fn mode(mut self, mode: Mode) -> Self { self.mode = mode; self }
+    pub fn heads_up_convention(mut self, heads_up_convention: HeadsUpConvention) -> Self { self.heads_up_convention = heads_up_convention; self }
+    pub fn seed(mut self, seed: u64) -> Self { self.seed = Some(seed); self }
+    /// Shuffles the cards with the random number generator, taking precedence over the seed, so
+    /// simulations can share one seeded generator across hands.
+    pub fn rng(mut self, rng: impl RngCore + Send + 'static) -> Self { self.rng = Some(Arc::new(Mutex::new(rng))); self }
+    pub fn hooks(mut self, hooks: Hooks) -> Self { self.hooks = hooks; self }
+    /// Stamps every operation with the time read from the clock, e.g. `SystemTime::now`, so hand
+    /// histories can record how long each decision took.
+    pub fn clock_source(mut self, clock_source: impl Fn() -> SystemTime + Send + Sync + 'static) -> Self { self.clock_source = Some(Arc::new(clock_source)); self }
+    /// Times the turns of the players, who each have the time per action and then the same time
+    /// bank to draw on, read from the clock source or the system clock.
+    pub fn time_bank(mut self, time_per_action: Duration, time_bank: Duration) -> Self { self.time_per_action = Some(time_per_action); self.time_bank = time_bank; self }
+    /// Deals the board cards of every street to this many boards, as in double-board games, each
+    /// board being awarded an equal share of every pot.
+    pub fn starting_board_count(mut self, starting_board_count: usize) -> Self { self.starting_board_count = starting_board_count; self }
+    pub fn rake(mut self, rake: impl Rake + 'static) -> Self { self.rake = Arc::new(rake); self }
+    /// Sets the smallest amount of chips, e.g. `0.01` for cents, to which split pots, rakes, and
+    /// scaled bets are rounded. Defaults to a whole chip, or a hundredth with decimal amounts.
+    pub fn chip_unit(mut self, chip_unit: Amount) -> Self { self.chip_unit = chip_unit; self }
+    pub fn odd_chip_rule(mut self, odd_chip_rule: OddChipRule) -> Self { self.odd_chip_rule = odd_chip_rule; self }
+    pub fn betting_cap(mut self, betting_cap: BettingCap) -> Self { self.betting_cap = Some(betting_cap); self }
+    /// Makes the hand a kill pot: the player posts a kill blind of the largest blind times the
+    /// multiplier of the kill, in place of their own blind, and the bets of every street go up by
+    /// the same multiplier.
+    pub fn kill(mut self, player_index: usize, kill: Kill) -> Self { self.kill = Some((player_index, kill)); self }
+    pub fn jackpot_drop(mut self, threshold: Amount, amount: Amount) -> Self { self.jackpot_drop = Some(JackpotDrop { threshold, amount }); self }
+    /// Deals the cards drawn from the source instead of the state's own shuffled deck.
+    pub fn deck_source(mut self, deck_source: impl DeckSource + 'static) -> Self { self.deck_source = Some(Arc::new(Mutex::new(deck_source))); self }
 
-    pub fn build(self) -> Result<State, String> {
-        if self.player_count < 2 { return Err("Player count must be at least 2".to_string()); }
-        if self.streets.is_empty() { return Err("Streets cannot be empty".to_string()); }
-        
-        let antes = clean_values(&self.raw_antes, self.player_count);
-        let blinds_or_straddles = clean_values(&self.raw_blinds_or_straddles, self.player_count);
-        let starting_stacks = clean_values(&self.raw_starting_stacks, self.player_count);
+    /// Moves the largest of the antes to the big blind or the button, if they alone post it.
+    ///
+    /// Antes, like blinds, are indexed by position among the players dealt in, and heads up the
+    /// button may take the place of the small blind.
+    fn place_antes(&self, antes: Vec<Amount>, blinds_or_straddles: &[Amount]) -> Vec<Amount> {
+        let ante = antes.iter().cloned().max().unwrap_or_default();
+        let dealt_player_count = self.dealt_player_count();
+        let poster_index = match self.ante_placement {
+            AntePlacement::PerPlayer => return antes,
+            AntePlacement::BigBlind => blinds_or_straddles.iter().enumerate().max_by_key(|&(i, &b)| (b, i)).map_or(0, |(i, _)| i),
+            AntePlacement::Button if dealt_player_count == 2 && self.heads_up_convention.button_posts_small_blind => 0,
+            AntePlacement::Button => dealt_player_count - 1,
+        };
+        (0..self.player_count).map(|i| if i == poster_index { ante } else { Amount::zero() }).collect()
+    }
+
+    /// Returns the automation of the seat: the one set for it, or that of every seat but the
+    /// excepted one, in that order.
+    fn resolve_seat_automation(&self, player_index: usize) -> Option<SeatAutomation> {
+        self.seat_automations.get(&player_index).cloned().or_else(|| {
+            self.excepted_seat_automation
+                .filter(|&(excepted_index, _)| excepted_index != player_index)
+                .map(|(_, seat_automation)| seat_automation)
+        })
+    }
+
+    /// Returns the evaluator of each of the hand types: the overriding evaluator or lookup, the
+    /// one of the registry, or the default one, in that order.
+    fn resolve_evaluators(&self, hand_types: &[HandType]) -> Result<Vec<Arc<dyn EvaluatorSpec>>, BuildError> {
+        if let Some(&index) = self.evaluators.keys().find(|&&i| i >= hand_types.len()) {
+            return Err(BuildError::EvaluatorIndex { index, hand_type_count: hand_types.len() });
+        }
+        hand_types.iter()
+            .enumerate()
+            .map(|(i, &hand_type)| match self.evaluators.get(&i) {
+                Some(EvaluatorOverride::Evaluator(evaluator)) => Ok(evaluator.clone()),
+                Some(EvaluatorOverride::Lookup(lookup)) => Ok(Arc::new(LookupEvaluator { hand_type, lookup: lookup.clone() }) as Arc<dyn EvaluatorSpec>),
+                None => match self.registry.as_ref().and_then(|registry| registry.evaluator(hand_type)) {
+                    Some(evaluator) => Ok(evaluator),
+                    None if hand_type.is_available() => Ok(Arc::new(hand_type) as Arc<dyn EvaluatorSpec>),
+                    None => Err(BuildError::UnavailableLookup(hand_type)),
+                },
+            })
+            .collect()
+    }
+
+    /// Returns the number of players dealt in, i.e. not sitting out.
+    fn dealt_player_count(&self) -> usize {
+        self.player_count - self.sitting_out_indices.range(..self.player_count).count()
+    }
+
+    pub fn build(self) -> Result<State, BuildError> {
+        if self.player_count < 2 { return Err(BuildError::PlayerCount { player_count: self.player_count }); }
+        if self.streets.is_empty() { return Err(BuildError::NoStreets); }
+        if self.starting_board_count == 0 { return Err(BuildError::NoBoards); }
+        if self.betting_cap.is_some_and(|betting_cap| betting_cap.amount() <= Amount::zero()) { return Err(BuildError::NonPositiveBettingCap); }
+        if self.chip_unit <= Amount::zero() { return Err(BuildError::NonPositiveChipUnit); }
+        if self.dealt_player_count() < 2 { return Err(BuildError::DealtPlayerCount { dealt_player_count: self.dealt_player_count() }); }
+        let evaluators = self.resolve_evaluators(&self.hand_types)?;
+
+        let mut blinds_or_straddles = self.raw_blinds_or_straddles.clean(self.player_count);
+        let mut streets = self.streets.clone();
+        if let Some((player_index, kill)) = self.kill {
+            if player_index >= self.player_count || self.sitting_out_indices.contains(&player_index) { return Err(BuildError::InvalidKiller { player_index }); }
+            for street in &mut streets {
+                street.min_completion_betting_or_raising_amount = scale_amount(street.min_completion_betting_or_raising_amount, kill.multiplier(), self.chip_unit);
+            }
+            let blind = blinds_or_straddles.iter().cloned().max().unwrap_or_default();
+            let position = player_index - self.sitting_out_indices.range(..player_index).count();
+            let blind_index = if self.dealt_player_count() == 2 && self.heads_up_convention.button_posts_small_blind { 1 - position } else { position };
+            blinds_or_straddles[blind_index] = scale_amount(blind, kill.multiplier(), self.chip_unit);
+        }
+        let antes = self.place_antes(self.raw_antes.clean(self.player_count), &blinds_or_straddles);
+        let starting_stacks = self.raw_starting_stacks.clean(self.player_count);
+        let seat_automations = (0..self.player_count).map(|i| self.resolve_seat_automation(i)).collect();
+
+        let street_investments = vec![vec![Amount::zero(); self.player_count]; self.streets.len()];
+        let street_raises = vec![Vec::new(); self.streets.len()];
 
         let mut state = State {
             automations: self.automations,
-            deck: self.deck.clone(),
-            hand_types: self.hand_types,
-            streets: self.streets,
+            seat_automations,
+            deck: self.deck.as_slice().into(),
+            hand_types: self.hand_types.into(),
+            evaluators,
+            streets: streets.into(),
             betting_structure: self.betting_structure,
             ante_trimming_status: self.ante_trimming_status,
+            ante_placement: self.ante_placement,
+            unknown_card_replacement_status: self.unknown_card_replacement_status,
+            undo_status: self.undo_status,
             antes,
             blinds_or_straddles,
             bring_in: self.bring_in,
             starting_stacks: starting_stacks.clone(),
             player_count: self.player_count,
+            sitting_out_statuses: (0..self.player_count).map(|i| self.sitting_out_indices.contains(&i)).collect(),
             mode: self.mode,
+            heads_up_convention: self.heads_up_convention,
             starting_board_count: self.starting_board_count,
             divmod: self.divmod,
+            chip_unit: self.chip_unit,
             rake: self.rake,
-            deck_cards: VecDeque::from(shuffled(&self.deck)),
+            odd_chip_rule: self.odd_chip_rule,
+            betting_cap: self.betting_cap,
+            jackpot_drop: self.jackpot_drop,
+            deck_source: self.deck_source,
+            hooks: self.hooks,
+            seed: self.seed,
+            deck_cards: VecDeque::from(match (&self.rng, self.seed) {
+                (Some(rng), _) => shuffled_with(&self.deck, &mut *rng.lock().unwrap()),
+                (None, Some(seed)) => shuffled_with(&self.deck, &mut StdRng::seed_from_u64(seed)),
+                (None, None) => shuffled(&self.deck),
+            }),
+            rng: self.rng,
+            clock_source: self.clock_source,
+            time_per_action: self.time_per_action,
             board_cards: vec![Vec::new(); self.starting_board_count],
             mucked_cards: Vec::new(),
             burn_cards: Vec::new(),
-            statuses: vec![true; self.player_count],
-            bets: vec![0; self.player_count],
+            statuses: (0..self.player_count).map(|i| !self.sitting_out_indices.contains(&i)).collect(),
+            bets: vec![Amount::zero(); self.player_count],
             stacks: starting_stacks,
-            payoffs: vec![0; self.player_count],
+            capped_amounts: vec![Amount::zero(); self.player_count],
+            payoffs: vec![Amount::zero(); self.player_count],
             hole_cards: vec![Vec::new(); self.player_count],
             hole_card_statuses: vec![Vec::new(); self.player_count],
             discarded_cards: vec![Vec::new(); self.player_count],
             street_index: None,
+            street_pot_amounts: Vec::new(),
+            street_investments,
+            street_raises,
+            straddler_indices: Vec::new(),
             status: true,
-            operations: Vec::new(),
+            operations: Arc::default(),
+            operation_timestamps: Arc::default(),
             ante_posting_statuses: vec![false; self.player_count],
             bet_collection_status: false,
             blind_or_straddle_posting_statuses: vec![false; self.player_count],
@@ -301,14 +1085,23 @@ impl StateBuilder {
             board_dealing_counts: vec![0; self.starting_board_count],
             standing_pat_or_discarding_statuses: vec![false; self.player_count],
             actor_indices: VecDeque::new(),
+            turn_started_at: None,
+            time_banks: if self.time_per_action.is_some() { vec![self.time_bank; self.player_count] } else { Vec::new() },
             opener_index: None,
             bring_in_status: false,
             completion_status: false,
-            completion_betting_or_raising_amount: 0,
+            completion_betting_or_raising_amount: Amount::zero(),
             completion_betting_or_raising_count: 0,
             acted_player_indices: HashSet::new(),
             runout_count: None,
+            runout_count_selector_statuses: vec![false; self.player_count],
+            runout_count_selections: vec![None; self.player_count],
             showdown_indices: VecDeque::new(),
+            hand_killing_statuses: vec![false; self.player_count],
+            chips_pushing_queue: VecDeque::new(),
+            chips_pulling_statuses: vec![false; self.player_count],
+            hand_cache: HandCache::default(),
+            undo_history: Arc::default(),
         };
 
         state.begin();
@@ -319,67 +1112,149 @@ impl StateBuilder {
 
 impl State {
     // Core state machine logic
-    fn begin(&mut self) { self.begin_ante_posting(); }
-    fn end(&mut self) { self.status = false; }
+    fn begin(&mut self) {
+        self.cap_stacks();
+        self.begin_ante_posting();
+    }
+    fn end(&mut self) {
+        for i in 0..self.player_count {
+            self.stacks[i] += std::mem::take(&mut self.capped_amounts[i]);
+        }
+        self.status = false;
+    }
+
+    /// Sets aside the chips of each player beyond the betting cap, if any, along with those set
+    /// aside before.
+    fn cap_stacks(&mut self) {
+        let Some(betting_cap) = self.betting_cap else { return };
+        for i in 0..self.player_count {
+            let total_amount = self.stacks[i] + self.capped_amounts[i];
+            self.stacks[i] = total_amount.min(betting_cap.amount());
+            self.capped_amounts[i] = total_amount - self.stacks[i];
+        }
+    }
     
     // Game flow state transitions
     fn begin_ante_posting(&mut self) { 
         for i in 0..self.player_count {
-            self.ante_posting_statuses[i] = self.get_effective_ante(i) > 0;
+            self.ante_posting_statuses[i] = self.get_effective_ante(i) > Amount::zero();
         }
         self.run_ante_posting_automation();
     }
     fn run_ante_posting_automation(&mut self) {
-        if self.automations.contains(&Automation::AntePosting) {
-            let indices: Vec<usize> = self.ante_poster_indices().collect();
-            for i in indices {
-                self.post_ante(Some(i), None).unwrap();
-            }
-        }
-        if !self.ante_posting_statuses.iter().any(|&s| s) {
+        if self.automations.contains(&Automation::AntePosting) && self.can_post_ante(None) {
+            self.post_ante(None, None).unwrap();
+        } else if !self.can_post_ante(None) {
             self.end_ante_posting();
         }
     }
     fn end_ante_posting(&mut self) { self.begin_bet_collection(); }
 
     fn begin_bet_collection(&mut self) {
-        self.bet_collection_status = self.bets.iter().any(|&b| b > 0);
+        self.bet_collection_status = self.bets.iter().any(|&b| b > Amount::zero());
         self.run_bet_collection_automation();
     }
     fn run_bet_collection_automation(&mut self) {
-        if self.automations.contains(&Automation::BetCollection) && self.bet_collection_status {
+        if self.automations.contains(&Automation::BetCollection) && self.can_collect_bets() {
             self.collect_bets(None).unwrap();
-        }
-        if !self.bet_collection_status {
+        } else if !self.can_collect_bets() {
             self.end_bet_collection();
         }
     }
     fn end_bet_collection(&mut self) {
+        // The forced bets count towards the cap of the first street, set when the hand began.
+        if self.street_index.is_some() && matches!(self.betting_cap, Some(BettingCap::Street(_))) {
+            self.cap_stacks();
+        }
+        if self.mode == Mode::Tournament && self.street_index.is_some() {
+            self.expose_all_in_hands();
+        }
         if self.statuses.iter().filter(|&&s| s).count() <= 1 {
             self.begin_chips_pushing();
         } else if self.street_index.is_none() {
             self.begin_blind_or_straddle_posting();
         } else if self.street_index == Some(self.streets.len() - 1) { // is last street
             self.begin_showdown();
+        } else if self.runout_count.is_none() && self.is_all_in() {
+            self.begin_runout_count_selection();
         } else {
             self.begin_dealing();
         }
     }
 
+    /// Returns whether the betting is over for the rest of the hand, with at most one player with
+    /// chips behind, and board cards still to come.
+    fn is_all_in(&self) -> bool {
+        let street_index = self.street_index.map_or(0, |i| i + 1);
+        self.is_betting_closed() && self.streets[street_index..].iter().any(|street| street.board_dealing_count > 0)
+    }
+
+    /// Returns whether no one is left to bet against, with every active player but at most one
+    /// all in, and that one not facing a bet.
+    pub fn is_betting_closed(&self) -> bool {
+        let active_indices: Vec<usize> = (0..self.player_count).filter(|&i| self.statuses[i]).collect();
+        let max_bet = self.bets.iter().cloned().max().unwrap_or_default();
+        let bettor_indices: Vec<usize> = active_indices.iter().cloned().filter(|&i| self.stacks[i] > Amount::zero()).collect();
+        active_indices.len() > 1 && bettor_indices.len() <= 1 && bettor_indices.iter().all(|&i| self.bets[i] == max_bet)
+    }
+
+    /// Returns whether the dealing operation is automated, either by itself or as part of an
+    /// all-in runout.
+    fn is_dealing_automated(&self, automation: Automation) -> bool {
+        self.automations.contains(&automation) || (self.automations.contains(&Automation::AllInRunout) && self.is_betting_closed())
+    }
+
+    /// Turns every hand still in face up once the betting is closed by players all in, as
+    /// tournaments require.
+    fn expose_all_in_hands(&mut self) {
+        if self.is_betting_closed() {
+            for i in (0..self.player_count).filter(|&i| self.statuses[i]) {
+                self.hole_card_statuses[i].iter_mut().for_each(|status| *status = true);
+            }
+        }
+    }
+
+    /// Returns whether the hand of the player was turned face up all in at a tournament, and so
+    /// cannot be mucked at showdown.
+    fn is_hand_exposed(&self, player_index: usize) -> bool {
+        self.mode == Mode::Tournament && self.hole_card_statuses[player_index].iter().all(|&status| status)
+    }
+
+    fn begin_runout_count_selection(&mut self) {
+        self.runout_count_selector_statuses = self.statuses.clone();
+        self.runout_count_selections = vec![None; self.player_count];
+        self.run_runout_count_selection_automation();
+    }
+    fn run_runout_count_selection_automation(&mut self) {
+        if self.automations.contains(&Automation::RunoutCountSelection) && self.can_select_runout_count(None, None) {
+            self.select_runout_count(None, None, None).unwrap();
+        } else if self.runout_count_selector_index().is_none() {
+            self.end_runout_count_selection();
+        }
+    }
+    /// Settles on the count every player selected, or a single runout if they disagree, and
+    /// copies the board for each runout.
+    fn end_runout_count_selection(&mut self) {
+        let selections: Vec<Option<usize>> = (0..self.player_count).filter(|&i| self.statuses[i]).map(|i| self.runout_count_selections[i]).collect();
+        let runout_count = match selections.first() {
+            Some(&Some(count)) if selections.iter().all(|&selection| selection == Some(count)) => count,
+            _ => 1,
+        };
+        self.runout_count = Some(runout_count);
+        self.board_cards = (0..runout_count).flat_map(|_| self.board_cards.iter().cloned()).collect();
+        self.begin_dealing();
+    }
+
     fn begin_blind_or_straddle_posting(&mut self) {
         for i in 0..self.player_count {
-            self.blind_or_straddle_posting_statuses[i] = self.get_effective_blind_or_straddle(i) > 0;
+            self.blind_or_straddle_posting_statuses[i] = self.get_effective_blind_or_straddle(i) > Amount::zero();
         }
         self.run_blind_or_straddle_posting_automation();
     }
     fn run_blind_or_straddle_posting_automation(&mut self) {
-        if self.automations.contains(&Automation::BlindOrStraddlePosting) {
-            let indices: Vec<usize> = self.blind_or_straddle_poster_indices().collect();
-            for i in indices {
-                self.post_blind_or_straddle(Some(i), None).unwrap();
-            }
-        }
-        if !self.blind_or_straddle_posting_statuses.iter().any(|&s| s) {
+        if self.automations.contains(&Automation::BlindOrStraddlePosting) && self.can_post_blind_or_straddle(None) {
+            self.post_blind_or_straddle(None, None).unwrap();
+        } else if !self.can_post_blind_or_straddle(None) {
             self.end_blind_or_straddle_posting();
         }
     }
@@ -388,18 +1263,36 @@ impl State {
     fn begin_dealing(&mut self) {
         let new_street_index = self.street_index.map_or(0, |i| i + 1);
         self.street_index = Some(new_street_index);
+        self.street_pot_amounts.push(-self.payoffs.iter().sum::<Amount>());
+        self.run_hook(self.hooks.street_begin.clone());
         let street = self.streets[new_street_index].clone();
 
-        self.card_burning_status = street.card_burning_status;
+        let community_card_status = self.is_deck_short(&street);
+        // The burn is skipped if it would leave too few cards for the community cards.
+        self.card_burning_status = street.card_burning_status
+            && !(community_card_status && self.deck_cards.len() <= self.board_cards.len());
         for i in 0..self.player_count {
             if self.statuses[i] {
-                self.hole_dealing_statuses[i].extend(street.hole_dealing_statuses.iter());
+                if !community_card_status {
+                    self.hole_dealing_statuses[i].extend(street.hole_dealing_statuses.iter());
+                }
                 self.standing_pat_or_discarding_statuses[i] = street.draw_status;
             }
         }
-        self.board_dealing_counts = vec![street.board_dealing_count; self.starting_board_count];
+        let board_dealing_count = if community_card_status { 1 } else { street.board_dealing_count };
+        self.board_dealing_counts = vec![board_dealing_count; self.board_cards.len()];
         self.run_dealing_automation();
     }
+    /// Returns whether the deck is too short to deal every player the single hole card of the
+    /// street, as in seven-card stud with eight players, in which case a single community card
+    /// shared by everyone is dealt to the board instead, without a burn if the deck is too short
+    /// for it as well.
+    fn is_deck_short(&self, street: &Street) -> bool {
+        let dealee_count = self.statuses.iter().filter(|&&status| status).count();
+        street.hole_dealing_statuses.len() == 1
+            && street.board_dealing_count == 0
+            && self.deck_cards.len() < dealee_count + street.card_burning_status as usize
+    }
     fn run_dealing_automation(&mut self) {
         let dealing_done = !self.card_burning_status 
             && !self.hole_dealing_statuses.iter().any(|q| !q.is_empty())
@@ -408,30 +1301,46 @@ impl State {
         
         if dealing_done {
             self.end_dealing();
-        } else if self.automations.contains(&Automation::CardBurning) && self.can_burn_card(None) {
+        } else if self.is_dealing_automated(Automation::CardBurning) && self.can_burn_card(None) {
             self.burn_card(None, None).unwrap();
-        } else if self.automations.contains(&Automation::HoleDealing) && self.hole_dealee_index().is_some() {
+        } else if self.is_dealing_automated(Automation::HoleDealing) && self.hole_dealee_index().is_some() {
             while self.hole_dealee_index().is_some() {
                 self.deal_hole(None, None, None).unwrap();
             }
-        } // ... and so on for other dealing automations
+        } else if self.is_dealing_automated(Automation::BoardDealing) && self.can_deal_board() {
+            self.deal_board(None, None).unwrap();
+        }
     }
     fn end_dealing(&mut self) { self.begin_betting(); }
 
     fn begin_betting(&mut self) {
         self.opener_index = None;
         self.acted_player_indices.clear();
-        self.completion_betting_or_raising_amount = 0;
+        self.completion_betting_or_raising_amount = Amount::zero();
         self.completion_betting_or_raising_count = 0;
+        self.bring_in_status = self.street_index == Some(0) && self.bring_in > Amount::zero();
+        self.completion_status = self.bring_in_status;
+
+        // Once the betting is closed, the street is dealt without a betting round.
+        if self.is_betting_closed() {
+            self.bring_in_status = false;
+            self.completion_status = false;
+            self.end_betting();
+            return;
+        }
     
         let street = self.streets[self.street_index.unwrap()].clone();
     
         // Determine the first player to act.
         let opener_index = match street.opening {
             Opening::Position => {
-                if self.street_index == Some(0) { // Pre-flop
-                    let bb_index = self.blinds_or_straddles.iter().rposition(|&b| b > 0).unwrap_or(self.player_count - 1);
-                    let mut current = (bb_index + 1) % self.player_count;
+                if self.street_index == Some(0) && self.dealt_player_count() == 2 { // Heads-up pre-flop
+                    let dealt_indices: Vec<usize> = (0..self.player_count).filter(|&i| !self.is_sitting_out(i)).collect();
+                    let sb_index = if self.heads_up_convention.button_posts_small_blind { 1 } else { 0 };
+                    let first_index = if self.heads_up_convention.small_blind_acts_first { sb_index } else { 1 - sb_index };
+                    if self.statuses[dealt_indices[first_index]] { dealt_indices[first_index] } else { dealt_indices[1 - first_index] }
+                } else if self.street_index == Some(0) { // Pre-flop
+                    let mut current = (self.last_forced_bettor_index() + 1) % self.player_count;
                     // Find the next active player
                     while !self.statuses[current] {
                         current = (current + 1) % self.player_count;
@@ -441,21 +1350,71 @@ impl State {
                     (0..self.player_count).find(|&i| self.statuses[i]).unwrap_or(0)
                 }
             }
-            _ => unimplemented!("Opening type {:?} is not yet implemented", street.opening),
+            Opening::LowCard => self.up_card_opener_index(false),
+            Opening::HighCard => self.up_card_opener_index(true),
+            Opening::LowHand => self.up_hand_opener_index(false),
+            Opening::HighHand => self.up_hand_opener_index(true),
         };
     
         self.opener_index = Some(opener_index);
+        if self.street_index == Some(0) {
+            // A live straddle sets the size of the minimum raise.
+            self.completion_betting_or_raising_amount = self.straddler_indices.iter().map(|&i| self.bets[i]).max().unwrap_or_default();
+        }
     
         // Set up the actor queue.
         self.actor_indices = (0..self.player_count)
             .cycle()
             .skip(opener_index)
             .take(self.player_count)
-            .filter(|&i| self.statuses[i] && self.stacks[i] > 0)
+            .filter(|&i| self.statuses[i] && self.stacks[i] > Amount::zero())
             .collect();
     
         self.run_betting_automation();
     }
+    /// Returns the active player with the lowest face-up hole card, aces high, or the highest,
+    /// aces low, with ties broken by suit from clubs up to spades.
+    fn up_card_opener_index(&self, high_status: bool) -> usize {
+        let rank_order = if high_status { &RankOrder::REGULAR } else { &RankOrder::STANDARD };
+        let keys = (0..self.player_count).filter(|&i| self.statuses[i]).flat_map(|i| {
+            self.hole_cards[i].iter().zip(&self.hole_card_statuses[i])
+                .filter(|(card, &status)| status && !card.is_unknown())
+                .filter_map(move |(card, _)| Some((rank_order.iter().position(|&rank| rank == card.rank)?, card.suit as usize, i)))
+        });
+        let key = if high_status { keys.max() } else { keys.min() };
+
+        key.map_or_else(|| (0..self.player_count).find(|&i| self.statuses[i]).unwrap_or(0), |(_, _, i)| i)
+    }
+
+    /// Returns the active player with the best partial hand of face-up hole cards, or the worst
+    /// if the best is the lowest, as in razz, with ties going to the first player in position.
+    ///
+    /// Partial hands are ranked by their pairs, trips, and quads, then by their ranks in the order
+    /// of the lookup of the first hand type; straights and flushes do not count.
+    fn up_hand_opener_index(&self, high_status: bool) -> usize {
+        let hand_type = self.hand_types[0];
+        let rank_order = if hand_type.is_available() { hand_type.lookup().rank_order() } else { &RankOrder::STANDARD };
+        let keys = (0..self.player_count).filter(|&i| self.statuses[i]).map(|i| {
+            let mut counts: Vec<(usize, usize)> = Vec::new();
+            for (card, _) in self.hole_cards[i].iter().zip(&self.hole_card_statuses[i]).filter(|(card, &status)| status && !card.is_unknown()) {
+                let Some(rank_index) = rank_order.iter().position(|&rank| rank == card.rank) else { continue };
+                match counts.iter_mut().find(|(_, index)| *index == rank_index) {
+                    Some((count, _)) => *count += 1,
+                    None => counts.push((1, rank_index)),
+                }
+            }
+            counts.sort_unstable_by(|a, b| b.cmp(a));
+            (counts, i)
+        });
+        let key = if high_status {
+            keys.max_by(|(a, i), (b, j)| a.cmp(b).then(j.cmp(i)))
+        } else {
+            keys.min_by(|(a, i), (b, j)| a.cmp(b).then(i.cmp(j)))
+        };
+
+        key.map_or(0, |(_, i)| i)
+    }
+
     fn run_betting_automation(&mut self) {
         let active_players: Vec<usize> = (0..self.player_count).filter(|&i| self.statuses[i]).collect();
         if active_players.len() <= 1 {
@@ -463,34 +1422,283 @@ impl State {
             return;
         }
     
-        let max_bet = self.bets.iter().max().cloned().unwrap_or(0);
-        let all_acted = active_players.iter().all(|i| self.acted_player_indices.contains(i));
-        let bets_settled = active_players.iter().all(|&i| self.bets[i] == max_bet || self.stacks[i] == 0);
+        let max_bet = self.bets.iter().max().cloned().unwrap_or_default();
+        let actor_count = active_players.iter().filter(|&&i| self.stacks[i] > Amount::zero()).count();
+        let all_acted = active_players.iter().filter(|&&i| self.stacks[i] > Amount::zero()).all(|i| self.acted_player_indices.contains(i));
+        let bets_settled = active_players.iter().all(|&i| self.bets[i] == max_bet || self.stacks[i] == Amount::zero());
     
-        if all_acted && bets_settled {
+        // Once at most one player has chips behind, there is no one left to bet against.
+        if (all_acted || actor_count <= 1) && bets_settled {
             self.end_betting();
+        } else {
+            self.start_turn();
+            self.run_seat_automation();
+        }
+    }
+
+    fn run_seat_automation(&mut self) {
+        let Some(&player_index) = self.actor_indices.front() else { return };
+        let facing_bet = self.bets.iter().max().is_some_and(|&max_bet| max_bet > self.bets[player_index]);
+
+        match self.seat_automations[player_index] {
+            Some(_) if self.bring_in_status => { self.post_bring_in(None).unwrap(); }
+            Some(SeatAutomation::Fold) => { self.fold(None).unwrap(); }
+            Some(SeatAutomation::CheckOrFold) if facing_bet => { self.fold(None).unwrap(); }
+            Some(SeatAutomation::CheckOrFold) | Some(SeatAutomation::CheckOrCall) => { self.check_or_call(None).unwrap(); }
+            None => {}
         }
     }
+
+    /// Sets or clears the automation of a seat, acting for it at once if it is in turn.
+    pub fn set_seat_automation(&mut self, player_index: usize, seat_automation: Option<SeatAutomation>) {
+        self.seat_automations[player_index] = seat_automation;
+        self.run_seat_automation();
+    }
     
     fn end_betting(&mut self) {
+        self.run_hook(self.hooks.street_end.clone());
         self.actor_indices.clear();
+        self.turn_started_at = None;
         self.begin_bet_collection();
     }
 
-    fn begin_showdown(&mut self) { /* ... */ }
-    fn begin_chips_pushing(&mut self) { /* ... */ }
+    /// Begins the showdown with the last aggressor of the final street, or the first active
+    /// player in position if everyone checked.
+    fn begin_showdown(&mut self) {
+        let first_index = self.street_index.and_then(|i| self.aggressor_index(i))
+            .filter(|&i| self.statuses[i])
+            .or_else(|| (0..self.player_count).find(|&i| self.statuses[i]))
+            .unwrap_or(0);
+        self.showdown_indices = (0..self.player_count)
+            .cycle()
+            .skip(first_index)
+            .take(self.player_count)
+            .filter(|&i| self.statuses[i])
+            .collect();
+        self.run_showdown_automation();
+    }
+    fn run_showdown_automation(&mut self) {
+        if self.automations.contains(&Automation::HoleCardsShowingOrMucking) && self.can_show_or_muck_hole_cards(None) {
+            let player_index = self.showdown_indices[0];
+            let cards = (self.is_hand_live(player_index) || self.is_hand_exposed(player_index)).then(Vec::new);
+            self.show_or_muck_hole_cards(cards, None).unwrap();
+        } else if self.showdown_indices.is_empty() {
+            self.end_showdown();
+        }
+    }
+    fn end_showdown(&mut self) { self.begin_hand_killing(); }
+
+    /// Returns whether the hand of the player could still win a portion of a pot they are in,
+    /// against the hands shown so far.
+    fn is_hand_live(&self, player_index: usize) -> bool {
+        let shown_indices: Vec<usize> = (0..self.player_count)
+            .filter(|&i| i != player_index && self.statuses[i] && !self.showdown_indices.contains(&i))
+            .collect();
+
+        self.pots().iter().filter(|pot| pot.player_indices.contains(&player_index)).any(|pot| {
+            (0..self.board_cards.len()).any(|board_index| (0..self.hand_types.len()).any(|hand_type_index| {
+                let Some(entry) = self.get_hand(player_index, board_index, hand_type_index) else { return false };
+                shown_indices.iter()
+                    .filter(|i| pot.player_indices.contains(i))
+                    .filter_map(|&i| self.get_hand(i, board_index, hand_type_index))
+                    .all(|other| self.evaluators[hand_type_index].compare(&entry, &other).is_ge())
+            }))
+        })
+    }
+
+    fn begin_hand_killing(&mut self) {
+        let winner_indices: HashSet<usize> = self.pots().iter()
+            .flat_map(|pot| self.pot_portions(&pot.player_indices))
+            .flat_map(|(_, _, winner_indices)| winner_indices)
+            .collect();
+        for i in 0..self.player_count {
+            self.hand_killing_statuses[i] = self.statuses[i] && !winner_indices.contains(&i);
+        }
+        self.run_hand_killing_automation();
+    }
+    fn run_hand_killing_automation(&mut self) {
+        if self.automations.contains(&Automation::HandKilling) && self.can_kill_hand(None) {
+            self.kill_hand(None, None).unwrap();
+        } else if !self.can_kill_hand(None) {
+            self.end_hand_killing();
+        }
+    }
+    fn end_hand_killing(&mut self) { self.begin_chips_pushing(); }
+
+    fn begin_chips_pushing(&mut self) {
+        self.run_hook(self.hooks.before_awarding.clone());
+        for (pot_index, pot) in self.pots().into_iter().enumerate() {
+            let portions = self.pot_portions(&pot.player_indices);
+            let portion_amounts = self.portion_amounts(pot.unraked_amount, &portions);
+            for (i, ((board_index, hand_type_index, winner_indices), portion_amount)) in portions.into_iter().zip(portion_amounts).enumerate() {
+                let (winner_share, winner_remainder) = self.split_amount(portion_amount, winner_indices.len());
+                let odd_chip_index = self.odd_chip_winner_index(&winner_indices);
+                let mut amounts = vec![Amount::zero(); self.player_count];
+                for &winner_index in &winner_indices {
+                    amounts[winner_index] = winner_share + if Some(winner_index) == odd_chip_index { winner_remainder } else { Amount::zero() };
+                }
+
+                self.chips_pushing_queue.push_back(ChipsPushing {
+                    amounts,
+                    pot_index,
+                    board_index,
+                    hand_type_index,
+                    raked_amount: if i == 0 { pot.raked_amount } else { Amount::zero() },
+                    dropped_amount: if i == 0 { pot.dropped_amount } else { Amount::zero() },
+                    commentary: None,
+                });
+            }
+        }
+        self.run_chips_pushing_automation();
+    }
+    fn run_chips_pushing_automation(&mut self) {
+        if self.automations.contains(&Automation::ChipsPushing) && self.can_push_chips() {
+            self.push_chips(None).unwrap();
+        } else if !self.can_push_chips() {
+            self.end_chips_pushing();
+        }
+    }
+    fn end_chips_pushing(&mut self) { self.begin_chips_pulling(); }
+
+    fn begin_chips_pulling(&mut self) {
+        for i in 0..self.player_count {
+            self.chips_pulling_statuses[i] = self.bets[i] > Amount::zero();
+        }
+        self.run_chips_pulling_automation();
+    }
+    fn run_chips_pulling_automation(&mut self) {
+        if self.automations.contains(&Automation::ChipsPulling) && self.can_pull_chips(None) {
+            self.pull_chips(None, None).unwrap();
+        } else if !self.can_pull_chips(None) {
+            self.end_chips_pulling();
+        }
+    }
+    fn end_chips_pulling(&mut self) { self.end(); }
+
+    /// Returns the board index, the hand type index, and the winners of each equal portion of a
+    /// pot contested by the players.
+    ///
+    /// A pot left to a single player is theirs without a showdown. Otherwise, each board and hand
+    /// type gets a portion, such as the high and the low halves of hi-lo games, except those no
+    /// player qualifies for (e.g. when no low hand is eight or better).
+    fn pot_portions(&self, player_indices: &[usize]) -> Vec<(Option<usize>, Option<usize>, Vec<usize>)> {
+        let mut portions = Vec::new();
+        if player_indices.len() > 1 {
+            for board_index in 0..self.board_cards.len() {
+                for hand_type_index in 0..self.hand_types.len() {
+                    let evaluator = &self.evaluators[hand_type_index];
+                    let entries: Vec<(usize, Entry)> = player_indices.iter()
+                        .filter_map(|&i| self.get_hand(i, board_index, hand_type_index).map(|entry| (i, entry)))
+                        .collect();
+                    let Some(best_entry) = entries.iter().map(|&(_, entry)| entry).max_by(|entry, other| evaluator.compare(entry, other)) else { continue };
+                    let winner_indices = entries.iter()
+                        .filter(|(_, entry)| evaluator.compare(entry, &best_entry).is_eq())
+                        .map(|&(i, _)| i)
+                        .collect();
+                    portions.push((Some(board_index), Some(hand_type_index), winner_indices));
+                }
+            }
+        }
+        if portions.is_empty() {
+            portions.push((None, None, player_indices.to_vec()));
+        }
+
+        portions
+    }
+
+    /// Splits the amount evenly between the boards of the portions, and the share of each board
+    /// evenly between its portions, so that the share of a portion no player qualifies for goes to
+    /// the other portions of its board. The odd chips go to the first board and portion, i.e. the
+    /// high hand of hi-lo games.
+    fn portion_amounts(&self, amount: Amount, portions: &[(Option<usize>, Option<usize>, Vec<usize>)]) -> Vec<Amount> {
+        let mut board_indices: Vec<Option<usize>> = portions.iter().map(|&(board_index, _, _)| board_index).collect();
+        board_indices.dedup();
+        let (board_share, board_remainder) = self.split_amount(amount, board_indices.len());
+        let mut amounts = Vec::new();
+        for (i, &board_index) in board_indices.iter().enumerate() {
+            let board_amount = board_share + if i == 0 { board_remainder } else { Amount::zero() };
+            let portion_count = portions.iter().filter(|&&(j, _, _)| j == board_index).count();
+            let (share, remainder) = self.split_amount(board_amount, portion_count);
+            amounts.extend((0..portion_count).map(|j| share + if j == 0 { remainder } else { Amount::zero() }));
+        }
+
+        amounts
+    }
+
+    /// Splits the amount into the count of equal shares of whole chip units, and returns the share
+    /// and the odd chips left over.
+    fn split_amount(&self, amount: Amount, count: usize) -> (Amount, Amount) {
+        let (quotient, _) = (self.divmod)(amount / self.chip_unit, to_amount(count));
+        let share = quotient * self.chip_unit;
+        (share, amount - share * to_amount(count))
+    }
+
+    /// Returns the winner of a portion who receives its odd chips under the odd chip rule.
+    fn odd_chip_winner_index(&self, winner_indices: &[usize]) -> Option<usize> {
+        match self.odd_chip_rule {
+            OddChipRule::Position => winner_indices.first().copied(),
+            OddChipRule::HighCard => winner_indices.iter().rev().copied().max_by_key(|&i| {
+                self.hole_cards[i].iter()
+                    .filter_map(|card| Some((RankOrder::STANDARD.iter().position(|&rank| rank == card.rank)?, card.suit as usize)))
+                    .max()
+            }),
+        }
+    }
+
+    fn run_hook(&mut self, hook: Option<Hook>) {
+        if let Some(hook) = hook {
+            hook(self);
+        }
+    }
 
     // Helper methods
-    pub fn get_effective_ante(&self, player_index: usize) -> i64 {
-        let ante = if self.player_count == 2 { self.antes[1 - player_index] } else { self.antes[player_index] };
-        ante.min(self.starting_stacks[player_index])
+    /// Returns the index of the forced bet the player posts, which is their position among the
+    /// players dealt in, so that the blinds move on to the next seats past those sitting out.
+    /// Heads up, it depends on the convention.
+    fn forced_bet_index(&self, player_index: usize) -> usize {
+        let position = (0..player_index).filter(|&i| !self.is_sitting_out(i)).count();
+        if self.dealt_player_count() == 2 && self.heads_up_convention.button_posts_small_blind { 1usize.saturating_sub(position) } else { position }
+    }
+
+    /// Returns the number of players dealt in, i.e. not sitting out.
+    pub fn dealt_player_count(&self) -> usize {
+        (0..self.player_count).filter(|&i| !self.is_sitting_out(i)).count()
+    }
+
+    pub fn is_sitting_out(&self, player_index: usize) -> bool {
+        self.sitting_out_statuses.get(player_index).copied().unwrap_or(false)
+    }
+
+    /// Returns the ante the player posts, which comes after the blind under a big blind ante.
+    pub fn get_effective_ante(&self, player_index: usize) -> Amount {
+        if self.is_sitting_out(player_index) { return Amount::zero(); }
+        let ante = self.antes[self.forced_bet_index(player_index)];
+        let blind = match self.ante_placement {
+            AntePlacement::BigBlind => self.blinds_or_straddles[self.forced_bet_index(player_index)].abs(),
+            _ => Amount::zero(),
+        };
+        ante.min((self.starting_stacks[player_index] - blind).max(Amount::zero()))
     }
     
-    pub fn get_effective_blind_or_straddle(&self, player_index: usize) -> i64 {
-        let blind = if self.player_count == 2 { self.blinds_or_straddles[1 - player_index].abs() } else { self.blinds_or_straddles[player_index].abs() };
+    pub fn get_effective_blind_or_straddle(&self, player_index: usize) -> Amount {
+        if self.is_sitting_out(player_index) { return Amount::zero(); }
+        let blind = self.blinds_or_straddles[self.forced_bet_index(player_index)].abs();
         blind.min(self.starting_stacks[player_index] - self.get_effective_ante(player_index))
     }
 
+    /// Returns the antes posted as dead money, by player, which is the big blind or button ante.
+    fn dead_antes(&self) -> Vec<Amount> {
+        let mut dead_antes = vec![Amount::zero(); self.player_count];
+        if self.ante_placement != AntePlacement::PerPlayer {
+            for operation in self.operations.iter() {
+                if let Operation::AntePosting(op) = operation {
+                    dead_antes[op.player_index] += op.amount;
+                }
+            }
+        }
+        dead_antes
+    }
+
     pub fn ante_poster_indices(&self) -> impl Iterator<Item = usize> + '_ {
         (0..self.player_count).filter(move |&i| self.ante_posting_statuses[i])
     }
@@ -499,70 +1707,254 @@ impl State {
         (0..self.player_count).filter(move |&i| self.blind_or_straddle_posting_statuses[i])
     }
     
+    /// Returns the next player to deal hole cards to, once any pending draw decisions are made.
     pub fn hole_dealee_index(&self) -> Option<usize> {
+        if self.standing_pat_or_discarding_statuses.iter().any(|&status| status) {
+            return None;
+        }
         (0..self.player_count).filter(|&i| !self.hole_dealing_statuses[i].is_empty()).max_by_key(|&i| (self.hole_dealing_statuses[i].len(), -(i as isize)))
     }
 
-    // Public API for actions
-    pub fn post_ante(&mut self, player_index: Option<usize>, commentary: Option<String>) -> Result<AntePosting, String> {
-        let player_index = player_index.unwrap_or_else(|| self.ante_poster_indices().next().unwrap());
-        if !self.ante_posting_statuses[player_index] { return Err("Player cannot post ante".to_string()); }
-        
-        let amount = self.get_effective_ante(player_index);
-        self.ante_posting_statuses[player_index] = false;
-        self.bets[player_index] = amount;
+    /// Moves chips from the player's stack into the pot and records them against the street.
+    fn invest(&mut self, player_index: usize, amount: Amount) {
         self.stacks[player_index] -= amount;
         self.payoffs[player_index] -= amount;
-        
+        self.street_investments[self.street_index.unwrap_or(0)][player_index] += amount;
+    }
+
+    // Public API for actions
+    pub fn can_post_ante(&self, player_index: Option<usize>) -> bool {
+        match player_index {
+            Some(i) => self.ante_posting_statuses.get(i).cloned().unwrap_or(false),
+            None => self.ante_poster_indices().next().is_some(),
+        }
+    }
+
+    pub fn post_ante(&mut self, player_index: Option<usize>, commentary: Option<String>) -> Result<AntePosting, StateError> {
+        self.save_undo_point();
+        if !self.can_post_ante(player_index) { return Err(StateError::unavailable("ante posting", player_index)); }
+        let player_index = player_index.or_else(|| self.ante_poster_indices().next()).unwrap();
+        
+        let amount = self.get_effective_ante(player_index);
+        self.ante_posting_statuses[player_index] = false;
+        self.bets[player_index] = amount;
+        self.invest(player_index, amount);
+        
         let op = AntePosting { player_index, amount, commentary };
-        self.operations.push(Operation::AntePosting(op.clone()));
+        self.push_operation(Operation::AntePosting(op.clone()));
+        self.run_ante_posting_automation();
         Ok(op)
     }
     
-    pub fn collect_bets(&mut self, commentary: Option<String>) -> Result<BetCollection, String> {
-        if !self.bet_collection_status { return Err("No bets to collect".to_string()); }
+    pub fn can_collect_bets(&self) -> bool { self.bet_collection_status }
+
+    pub fn collect_bets(&mut self, commentary: Option<String>) -> Result<BetCollection, StateError> {
+        self.save_undo_point();
+        if !self.can_collect_bets() { return Err(StateError::unavailable("bet collection", None)); }
         self.bet_collection_status = false;
         let bets = self.bets.clone();
-        self.bets.iter_mut().for_each(|b| *b = 0);
+        self.bets.iter_mut().for_each(|b| *b = Amount::zero());
         let op = BetCollection { bets, commentary };
-        self.operations.push(Operation::BetCollection(op.clone()));
+        self.push_operation(Operation::BetCollection(op.clone()));
+        self.run_bet_collection_automation();
         Ok(op)
     }
     
-    pub fn post_blind_or_straddle(&mut self, player_index: Option<usize>, commentary: Option<String>) -> Result<BlindOrStraddlePosting, String> {
-        let player_index = player_index.unwrap_or_else(|| self.blind_or_straddle_poster_indices().next().unwrap());
-        if !self.blind_or_straddle_posting_statuses[player_index] { return Err("Player cannot post blind/straddle".to_string()); }
+    pub fn can_post_blind_or_straddle(&self, player_index: Option<usize>) -> bool {
+        match player_index {
+            Some(i) => self.blind_or_straddle_posting_statuses.get(i).cloned().unwrap_or(false),
+            None => self.blind_or_straddle_poster_indices().next().is_some(),
+        }
+    }
+
+    pub fn post_blind_or_straddle(&mut self, player_index: Option<usize>, commentary: Option<String>) -> Result<BlindOrStraddlePosting, StateError> {
+        self.save_undo_point();
+        if !self.can_post_blind_or_straddle(player_index) { return Err(StateError::unavailable("blind or straddle posting", player_index)); }
+        let player_index = player_index.or_else(|| self.blind_or_straddle_poster_indices().next()).unwrap();
 
         let amount = self.get_effective_blind_or_straddle(player_index);
         self.blind_or_straddle_posting_statuses[player_index] = false;
         self.bets[player_index] += amount;
-        self.stacks[player_index] -= amount;
-        self.payoffs[player_index] -= amount;
+        self.invest(player_index, amount);
         
         let op = BlindOrStraddlePosting { player_index, amount, commentary };
-        self.operations.push(Operation::BlindOrStraddlePosting(op.clone()));
+        self.push_operation(Operation::BlindOrStraddlePosting(op.clone()));
+        self.run_blind_or_straddle_posting_automation();
         Ok(op)
     }
     
+    /// Returns the last player to post a blind or a live straddle.
+    fn last_forced_bettor_index(&self) -> usize {
+        self.straddler_indices.last().copied().unwrap_or_else(|| {
+            (0..self.player_count)
+                .filter(|&i| !self.is_sitting_out(i) && self.blinds_or_straddles[self.forced_bet_index(i)] > Amount::zero())
+                .max_by_key(|&i| self.forced_bet_index(i))
+                .unwrap_or(self.player_count - 1)
+        })
+    }
+
+    /// Returns the player who may post the next live straddle: the first active player with chips
+    /// after the last blind or straddle, if they posted no blind of their own.
+    pub fn straddler_index(&self) -> Option<usize> {
+        let last_index = self.last_forced_bettor_index();
+        let player_index = (1..self.player_count).map(|i| (last_index + i) % self.player_count)
+            .find(|&i| self.statuses[i] && self.stacks[i] > Amount::zero())?;
+        let blind_status = self.blinds_or_straddles[self.forced_bet_index(player_index)] != Amount::zero();
+        (!blind_status && !self.straddler_indices.contains(&player_index)).then_some(player_index)
+    }
+
+    /// Returns the amount of the next live straddle, twice the largest blind or straddle so far,
+    /// capped by the stack of the straddler.
+    pub fn get_effective_straddle(&self, player_index: usize) -> Amount {
+        let blind = (0..self.player_count).map(|i| self.get_effective_blind_or_straddle(i)).max().unwrap_or_default();
+        let straddle = self.straddler_indices.iter().map(|&i| self.bets[i]).max().unwrap_or_default();
+        (blind.max(straddle) + blind.max(straddle)).min(self.stacks[player_index])
+    }
+
+    /// Returns whether the player, or the next straddler if `None`, may post a live straddle.
+    ///
+    /// Straddles are posted once the antes are collected and before any card is dealt, so they
+    /// are only available while hole dealing is done by hand.
+    pub fn can_post_straddle(&self, player_index: Option<usize>) -> bool {
+        let window_status = self.status
+            && self.street_index.is_none_or(|i| i == 0)
+            && !self.ante_posting_statuses.iter().any(|&status| status)
+            && !self.bet_collection_status
+            && self.actor_indices.is_empty()
+            && !self.card_burning_status
+            && self.hole_cards.iter().all(|cards| cards.is_empty());
+        window_status && self.straddler_index().is_some_and(|i| player_index.is_none_or(|j| i == j))
+    }
+
+    /// Posts a live straddle of twice the largest blind or straddle, or re-straddles over the last
+    /// one. The player after the last straddler opens the betting, and the minimum raise becomes
+    /// the size of the straddle.
+    pub fn post_straddle(&mut self, player_index: Option<usize>, commentary: Option<String>) -> Result<BlindOrStraddlePosting, StateError> {
+        self.save_undo_point();
+        if !self.can_post_straddle(player_index) { return Err(StateError::unavailable("straddle posting", player_index)); }
+        let player_index = self.straddler_index().unwrap();
+
+        let amount = self.get_effective_straddle(player_index);
+        self.straddler_indices.push(player_index);
+        self.bets[player_index] += amount;
+        self.invest(player_index, amount);
+
+        let op = BlindOrStraddlePosting { player_index, amount, commentary };
+        self.push_operation(Operation::BlindOrStraddlePosting(op.clone()));
+        Ok(op)
+    }
+
+    /// Draws the next cards to be dealt from the deck source, or from the deck if there is none.
+    ///
+    /// Cards supplied by a source must still be in the deck, so the state stays authoritative.
+    fn draw_cards(&mut self, count: usize) -> Result<Vec<Card>, StateError> {
+        let cards = match &self.deck_source {
+            Some(deck_source) => deck_source.lock().map_err(|e| StateError::DeckSource(DeckError::Unavailable(e.to_string())))?.draw(count).map_err(StateError::DeckSource)?,
+            None if self.deck_cards.len() >= count => return Ok(self.deck_cards.drain(..count).collect()),
+            None if self.deck_cards.len() + self.recyclable_card_count() >= count => {
+                self.recycle_cards();
+                return Ok(self.deck_cards.drain(..count).collect());
+            }
+            None => return Err(StateError::NotEnoughCards { count, available: self.deck_cards.len() + self.recyclable_card_count() }),
+        };
+        if cards.len() != count {
+            return Err(StateError::DeckSource(DeckError::CardCount { count: cards.len(), expected: count }));
+        }
+
+        if let Some(card) = self.absent_card(&cards) {
+            return Err(StateError::InvalidCard { card });
+        }
+        self.deck_cards.retain(|card| !cards.contains(card));
+
+        Ok(cards)
+    }
+
+    fn recyclable_card_count(&self) -> usize {
+        self.mucked_cards.len() + self.discarded_cards.iter().map(Vec::len).sum::<usize>()
+    }
+
+    /// Shuffles the mucked and discarded cards back into the deck, beneath the cards left, for
+    /// draw games that run out of cards to replace the discards with.
+    fn recycle_cards(&mut self) {
+        let mut cards = std::mem::take(&mut self.mucked_cards);
+        for discarded_cards in &mut self.discarded_cards {
+            cards.append(discarded_cards);
+        }
+        let cards = match (&self.rng, self.seed) {
+            (Some(rng), _) => shuffled_with(&cards, &mut *rng.lock().unwrap()),
+            (None, Some(seed)) => shuffled_with(&cards, &mut StdRng::seed_from_u64(seed.wrapping_add(self.operations.len() as u64))),
+            (None, None) => shuffled(&cards),
+        };
+        self.deck_cards.extend(cards);
+    }
+
+    /// Returns the first of the cards not in the deck, or repeated, if any.
+    fn absent_card(&self, cards: &[Card]) -> Option<Card> {
+        cards.iter().enumerate().find(|(i, card)| !self.deck_cards.contains(card) || cards[..*i].contains(card)).map(|(_, &card)| card)
+    }
+
+    /// Takes the manually supplied cards out of the deck, drawing the supplied cards if there are
+    /// none.
+    ///
+    /// Known cards must still be in the deck. Unknown cards, as dealt in hands with hidden hole
+    /// cards, leave the deck untouched, or are replaced by drawn cards if so configured.
+    fn take_cards(&mut self, cards: Option<Vec<Card>>, count: usize) -> Result<Vec<Card>, StateError> {
+        let Some(mut cards) = cards else { return self.draw_cards(count) };
+        let known_cards: Vec<Card> = cards.iter().filter(|card| !card.is_unknown()).cloned().collect();
+        if let Some(card) = self.absent_card(&known_cards) {
+            return Err(StateError::InvalidCard { card });
+        }
+        self.deck_cards.retain(|card| !known_cards.contains(card));
+
+        if self.unknown_card_replacement_status {
+            for card in cards.iter_mut().filter(|card| card.is_unknown()) {
+                *card = self.draw_cards(1)?[0];
+            }
+        }
+
+        Ok(cards)
+    }
+
     pub fn can_burn_card(&self, _card: Option<Card>) -> bool { self.card_burning_status }
     
-    pub fn burn_card(&mut self, card: Option<Card>, commentary: Option<String>) -> Result<CardBurning, String> {
-        if !self.can_burn_card(card) { return Err("Cannot burn card now".to_string()); }
-        let card_to_burn = card.unwrap_or_else(|| self.deck_cards.pop_front().unwrap());
+    pub fn burn_card(&mut self, card: Option<Card>, commentary: Option<String>) -> Result<CardBurning, StateError> {
+        self.save_undo_point();
+        if !self.can_burn_card(card) { return Err(StateError::unavailable("card burning", None)); }
+        let card_to_burn = self.take_cards(card.map(|card| vec![card]), 1)?[0];
         self.card_burning_status = false;
         self.burn_cards.push(card_to_burn);
         let op = CardBurning { card: card_to_burn, commentary };
-        self.operations.push(Operation::CardBurning(op.clone()));
+        self.push_operation(Operation::CardBurning(op.clone()));
         self.run_dealing_automation();
         Ok(op)
     }
 
-    pub fn deal_hole(&mut self, cards: Option<Vec<Card>>, player_index: Option<usize>, commentary: Option<String>) -> Result<HoleDealing, String> {
-        let player_index = player_index.or_else(|| self.hole_dealee_index()).ok_or("No player to deal to")?;
+    /// Returns whether hole cards can be dealt to the player, or to anyone if no player is given.
+    pub fn can_deal_hole(&self, player_index: Option<usize>) -> bool {
+        match player_index {
+            _ if self.standing_pat_or_discarding_statuses.iter().any(|&status| status) => false,
+            Some(i) => self.hole_dealing_statuses.get(i).is_some_and(|statuses| !statuses.is_empty()),
+            None => self.hole_dealee_index().is_some(),
+        }
+    }
+
+    /// Deals the cards, or the next card of the deck if `None`, to the player, or to the next
+    /// dealee if `None`.
+    ///
+    /// Unknown cards ([`Card::UNKNOWN`]) may be dealt as placeholders for cards that were never
+    /// seen, and are resolved once the player shows them.
+    pub fn deal_hole(&mut self, cards: Option<Vec<Card>>, player_index: Option<usize>, commentary: Option<String>) -> Result<HoleDealing, StateError> {
+        self.save_undo_point();
+        if self.standing_pat_or_discarding_statuses.iter().any(|&status| status) {
+            return Err(StateError::DiscardsPending);
+        }
+        let player_index = player_index.or_else(|| self.hole_dealee_index()).ok_or(StateError::unavailable("hole dealing", None))?;
         let num_to_deal = cards.as_ref().map_or(1, |c| c.len());
-        if self.hole_dealing_statuses[player_index].len() < num_to_deal { return Err("Not enough hole cards to be dealt to player".to_string()); }
+        let expected = self.hole_dealing_statuses.get(player_index).ok_or(StateError::NotYourTurn { player_index })?.len();
+        if expected == 0 { return Err(StateError::NotYourTurn { player_index }); }
+        if expected < num_to_deal { return Err(StateError::InvalidCardCount { count: num_to_deal, expected }); }
 
-        let dealt_cards = cards.unwrap_or_else(|| self.deck_cards.drain(..num_to_deal).collect());
+        let dealt_cards = self.take_cards(cards, num_to_deal)?;
         let mut statuses = Vec::new();
         for card in &dealt_cards {
             let status = self.hole_dealing_statuses[player_index].pop_front().unwrap();
@@ -572,13 +1964,248 @@ impl State {
         }
         
         let op = HoleDealing { player_index, cards: dealt_cards, statuses, commentary };
-        self.operations.push(Operation::HoleDealing(op.clone()));
+        self.push_operation(Operation::HoleDealing(op.clone()));
         self.run_dealing_automation();
         Ok(op)
     }
 
-    fn actor_index(&self) -> Result<usize, String> {
-        self.actor_indices.front().cloned().ok_or_else(|| "There is no player to act.".to_string())
+    /// Returns the next board to deal cards to, the boards of later runouts coming after the
+    /// earlier ones.
+    pub fn board_dealing_index(&self) -> Option<usize> {
+        self.board_dealing_counts.iter().position(|&count| count > 0)
+    }
+
+    pub fn can_deal_board(&self) -> bool { !self.card_burning_status && self.board_dealing_index().is_some() }
+
+    /// Deals the cards, or the cards still due if none are given, to the next board.
+    pub fn deal_board(&mut self, cards: Option<Vec<Card>>, commentary: Option<String>) -> Result<BoardDealing, StateError> {
+        self.save_undo_point();
+        if self.card_burning_status { return Err(StateError::BurnPending); }
+        let board_index = self.board_dealing_index().ok_or(StateError::unavailable("board dealing", None))?;
+        let count = cards.as_ref().map_or(self.board_dealing_counts[board_index], |cards| cards.len());
+        if count == 0 || count > self.board_dealing_counts[board_index] {
+            return Err(StateError::InvalidCardCount { count, expected: self.board_dealing_counts[board_index] });
+        }
+
+        let dealt_cards = self.take_cards(cards, count)?;
+        self.board_dealing_counts[board_index] -= count;
+        self.board_cards[board_index].extend(dealt_cards.iter().cloned());
+
+        let op = BoardDealing { cards: dealt_cards, commentary };
+        self.push_operation(Operation::BoardDealing(op.clone()));
+        self.run_dealing_automation();
+        Ok(op)
+    }
+
+    /// Returns the next player to stand pat or discard, in order of position.
+    pub fn stand_pat_or_discarder_index(&self) -> Option<usize> {
+        (0..self.player_count).find(|&i| self.standing_pat_or_discarding_statuses[i])
+    }
+
+    /// Returns whether the next player to draw can discard the cards, all of which must be theirs.
+    pub fn can_stand_pat_or_discard(&self, cards: &[Card]) -> bool {
+        self.stand_pat_or_discarder_index().is_some_and(|i| {
+            cards.iter().enumerate().all(|(j, card)| self.hole_cards[i].contains(card) && !cards[..j].contains(card))
+        })
+    }
+
+    /// Stands pat if no cards are given, and discards the hole cards given otherwise.
+    ///
+    /// The discarded cards are replaced by hole dealing once every player has drawn, each as
+    /// exposed or hidden as the card it replaces.
+    pub fn stand_pat_or_discard(&mut self, cards: Vec<Card>, commentary: Option<String>) -> Result<StandingPatOrDiscarding, StateError> {
+        self.save_undo_point();
+        let player_index = self.stand_pat_or_discarder_index().ok_or(StateError::unavailable("standing pat or discarding", None))?;
+        let mut positions = cards.iter().enumerate()
+            .map(|(i, card)| match self.hole_cards[player_index].iter().position(|hole_card| hole_card == card) {
+                Some(position) if !cards[..i].contains(card) => Ok(position),
+                _ => Err(StateError::InvalidCard { card: *card }),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        positions.sort_unstable_by(|a, b| b.cmp(a));
+        let mut statuses = Vec::new();
+        for position in positions {
+            self.hole_cards[player_index].remove(position);
+            statuses.push(self.hole_card_statuses[player_index].remove(position));
+        }
+        statuses.reverse();
+        self.hole_dealing_statuses[player_index].extend(statuses);
+        self.discarded_cards[player_index].extend(cards.iter().cloned());
+        self.standing_pat_or_discarding_statuses[player_index] = false;
+
+        let op = StandingPatOrDiscarding { player_index, cards, commentary };
+        self.push_operation(Operation::StandingPatOrDiscarding(op.clone()));
+        self.run_dealing_automation();
+        Ok(op)
+    }
+
+    pub fn runout_count_selector_index(&self) -> Option<usize> {
+        (0..self.player_count).find(|&i| self.runout_count_selector_statuses[i])
+    }
+
+    pub fn can_select_runout_count(&self, player_index: Option<usize>, runout_count: Option<usize>) -> bool {
+        player_index.or_else(|| self.runout_count_selector_index())
+            .is_some_and(|i| self.runout_count_selector_statuses[i])
+            && runout_count.is_none_or(|count| count > 0)
+    }
+
+    /// Selects the number of runouts the player would like, or no preference if `None`.
+    ///
+    /// The board is run out as many times as selected only if every player all in agrees, and
+    /// once otherwise.
+    pub fn select_runout_count(&mut self, player_index: Option<usize>, runout_count: Option<usize>, commentary: Option<String>) -> Result<RunoutCountSelection, StateError> {
+        self.save_undo_point();
+        if !self.can_select_runout_count(player_index, None) { return Err(StateError::unavailable("runout count selection", player_index)); }
+        if let Some(runout_count) = runout_count.filter(|&count| count == 0) { return Err(StateError::InvalidRunoutCount { runout_count }); }
+        let player_index = player_index.or_else(|| self.runout_count_selector_index()).unwrap();
+        self.runout_count_selector_statuses[player_index] = false;
+        self.runout_count_selections[player_index] = runout_count;
+
+        let op = RunoutCountSelection { player_index, runout_count, commentary };
+        self.push_operation(Operation::RunoutCountSelection(op.clone()));
+        self.run_runout_count_selection_automation();
+        Ok(op)
+    }
+
+    /// Returns whether the next player in the showdown can show the cards, all of which must be
+    /// theirs, or show or muck their hand if none are given.
+    pub fn can_show_or_muck_hole_cards(&self, cards: Option<&[Card]>) -> bool {
+        let cards = cards.unwrap_or_default();
+        self.showdown_indices.front().is_some_and(|&i| {
+            cards.iter().enumerate().all(|(j, card)| self.hole_cards[i].contains(card) && !cards[..j].contains(card))
+        })
+    }
+
+    /// Mucks the hand of the next player in the showdown if `None`, shows every hole card if
+    /// empty, and shows the cards otherwise.
+    pub fn show_or_muck_hole_cards(&mut self, cards: Option<Vec<Card>>, commentary: Option<String>) -> Result<HoleCardsShowingOrMucking, StateError> {
+        self.save_undo_point();
+        let Some(&player_index) = self.showdown_indices.front() else { return Err(StateError::unavailable("showing or mucking", None)) };
+        if cards.is_none() && self.is_hand_exposed(player_index) { return Err(StateError::HandExposed); }
+        let shown_cards = cards.as_deref().unwrap_or_default();
+        if let Some((_, &card)) = shown_cards.iter().enumerate().find(|&(j, card)| shown_cards[..j].contains(card)) {
+            return Err(StateError::InvalidCard { card });
+        }
+        self.resolve_unknown_cards(player_index, shown_cards)?;
+        if let Some(&card) = shown_cards.iter().find(|card| !self.hole_cards[player_index].contains(card)) {
+            return Err(StateError::InvalidCard { card });
+        }
+        self.showdown_indices.pop_front();
+
+        let hole_cards = match cards {
+            None => {
+                self.statuses[player_index] = false;
+                self.mucked_cards.append(&mut self.hole_cards[player_index]);
+                Vec::new()
+            }
+            Some(cards) => {
+                for (card, status) in self.hole_cards[player_index].iter().zip(self.hole_card_statuses[player_index].iter_mut()) {
+                    *status |= cards.is_empty() || cards.contains(card);
+                }
+                if cards.is_empty() { self.hole_cards[player_index].clone() } else { cards }
+            }
+        };
+
+        let op = HoleCardsShowingOrMucking { player_index, hole_cards, commentary };
+        self.push_operation(Operation::HoleCardsShowingOrMucking(op.clone()));
+        self.run_showdown_automation();
+        Ok(op)
+    }
+
+    /// Replaces the unknown hole cards of the player with the shown cards they were not known to
+    /// hold, as when a hand history reveals the cards of a player only at showdown.
+    ///
+    /// The revealed cards must still be in the deck, and there must be an unknown card for each.
+    fn resolve_unknown_cards(&mut self, player_index: usize, cards: &[Card]) -> Result<(), StateError> {
+        let revealed_cards: Vec<Card> = cards.iter().filter(|card| !card.is_unknown() && !self.hole_cards[player_index].contains(card)).cloned().collect();
+        if let Some(card) = self.absent_card(&revealed_cards) {
+            return Err(StateError::InvalidCard { card });
+        }
+        let unknown_count = self.hole_cards[player_index].iter().filter(|card| card.is_unknown()).count();
+        if let Some(&card) = revealed_cards.get(unknown_count) {
+            return Err(StateError::InvalidCard { card });
+        }
+
+        self.deck_cards.retain(|card| !revealed_cards.contains(card));
+        let unknown_cards = self.hole_cards[player_index].iter_mut().filter(|card| card.is_unknown());
+        for (card, revealed_card) in unknown_cards.zip(revealed_cards) {
+            *card = revealed_card;
+        }
+        Ok(())
+    }
+
+    pub fn can_push_chips(&self) -> bool { !self.chips_pushing_queue.is_empty() }
+
+    /// Pushes the next portion of a pot in front of its winners.
+    pub fn push_chips(&mut self, commentary: Option<String>) -> Result<ChipsPushing, StateError> {
+        self.save_undo_point();
+        let mut op = self.chips_pushing_queue.pop_front().ok_or(StateError::unavailable("chips pushing", None))?;
+        op.commentary = commentary;
+        for (bet, amount) in self.bets.iter_mut().zip(&op.amounts) {
+            *bet += amount;
+        }
+        self.push_operation(Operation::ChipsPushing(op.clone()));
+        self.run_chips_pushing_automation();
+        Ok(op)
+    }
+
+    pub fn hand_killing_indices(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.player_count).filter(move |&i| self.hand_killing_statuses[i])
+    }
+
+    pub fn can_kill_hand(&self, player_index: Option<usize>) -> bool {
+        match player_index {
+            Some(i) => self.hand_killing_statuses.get(i).cloned().unwrap_or(false),
+            None => self.hand_killing_indices().next().is_some(),
+        }
+    }
+
+    /// Kills the hand of a player who cannot win any pot, moving their hole cards to the muck.
+    pub fn kill_hand(&mut self, player_index: Option<usize>, commentary: Option<String>) -> Result<HandKilling, StateError> {
+        self.save_undo_point();
+        if !self.can_kill_hand(player_index) { return Err(StateError::unavailable("hand killing", player_index)); }
+        let player_index = player_index.or_else(|| self.hand_killing_indices().next()).unwrap();
+
+        self.hand_killing_statuses[player_index] = false;
+        self.statuses[player_index] = false;
+        self.mucked_cards.append(&mut self.hole_cards[player_index]);
+        let op = HandKilling { player_index, commentary };
+        self.push_operation(Operation::HandKilling(op.clone()));
+        self.run_hand_killing_automation();
+        Ok(op)
+    }
+
+    pub fn chips_pulling_indices(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.player_count).filter(move |&i| self.chips_pulling_statuses[i])
+    }
+
+    pub fn can_pull_chips(&self, player_index: Option<usize>) -> bool {
+        match player_index {
+            Some(i) => self.chips_pulling_statuses.get(i).cloned().unwrap_or(false),
+            None => self.chips_pulling_indices().next().is_some(),
+        }
+    }
+
+    /// Moves the chips pushed in front of a winner into their stack.
+    pub fn pull_chips(&mut self, player_index: Option<usize>, commentary: Option<String>) -> Result<ChipsPulling, StateError> {
+        self.save_undo_point();
+        if !self.can_pull_chips(player_index) { return Err(StateError::unavailable("chips pulling", player_index)); }
+        let player_index = player_index.or_else(|| self.chips_pulling_indices().next()).unwrap();
+
+        let amount = self.bets[player_index];
+        self.chips_pulling_statuses[player_index] = false;
+        self.bets[player_index] = Amount::zero();
+        self.stacks[player_index] += amount;
+        self.payoffs[player_index] += amount;
+        let op = ChipsPulling { player_index, amount, commentary };
+        self.push_operation(Operation::ChipsPulling(op.clone()));
+        self.run_chips_pulling_automation();
+        Ok(op)
+    }
+
+    fn actor_index(&self) -> Result<usize, StateError> {
+        self.actor_indices.front().cloned().ok_or(StateError::NoActor)
     }
 
     fn advance_actor(&mut self) {
@@ -587,100 +2214,935 @@ impl State {
         }
     }
 
-    pub fn fold(&mut self, commentary: Option<String>) -> Result<Folding, String> {
+    fn now(&self) -> SystemTime {
+        self.clock_source.as_ref().map_or_else(SystemTime::now, |clock_source| clock_source())
+    }
+
+    /// Starts timing the player first in the actor queue, if the turns are timed.
+    fn start_turn(&mut self) {
+        if self.time_per_action.is_some() {
+            self.turn_started_at = Some(self.now());
+        }
+    }
+
+    /// Stops timing the player, charging the time they took beyond the time per action to their
+    /// time bank.
+    fn end_turn(&mut self, player_index: usize) {
+        let (Some(time_per_action), Some(started_at)) = (self.time_per_action, self.turn_started_at.take()) else { return };
+        let overage = self.now().duration_since(started_at).unwrap_or_default().saturating_sub(time_per_action);
+        if let Some(time_bank) = self.time_banks.get_mut(player_index) {
+            *time_bank = time_bank.saturating_sub(overage);
+        }
+    }
+
+    /// Returns the time left to the player in turn, time bank included, if the turns are timed.
+    pub fn remaining_time(&self) -> Option<Duration> {
+        let (Some(time_per_action), Some(started_at), Some(&player_index)) = (self.time_per_action, self.turn_started_at, self.actor_indices.front()) else { return None };
+        let elapsed = self.now().duration_since(started_at).unwrap_or_default();
+        Some((time_per_action + self.time_banks[player_index]).saturating_sub(elapsed))
+    }
+
+    fn verify_timeout(&self) -> Result<(), StateError> {
+        match self.remaining_time() {
+            Some(remaining) if !remaining.is_zero() => Err(StateError::TimeRemaining { remaining }),
+            _ => Ok(()),
+        }
+    }
+
+    /// Folds for the player in turn once their time has run out, or posts the bring-in if it is
+    /// due. Untimed states leave it to the caller to decide when the time is up.
+    pub fn timeout_fold(&mut self) -> Result<Operation, StateError> {
+        self.verify_timeout()?;
+        let commentary = Some(EXPIRY_COMMENTARY.to_string());
+        if self.can_post_bring_in() {
+            Ok(Operation::BringInPosting(self.post_bring_in(commentary)?))
+        } else {
+            Ok(Operation::Folding(self.fold(commentary)?))
+        }
+    }
+
+    /// Checks for the player in turn once their time has run out, folding instead if they face a
+    /// bet, or posts the bring-in if it is due.
+    pub fn timeout_check(&mut self) -> Result<Operation, StateError> {
+        self.verify_timeout()?;
+        if self.checking_or_calling_amount().is_some_and(|amount| amount > Amount::zero()) || self.can_post_bring_in() {
+            return self.timeout_fold();
+        }
+        Ok(Operation::CheckingOrCalling(self.check_or_call(Some(EXPIRY_COMMENTARY.to_string()))?))
+    }
+
+    pub fn verify_folding(&self) -> Result<(), StateError> {
+        self.actor_index()?;
+        if self.bring_in_status { Err(StateError::BringInPending) } else { Ok(()) }
+    }
+
+    pub fn verify_checking_or_calling(&self) -> Result<(), StateError> {
+        self.actor_index()?;
+        if self.bring_in_status { Err(StateError::BringInPending) } else { Ok(()) }
+    }
+
+    pub fn can_post_bring_in(&self) -> bool { self.bring_in_status && !self.actor_indices.is_empty() }
+
+    /// Posts the bring-in, or the rest of the stack if it is short, instead of completing.
+    pub fn post_bring_in(&mut self, commentary: Option<String>) -> Result<BringInPosting, StateError> {
+        self.save_undo_point();
+        if !self.can_post_bring_in() { return Err(StateError::unavailable("bring-in posting", None)); }
+        let player_index = self.actor_index()?;
+        let amount = self.bring_in.min(self.stacks[player_index]);
+
+        self.advance_actor();
+        self.bring_in_status = false;
+        self.bets[player_index] += amount;
+        self.invest(player_index, amount);
+
+        let op = BringInPosting { player_index, amount, commentary };
+        self.push_operation(Operation::BringInPosting(op.clone()));
+        self.run_betting_automation();
+        Ok(op)
+    }
+
+    pub fn can_fold(&self) -> bool { self.verify_folding().is_ok() }
+
+    pub fn can_check_or_call(&self) -> bool { self.verify_checking_or_calling().is_ok() }
+
+    /// Returns whether the player in turn can complete, bet, or raise to the amount, or to some
+    /// amount if none is given.
+    pub fn can_complete_bet_or_raise_to(&self, amount: Option<Amount>) -> bool {
+        match (amount, self.actor_indices.front()) {
+            (_, None) => false,
+            (Some(amount), Some(_)) => self.verify_completion_betting_or_raising_to(amount).is_ok(),
+            (None, Some(_)) => self.completion_betting_or_raising_status(),
+        }
+    }
+
+    pub fn fold(&mut self, commentary: Option<String>) -> Result<Folding, StateError> {
+        self.save_undo_point();
+        self.verify_folding()?;
         let player_index = self.actor_index()?;
         self.advance_actor();
         self.statuses[player_index] = false;
         self.mucked_cards.append(&mut self.hole_cards[player_index]);
         let op = Folding { player_index, commentary };
-        self.operations.push(Operation::Folding(op.clone()));
+        self.push_operation(Operation::Folding(op.clone()));
         self.run_betting_automation();
         Ok(op)
     }
 
-    pub fn check_or_call(&mut self, commentary: Option<String>) -> Result<CheckingOrCalling, String> {
+    /// Returns the amount the player in turn must put in to call, capped by their stack, or zero
+    /// to check.
+    pub fn checking_or_calling_amount(&self) -> Option<Amount> {
+        let player_index = *self.actor_indices.front()?;
+        let max_bet = self.bets.iter().cloned().max().unwrap_or_default();
+        Some((max_bet - self.bets[player_index]).min(self.stacks[player_index]))
+    }
+
+    pub fn check_or_call(&mut self, commentary: Option<String>) -> Result<CheckingOrCalling, StateError> {
+        self.save_undo_point();
+        self.verify_checking_or_calling()?;
         let player_index = self.actor_index()?;
-        let max_bet = *self.bets.iter().max().unwrap_or(&0);
-        let amount_to_call = (max_bet - self.bets[player_index]).min(self.stacks[player_index]);
+        let amount_to_call = self.checking_or_calling_amount().unwrap_or_default();
         
         self.advance_actor();
         self.bets[player_index] += amount_to_call;
-        self.stacks[player_index] -= amount_to_call;
-        self.payoffs[player_index] -= amount_to_call;
+        self.invest(player_index, amount_to_call);
 
         let op = CheckingOrCalling { player_index, amount: amount_to_call, commentary };
-        self.operations.push(Operation::CheckingOrCalling(op.clone()));
+        self.push_operation(Operation::CheckingOrCalling(op.clone()));
         self.run_betting_automation();
         Ok(op)
     }
 
-    pub fn complete_bet_or_raise_to(&mut self, amount: i64, commentary: Option<String>) -> Result<CompletionBettingOrRaisingTo, String> {
+    /// Returns whether the player in turn may complete, bet, or raise at all: they must have more
+    /// than the bet to call, the street must not be capped, and the action must be open to them.
+    fn completion_betting_or_raising_status(&self) -> bool {
+        let (Some(&player_index), Some(street_index)) = (self.actor_indices.front(), self.street_index) else { return false };
+        let max_bet = self.bets.iter().cloned().max().unwrap_or_default();
+        let capped = self.streets[street_index].max_completion_betting_or_raising_count
+            .is_some_and(|count| self.completion_betting_or_raising_count >= count);
+
+        self.bets[player_index] + self.stacks[player_index] > max_bet
+            && !capped
+            && !self.acted_player_indices.contains(&player_index)
+    }
+
+    /// Returns the smallest amount the player in turn may complete, bet, or raise to, which is
+    /// all in if they cannot afford a full completion, bet, or raise.
+    pub fn min_completion_betting_or_raising_to_amount(&self) -> Option<Amount> {
+        if !self.completion_betting_or_raising_status() { return None; }
+        Some(self.full_completion_betting_or_raising_to_amount()?.min(self.max_completion_betting_or_raising_to_amount()?))
+    }
+
+    /// Returns the largest amount the player in turn may complete, bet, or raise to under the
+    /// betting structure, capped by their stack.
+    ///
+    /// Under pot-limit betting, this is the size of the pot (collected bets and the bets in front
+    /// of the players) after the call of the player, on top of the bet to call.
+    pub fn max_completion_betting_or_raising_to_amount(&self) -> Option<Amount> {
+        if !self.completion_betting_or_raising_status() { return None; }
+        let player_index = *self.actor_indices.front()?;
+        let max_bet = self.bets.iter().cloned().max().unwrap_or_default();
+        let all_in_amount = self.bets[player_index] + self.stacks[player_index];
+        let call_amount = self.checking_or_calling_amount()?;
+
+        let max_amount = match self.betting_structure {
+            BettingStructure::FixedLimit => self.full_completion_betting_or_raising_to_amount()?,
+            BettingStructure::PotLimit => max_bet - self.payoffs.iter().sum::<Amount>() + call_amount,
+            BettingStructure::NoLimit => all_in_amount,
+        };
+
+        Some(max_amount.min(all_in_amount))
+    }
+
+    /// Returns the amount of a full completion, bet, or raise: the street's minimum bet while
+    /// only the bring-in is in, and otherwise a raise by the street's minimum or the largest
+    /// raise so far.
+    fn full_completion_betting_or_raising_to_amount(&self) -> Option<Amount> {
+        let street = &self.streets[self.street_index?];
+        let max_bet = self.bets.iter().cloned().max().unwrap_or_default();
+
+        Some(if self.completion_status {
+            street.min_completion_betting_or_raising_amount.max(max_bet)
+        } else {
+            max_bet + street.min_completion_betting_or_raising_amount.max(self.completion_betting_or_raising_amount)
+        })
+    }
+
+    /// Verifies that the player in turn may complete, bet, or raise to the amount: by at least the
+    /// street's minimum and the largest raise so far, unless going all in for less, and by no
+    /// more than their stack or the maximum of the betting structure. The street's cap on
+    /// completions, bets, or raises must not have been reached, and a player who has acted must
+    /// have faced a full raise since.
+    pub fn verify_completion_betting_or_raising_to(&self, amount: Amount) -> Result<(), StateError> {
         let player_index = self.actor_index()?;
+        let street = &self.streets[self.street_index.ok_or(StateError::NoActor)?];
+        let max_bet = self.bets.iter().cloned().max().unwrap_or_default();
+        let all_in_amount = self.bets[player_index] + self.stacks[player_index];
+        let min_amount = self.full_completion_betting_or_raising_to_amount().unwrap_or(max_bet);
+        let max_amount = self.max_completion_betting_or_raising_to_amount().unwrap_or(all_in_amount);
+
+        if self.acted_player_indices.contains(&player_index) {
+            Err(StateError::ActionNotReopened)
+        } else if let Some(count) = street.max_completion_betting_or_raising_count.filter(|&count| self.completion_betting_or_raising_count >= count) {
+            Err(StateError::CapReached { count })
+        } else if amount > all_in_amount {
+            Err(StateError::AboveStack { amount, all_in_amount })
+        } else if amount > max_amount {
+            Err(StateError::AboveMaximum { amount, max_amount, betting_structure: self.betting_structure })
+        } else if amount <= max_bet {
+            Err(StateError::NotAboveBet { amount, max_bet })
+        } else if amount < min_amount && amount < all_in_amount {
+            Err(StateError::BelowMinimum { amount, min_amount })
+        } else {
+            Ok(())
+        }
+    }
+
+    pub fn complete_bet_or_raise_to(&mut self, amount: Amount, commentary: Option<String>) -> Result<CompletionBettingOrRaisingTo, StateError> {
+        self.save_undo_point();
+        self.verify_completion_betting_or_raising_to(amount)?;
+        let player_index = self.actor_index()?;
+        let max_bet = self.bets.iter().cloned().max().unwrap_or_default();
+        let full_status = self.full_completion_betting_or_raising_to_amount().is_some_and(|full_amount| amount >= full_amount);
         let delta = amount - self.bets[player_index];
         
         self.bets[player_index] = amount;
-        self.stacks[player_index] -= delta;
-        self.payoffs[player_index] -= delta;
+        self.invest(player_index, delta);
         
+        self.completion_betting_or_raising_amount = self.completion_betting_or_raising_amount.max(amount - max_bet);
         self.opener_index = Some(player_index);
-        self.completion_betting_or_raising_count += 1;
+        if let Some(street_index) = self.street_index {
+            self.street_raises[street_index].push(RaiseTo { player_index, amount });
+        }
         
-        // Action re-opens for all other active players.
+        // Everyone else gets to respond, but an all in for less than a full raise does not reopen
+        // the action to those who have acted since the last full one: they may only call or fold.
         self.actor_indices = (0..self.player_count)
             .cycle()
             .skip(player_index + 1)
             .take(self.player_count)
-            .filter(|&i| self.statuses[i] && self.stacks[i] > 0)
+            .filter(|&i| self.statuses[i] && self.stacks[i] > Amount::zero())
             .collect();
-        self.acted_player_indices.clear();
+        self.bring_in_status = false;
+        if full_status {
+            self.completion_status = false;
+            self.completion_betting_or_raising_count += 1;
+            self.acted_player_indices.clear();
+        }
         self.acted_player_indices.insert(player_index);
 
         let op = CompletionBettingOrRaisingTo { player_index, amount, commentary };
-        self.operations.push(Operation::CompletionBettingOrRaisingTo(op.clone()));
+        self.push_operation(Operation::CompletionBettingOrRaisingTo(op.clone()));
         self.run_betting_automation();
         Ok(op)
     }
 
-    pub fn pots(&self) -> Vec<Pot> {
-        let mut contributions: Vec<i64> = self.payoffs.iter().map(|p| -p).collect();
-        let mut pots = Vec::new();
+    /// Serializes everything needed to continue the hand, including the order of the remaining
+    /// deck. To branch from the hand in memory instead, see [`State::hand_snapshot`].
+    pub fn snapshot(&self) -> Result<Vec<u8>, serde_json::Error> {
+        serde_json::to_vec(self)
+    }
 
-        for i in 0..self.player_count {
-            contributions[i] += self.bets[i];
+    /// Picks a hand up exactly where its snapshot was taken.
+    ///
+    /// What the snapshot leaves out (custom evaluators, the division function, the rake, the deck
+    /// source, the random number generator, the clock source, and the hooks) is taken from the
+    /// builder, which should be configured as the hand was.
+    pub fn resume(bytes: &[u8], builder: &StateBuilder) -> Result<State, BuildError> {
+        let mut state: State = serde_json::from_slice(bytes).map_err(|e| BuildError::InvalidSnapshot(e.to_string()))?;
+        state.evaluators = builder.resolve_evaluators(&state.hand_types)?;
+        state.divmod = builder.divmod;
+        state.rake = builder.rake.clone();
+        state.deck_source = builder.deck_source.clone();
+        state.hooks = builder.hooks.clone();
+        state.rng = builder.rng.clone();
+        state.clock_source = builder.clock_source.clone();
+        Ok(state)
+    }
+
+    /// Captures the mutable state of the hand, from the order of the deck to the players in turn.
+    pub fn hand_snapshot(&self) -> HandSnapshot {
+        HandSnapshot {
+            deck_cards: self.deck_cards.clone(),
+            board_cards: self.board_cards.clone(),
+            mucked_cards: self.mucked_cards.clone(),
+            burn_cards: self.burn_cards.clone(),
+            statuses: self.statuses.clone(),
+            bets: self.bets.clone(),
+            stacks: self.stacks.clone(),
+            capped_amounts: self.capped_amounts.clone(),
+            payoffs: self.payoffs.clone(),
+            hole_cards: self.hole_cards.clone(),
+            hole_card_statuses: self.hole_card_statuses.clone(),
+            discarded_cards: self.discarded_cards.clone(),
+            street_index: self.street_index,
+            street_pot_amounts: self.street_pot_amounts.clone(),
+            street_investments: self.street_investments.clone(),
+            street_raises: self.street_raises.clone(),
+            straddler_indices: self.straddler_indices.clone(),
+            status: self.status,
+            operations: self.operations.clone(),
+            operation_timestamps: self.operation_timestamps.clone(),
+            ante_posting_statuses: self.ante_posting_statuses.clone(),
+            bet_collection_status: self.bet_collection_status,
+            blind_or_straddle_posting_statuses: self.blind_or_straddle_posting_statuses.clone(),
+            card_burning_status: self.card_burning_status,
+            hole_dealing_statuses: self.hole_dealing_statuses.clone(),
+            board_dealing_counts: self.board_dealing_counts.clone(),
+            standing_pat_or_discarding_statuses: self.standing_pat_or_discarding_statuses.clone(),
+            actor_indices: self.actor_indices.clone(),
+            turn_started_at: self.turn_started_at,
+            time_banks: self.time_banks.clone(),
+            opener_index: self.opener_index,
+            bring_in_status: self.bring_in_status,
+            completion_status: self.completion_status,
+            completion_betting_or_raising_amount: self.completion_betting_or_raising_amount,
+            completion_betting_or_raising_count: self.completion_betting_or_raising_count,
+            acted_player_indices: self.acted_player_indices.clone(),
+            runout_count: self.runout_count,
+            runout_count_selector_statuses: self.runout_count_selector_statuses.clone(),
+            runout_count_selections: self.runout_count_selections.clone(),
+            showdown_indices: self.showdown_indices.clone(),
+            hand_killing_statuses: self.hand_killing_statuses.clone(),
+            chips_pushing_queue: self.chips_pushing_queue.clone(),
+            chips_pulling_statuses: self.chips_pulling_statuses.clone(),
         }
+    }
 
-        let mut last_contribution = 0;
-        let mut unique_contributions: Vec<i64> = contributions
-            .iter()
-            .cloned()
-            .filter(|&c| c > 0)
-            .collect();
-        unique_contributions.sort_unstable();
-        unique_contributions.dedup();
+    /// Puts the hand back to where the snapshot was captured, keeping the configuration.
+    ///
+    /// The snapshot must come from this state or one built the same way. Cards drawn from a deck
+    /// source are not returned to it.
+    pub fn restore(&mut self, snapshot: HandSnapshot) {
+        let HandSnapshot {
+            deck_cards, board_cards, mucked_cards, burn_cards, statuses, bets, stacks, capped_amounts, payoffs,
+            hole_cards, hole_card_statuses, discarded_cards, street_index, street_pot_amounts,
+            street_investments, street_raises, straddler_indices, status, operations,
+            operation_timestamps, ante_posting_statuses,
+            bet_collection_status, blind_or_straddle_posting_statuses, card_burning_status,
+            hole_dealing_statuses, board_dealing_counts, standing_pat_or_discarding_statuses,
+            actor_indices, turn_started_at, time_banks, opener_index, bring_in_status, completion_status,
+            completion_betting_or_raising_amount, completion_betting_or_raising_count,
+            acted_player_indices, runout_count, runout_count_selector_statuses,
+            runout_count_selections, showdown_indices, hand_killing_statuses, chips_pushing_queue,
+            chips_pulling_statuses,
+        } = snapshot;
+        self.deck_cards = deck_cards;
+        self.board_cards = board_cards;
+        self.mucked_cards = mucked_cards;
+        self.burn_cards = burn_cards;
+        self.statuses = statuses;
+        self.bets = bets;
+        self.stacks = stacks;
+        self.capped_amounts = capped_amounts;
+        self.payoffs = payoffs;
+        self.hole_cards = hole_cards;
+        self.hole_card_statuses = hole_card_statuses;
+        self.discarded_cards = discarded_cards;
+        self.street_index = street_index;
+        self.street_pot_amounts = street_pot_amounts;
+        self.street_investments = street_investments;
+        self.street_raises = street_raises;
+        self.straddler_indices = straddler_indices;
+        self.status = status;
+        self.operations = operations;
+        self.operation_timestamps = operation_timestamps;
+        self.ante_posting_statuses = ante_posting_statuses;
+        self.bet_collection_status = bet_collection_status;
+        self.blind_or_straddle_posting_statuses = blind_or_straddle_posting_statuses;
+        self.card_burning_status = card_burning_status;
+        self.hole_dealing_statuses = hole_dealing_statuses;
+        self.board_dealing_counts = board_dealing_counts;
+        self.standing_pat_or_discarding_statuses = standing_pat_or_discarding_statuses;
+        self.actor_indices = actor_indices;
+        self.turn_started_at = turn_started_at;
+        self.time_banks = time_banks;
+        self.opener_index = opener_index;
+        self.bring_in_status = bring_in_status;
+        self.completion_status = completion_status;
+        self.completion_betting_or_raising_amount = completion_betting_or_raising_amount;
+        self.completion_betting_or_raising_count = completion_betting_or_raising_count;
+        self.acted_player_indices = acted_player_indices;
+        self.runout_count = runout_count;
+        self.runout_count_selector_statuses = runout_count_selector_statuses;
+        self.runout_count_selections = runout_count_selections;
+        self.showdown_indices = showdown_indices;
+        self.hand_killing_statuses = hand_killing_statuses;
+        self.chips_pushing_queue = chips_pushing_queue;
+        self.chips_pulling_statuses = chips_pulling_statuses;
+        self.hand_cache = HandCache::default();
+    }
 
-        for &contribution in &unique_contributions {
-            let mut pot_amount = 0;
-            let mut pot_player_indices = Vec::new();
+    /// Appends the operation to the log, stamping it with the time if the state has a clock.
+    fn push_operation(&mut self, operation: Operation) {
+        if let Some(clock_source) = &self.clock_source {
+            Arc::make_mut(&mut self.operation_timestamps).push(clock_source());
+        }
+        match &operation {
+            Operation::BringInPosting(BringInPosting { player_index, .. })
+            | Operation::Folding(Folding { player_index, .. })
+            | Operation::CheckingOrCalling(CheckingOrCalling { player_index, .. })
+            | Operation::CompletionBettingOrRaisingTo(CompletionBettingOrRaisingTo { player_index, .. }) => self.end_turn(*player_index),
+            _ => {}
+        }
+        Arc::make_mut(&mut self.operations).push(operation);
+    }
 
-            for i in 0..self.player_count {
-                if contributions[i] >= contribution {
-                    pot_amount += contribution - last_contribution;
-                }
-                if self.statuses[i] && contributions[i] >= contribution {
-                    pot_player_indices.push(i);
-                }
+    /// Returns the time the operation at the index was applied at, if it was stamped.
+    pub fn operation_timestamp(&self, operation_index: usize) -> Option<SystemTime> {
+        self.operation_timestamps.get(operation_index).copied()
+    }
+
+    /// Returns the time elapsed since the previous operation for each stamped operation, e.g. how
+    /// long each player took to decide. The first operation is timed from itself.
+    pub fn operation_durations(&self) -> Vec<Duration> {
+        self.operation_timestamps.iter()
+            .scan(None, |previous: &mut Option<SystemTime>, &timestamp| {
+                let duration = previous.map_or(Duration::ZERO, |previous| timestamp.duration_since(previous).unwrap_or_default());
+                *previous = Some(timestamp);
+                Some(duration)
+            })
+            .collect()
+    }
+
+    /// Records the state before an operation, discarding the records left by operations that
+    /// failed.
+    fn save_undo_point(&mut self) {
+        if !self.undo_status { return; }
+        let snapshot = self.hand_snapshot();
+        let undo_history = Arc::make_mut(&mut self.undo_history);
+        while undo_history.last().is_some_and(|previous| previous.operations.len() >= snapshot.operations.len()) {
+            undo_history.pop();
+        }
+        undo_history.push(snapshot);
+    }
+
+    pub fn can_undo(&self) -> bool {
+        self.undo_history.iter().any(|snapshot| snapshot.operations.len() < self.operations.len())
+    }
+
+    /// Reverses the most recent operation, restoring the state it started from, and returns it.
+    ///
+    /// Operations carried out by the automations count on their own, so undoing a call that
+    /// closed the action first takes back the collection of the bets. Cards drawn from a deck
+    /// source are not returned to it.
+    pub fn undo(&mut self) -> Result<Operation, StateError> {
+        if !self.undo_status { return Err(StateError::UndoDisabled); }
+        if !self.can_undo() { return Err(StateError::NothingToUndo); }
+        let operation_count = self.operations.len();
+        let undo_history = Arc::make_mut(&mut self.undo_history);
+        while undo_history.last().is_some_and(|snapshot| snapshot.operations.len() >= operation_count) {
+            undo_history.pop();
+        }
+        let operation = self.operations.last().cloned().unwrap();
+        let snapshot = undo_history.pop().unwrap();
+        self.restore(snapshot);
+        Ok(operation)
+    }
+
+    /// Applies the action on behalf of the player in turn or the dealer and returns the resulting
+    /// operation.
+    pub fn apply(&mut self, action: Action) -> Result<Operation, StateError> {
+        Ok(match action {
+            Action::Fold => Operation::Folding(self.fold(None)?),
+            Action::CheckOrCall => Operation::CheckingOrCalling(self.check_or_call(None)?),
+            Action::CompleteBetOrRaiseTo(amount) => Operation::CompletionBettingOrRaisingTo(self.complete_bet_or_raise_to(amount, None)?),
+            Action::PostBringIn => Operation::BringInPosting(self.post_bring_in(None)?),
+            Action::StandPatOrDiscard(cards) => Operation::StandingPatOrDiscarding(self.stand_pat_or_discard(cards, None)?),
+            Action::ShowOrMuckHoleCards(cards) => Operation::HoleCardsShowingOrMucking(self.show_or_muck_hole_cards(cards, None)?),
+            Action::DealHole { player_index, cards } => Operation::HoleDealing(self.deal_hole(Some(cards), Some(player_index), None)?),
+            Action::DealBoard(cards) => Operation::BoardDealing(self.deal_board(Some(cards), None)?),
+            Action::PostAnte(player_index) => Operation::AntePosting(self.post_ante(player_index, None)?),
+            Action::CollectBets => Operation::BetCollection(self.collect_bets(None)?),
+            Action::PostBlindOrStraddle(player_index) => Operation::BlindOrStraddlePosting(self.post_blind_or_straddle(player_index, None)?),
+            Action::PostStraddle(player_index) => Operation::BlindOrStraddlePosting(self.post_straddle(player_index, None)?),
+            Action::BurnCard(card) => Operation::CardBurning(self.burn_card(card, None)?),
+            Action::SelectRunoutCount { player_index, runout_count } => Operation::RunoutCountSelection(self.select_runout_count(player_index, runout_count, None)?),
+            Action::KillHand(player_index) => Operation::HandKilling(self.kill_hand(player_index, None)?),
+            Action::PushChips => Operation::ChipsPushing(self.push_chips(None)?),
+            Action::PullChips(player_index) => Operation::ChipsPulling(self.pull_chips(player_index, None)?),
+        })
+    }
+
+    /// Returns a copy of the state with the action applied, leaving this state untouched.
+    ///
+    /// The configuration and the operation log are shared with the copy until it diverges, so
+    /// only the state of the hand is cloned. The copy deals from its own copy of the deck, never
+    /// from the deck source or the random number generator of this state, and cannot be undone
+    /// past the action.
+    pub fn with_action(&self, action: Action) -> Result<State, StateError> {
+        let mut state = State { deck_source: None, rng: None, undo_history: Arc::default(), ..self.clone() };
+        state.apply(action)?;
+        Ok(state)
+    }
+
+    /// Returns the operation the state awaits next, and from whom, so that drivers need not inspect
+    /// the statuses of every phase.
+    pub fn next_step(&self) -> NextStep {
+        if let Some(player_index) = self.ante_poster_indices().next() {
+            NextStep::AwaitingAntePosting(player_index)
+        } else if self.can_collect_bets() {
+            NextStep::AwaitingBetCollection
+        } else if let Some(player_index) = self.blind_or_straddle_poster_indices().next() {
+            NextStep::AwaitingBlindOrStraddlePosting(player_index)
+        } else if self.card_burning_status {
+            NextStep::AwaitingCardBurning
+        } else if let Some(player_index) = self.stand_pat_or_discarder_index() {
+            NextStep::AwaitingStandingPatOrDiscarding(player_index)
+        } else if let Some(player_index) = self.hole_dealee_index() {
+            NextStep::AwaitingHoleDealing(player_index)
+        } else if self.can_deal_board() {
+            NextStep::AwaitingBoardDealing
+        } else if let Some(player_index) = self.runout_count_selector_index() {
+            NextStep::AwaitingRunoutCountSelection(player_index)
+        } else if let Some(&player_index) = self.actor_indices.front() {
+            NextStep::AwaitingAction(player_index)
+        } else if let Some(&player_index) = self.showdown_indices.front() {
+            NextStep::AwaitingShowdown(player_index)
+        } else if let Some(player_index) = self.hand_killing_indices().next() {
+            NextStep::AwaitingHandKilling(player_index)
+        } else if self.can_push_chips() {
+            NextStep::AwaitingChipsPushing
+        } else if let Some(player_index) = self.chips_pulling_indices().next() {
+            NextStep::AwaitingChipsPulling(player_index)
+        } else {
+            NextStep::HandComplete
+        }
+    }
+
+    /// Plays the hand out, burning and dealing the cards and asking the policy for the action of
+    /// each player in turn, until the hand is over. Players all in have no preference for the
+    /// number of runouts, and show their hands at showdown unless they cannot win.
+    ///
+    /// Returns an error if the policy chooses an illegal action or the hand reaches an operation
+    /// that cannot be carried out unattended.
+    pub fn play_out(&mut self, mut policy: impl FnMut(&State, usize) -> Action) -> Result<(), StateError> {
+        while self.status {
+            if self.card_burning_status {
+                self.burn_card(None, None)?;
+            } else if self.hole_dealee_index().is_some() {
+                self.deal_hole(None, None, None)?;
+            } else if self.can_deal_board() {
+                self.deal_board(None, None)?;
+            } else if let Some(&player_index) = self.showdown_indices.front() {
+                let cards = (self.is_hand_live(player_index) || self.is_hand_exposed(player_index)).then(Vec::new);
+                self.show_or_muck_hole_cards(cards, None)?;
+            } else if self.can_select_runout_count(None, None) {
+                self.select_runout_count(None, None, None)?;
+            } else if let Some(player_index) = self.stand_pat_or_discarder_index() {
+                let action = policy(self, player_index);
+                self.apply(action)?;
+            } else if let Some(&player_index) = self.actor_indices.front() {
+                let action = policy(self, player_index);
+                self.apply(action)?;
+            } else if self.can_kill_hand(None) {
+                self.kill_hand(None, None)?;
+            } else if self.can_push_chips() {
+                self.push_chips(None)?;
+            } else if self.can_pull_chips(None) {
+                self.pull_chips(None, None)?;
+            } else {
+                return Err(StateError::UnattendedOperation);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the amount the player in turn may complete, bet, or raise to for the fraction of
+    /// the pot after calling, added on top of the call and clamped to the legal amounts.
+    ///
+    /// The pot is counted as under pot-limit betting, before any rake, so that the full pot is
+    /// the pot-limit maximum.
+    pub fn pot_fraction_raise_to(&self, fraction: f64) -> Option<Amount> {
+        let max_bet = self.bets.iter().cloned().max().unwrap_or_default();
+        let pot_amount = self.checking_or_calling_amount()? - self.payoffs.iter().sum::<Amount>();
+        self.clamp_raise_to(max_bet + scale_amount(pot_amount, fraction, self.chip_unit))
+    }
+
+    /// Clamps the amount to those the player in turn may complete, bet, or raise to, if they may
+    /// do so at all, e.g. to make the sizing of a bot legal.
+    pub fn clamp_raise_to(&self, amount: Amount) -> Option<Amount> {
+        Some(amount.clamp(self.min_completion_betting_or_raising_to_amount()?, self.max_completion_betting_or_raising_to_amount()?))
+    }
+
+    /// Returns the bet of the player in turn if they put in their whole stack, regardless of the
+    /// limits of the betting structure, unlike [`State::max_raise_to`].
+    pub fn all_in_amount(&self) -> Option<Amount> {
+        let player_index = *self.actor_indices.front()?;
+        Some(self.bets[player_index] + self.stacks[player_index])
+    }
+
+    pub fn third_pot_raise_to(&self) -> Option<Amount> { self.pot_fraction_raise_to(1.0 / 3.0) }
+    pub fn half_pot_raise_to(&self) -> Option<Amount> { self.pot_fraction_raise_to(0.5) }
+    pub fn two_thirds_pot_raise_to(&self) -> Option<Amount> { self.pot_fraction_raise_to(2.0 / 3.0) }
+    pub fn pot_raise_to(&self) -> Option<Amount> { self.pot_fraction_raise_to(1.0) }
+    pub fn min_raise_to(&self) -> Option<Amount> { self.min_completion_betting_or_raising_to_amount() }
+    /// Returns the largest amount the player in turn may complete, bet, or raise to. This is all
+    /// in only under no-limit betting, while the pot-limit or fixed-limit maximum may fall short of
+    /// [`State::all_in_amount`].
+    pub fn max_raise_to(&self) -> Option<Amount> { self.max_completion_betting_or_raising_to_amount() }
+
+    /// Returns the entry of the best hand of the hand type at the index, evaluated with its
+    /// configured evaluator.
+    pub fn evaluate_hand(&self, hand_type_index: usize, hole_cards: &[Card], board_cards: &[Card]) -> Option<Entry> {
+        self.evaluators[hand_type_index].evaluate(hole_cards, board_cards)
+    }
+
+    /// Returns the entry of the best hand of the player with the board at the index, for the hand
+    /// type at the index.
+    ///
+    /// Evaluations are memoized per player, and reused until their hole cards or the board change.
+    pub fn get_hand(&self, player_index: usize, board_index: usize, hand_type_index: usize) -> Option<Entry> {
+        let hole_cards = &self.hole_cards[player_index];
+        let board_cards = self.board_cards.get(board_index).map_or(&[][..], |cards| cards.as_slice());
+        let key = (player_index, board_index, hand_type_index);
+        let mut cache = self.hand_cache.0.lock().unwrap();
+        if let Some(hand) = cache.get(&key).filter(|hand| &hand.hole_cards == hole_cards && hand.board_length == board_cards.len()) {
+            return hand.entry;
+        }
+
+        let entry = self.evaluate_hand(hand_type_index, hole_cards, board_cards);
+        cache.insert(key, CachedHand { hole_cards: hole_cards.clone(), board_length: board_cards.len(), entry });
+        entry
+    }
+
+    /// Returns the most the player can still win or lose against any one live opponent: the
+    /// smaller of their stack and the largest stack among the other active players.
+    pub fn get_effective_stack(&self, player_index: usize) -> Amount {
+        if !self.statuses[player_index] {
+            return Amount::zero();
+        }
+        let max_opponent_stack = (0..self.player_count)
+            .filter(|&i| i != player_index && self.statuses[i])
+            .map(|i| self.stacks[i])
+            .max()
+            .unwrap_or_default();
+
+        self.stacks[player_index].min(max_opponent_stack)
+    }
+
+    /// Returns the ratio of the effective stack of the player to the pot, bets in front of the
+    /// players included, or `None` if the pot is empty.
+    pub fn get_stack_to_pot_ratio(&self, player_index: usize) -> Option<f64> {
+        let pot_amount = self.total_pot_amount();
+        (pot_amount > Amount::zero()).then(|| self.get_effective_stack(player_index).to_f64().unwrap_or_default() / pot_amount.to_f64().unwrap_or_default())
+    }
+
+    /// Returns the last player to complete, bet, or raise on the street, if any.
+    pub fn aggressor_index(&self, street_index: usize) -> Option<usize> {
+        self.street_raises.get(street_index)?.last().map(|raise| raise.player_index)
+    }
+
+    /// Verifies that every card of the deck is in exactly one location: the deck, a board, a hand,
+    /// the muck, the burn cards, or the discards.
+    ///
+    /// Cards shared by the boards of several runouts are counted once, and unknown cards, which
+    /// stand in for hidden ones, are not counted.
+    pub fn card_audit(&self) -> CardAudit {
+        let board_length = self.board_cards.iter().map(|cards| cards.len()).max().unwrap_or(0);
+        let mut board_cards = Vec::new();
+        for i in 0..board_length {
+            let position_cards: Vec<Card> = self.board_cards.iter().filter_map(|cards| cards.get(i)).cloned().collect();
+            board_cards.extend(position_cards.iter().enumerate().filter(|(j, card)| !position_cards[..*j].contains(card)).map(|(_, card)| *card));
+        }
+
+        let mut counts: HashMap<Card, i64> = HashMap::new();
+        for card in self.deck.iter() {
+            *counts.entry(*card).or_default() += 1;
+        }
+        let mut audit = CardAudit::default();
+        let located_cards = self.deck_cards.iter()
+            .chain(&board_cards)
+            .chain(self.hole_cards.iter().flatten())
+            .chain(&self.mucked_cards)
+            .chain(&self.burn_cards)
+            .chain(self.discarded_cards.iter().flatten());
+        for card in located_cards.filter(|card| !card.is_unknown()) {
+            match counts.get_mut(card) {
+                Some(count) if *count > 0 => *count -= 1,
+                Some(_) => audit.duplicate_cards.push(*card),
+                None => audit.foreign_cards.push(*card),
+            }
+        }
+        for card in self.deck.iter() {
+            if let Some(count) = counts.get_mut(card).filter(|count| **count > 0) {
+                *count -= 1;
+                audit.missing_cards.push(*card);
             }
-            
-            if pot_amount > 0 {
-                let (raked, unraked) = (self.rake)(self, pot_amount);
+        }
+
+        audit
+    }
+
+    /// Returns the index of the first street dealing board cards, i.e. the flop in hold'em and
+    /// Omaha, if any.
+    pub fn flop_street_index(&self) -> Option<usize> {
+        self.streets.iter().position(|street| street.board_dealing_count > 0)
+    }
+
+    /// Returns whether the hand has reached the street, e.g. to rake only hands that see a flop.
+    pub fn has_reached_street(&self, street_index: usize) -> bool {
+        self.street_index.is_some_and(|i| i >= street_index)
+    }
+
+    /// Returns the number of players who saw the flop, that is, who had not folded when its
+    /// board cards were dealt, if they have been.
+    pub fn flop_player_count(&self) -> Option<usize> {
+        let mut player_count = (0..self.player_count).filter(|&i| !self.is_sitting_out(i)).count();
+        for operation in self.operations.iter() {
+            match operation {
+                Operation::Folding(_) => player_count -= 1,
+                Operation::BoardDealing(_) => return Some(player_count),
+                _ => {}
+            }
+        }
+        None
+    }
+
+    /// Returns the rake taken from the pots awarded so far.
+    pub fn total_rake(&self) -> Amount {
+        self.operations.iter()
+            .map(|operation| match operation {
+                Operation::ChipsPushing(op) => op.raked_amount,
+                _ => Amount::zero(),
+            })
+            .sum()
+    }
+
+    /// Returns the jackpot drop taken from the pots awarded so far.
+    pub fn total_drop(&self) -> Amount {
+        self.operations.iter()
+            .map(|operation| match operation {
+                Operation::ChipsPushing(op) => op.dropped_amount,
+                _ => Amount::zero(),
+            })
+            .sum()
+    }
+
+    /// Returns the amount of each pot, main pot first, counting the bets yet to be collected.
+    pub fn pot_amounts(&self) -> Vec<Amount> {
+        self.pots().iter().map(Pot::amount).collect()
+    }
+
+    /// Returns the total amount in the pots, counting the bets yet to be collected.
+    pub fn total_pot_amount(&self) -> Amount {
+        self.pot_amounts().iter().sum()
+    }
+
+    pub fn pots(&self) -> Vec<Pot> {
+        // Bets are already reflected in the payoffs, as investing a chip deducts it from both.
+        // Dead antes go to the main pot regardless of how much their poster contributed.
+        let dead_antes = self.dead_antes();
+        let contributions: Vec<Amount> = self.payoffs.iter().zip(&dead_antes).map(|(p, d)| -p - d).collect();
+        let mut dead_amount: Amount = dead_antes.iter().sum();
+        let mut pots = Vec::new();
+
+        // Side pots are capped at the contributions of the active all-in players, while the
+        // contributions of folded players simply count towards whichever pots they reach.
+        let mut last_contribution = Amount::zero();
+        let mut levels: Vec<Amount> = (0..self.player_count)
+            .filter(|&i| self.statuses[i] && self.stacks[i] == Amount::zero())
+            .map(|i| contributions[i])
+            .chain(contributions.iter().cloned().max())
+            .filter(|&c| c > Amount::zero())
+            .collect();
+        levels.sort_unstable();
+        levels.dedup();
+
+        if levels.is_empty() && dead_amount > Amount::zero() {
+            levels.push(Amount::zero());
+        }
+
+        for &contribution in &levels {
+            let pot_amount: Amount = contributions.iter()
+                .map(|&c| c.min(contribution) - c.min(last_contribution))
+                .sum::<Amount>() + std::mem::take(&mut dead_amount);
+            let pot_player_indices: Vec<usize> = (0..self.player_count)
+                .filter(|&i| self.statuses[i] && (contributions[i] >= contribution || self.stacks[i] > Amount::zero()))
+                .collect();
+
+            if pot_amount > Amount::zero() {
                 pots.push(Pot {
-                    raked_amount: raked,
-                    unraked_amount: unraked,
+                    raked_amount: Amount::zero(),
+                    dropped_amount: Amount::zero(),
+                    unraked_amount: pot_amount,
                     player_indices: pot_player_indices,
                 });
             }
             last_contribution = contribution;
         }
+
+        let pot_amounts: Vec<Amount> = pots.iter().map(|pot| pot.unraked_amount).collect();
+        for (pot, raked_amount) in pots.iter_mut().zip(self.rake.rake(self, &pot_amounts)) {
+            pot.raked_amount = raked_amount;
+            pot.unraked_amount -= raked_amount;
+        }
+
+        let total_amount: Amount = pots.iter().map(Pot::amount).sum();
+        if let (Some(jackpot_drop), Some(main_pot)) = (self.jackpot_drop, pots.first_mut()) {
+            if total_amount > jackpot_drop.threshold {
+                main_pot.dropped_amount = jackpot_drop.amount.min(main_pot.unraked_amount);
+                main_pot.unraked_amount -= main_pot.dropped_amount;
+            }
+        }
+
         pots
     }
+
+    /// Returns a canonical key of everything the player has observed so far: their own
+    /// hole cards, the exposed cards of others, the board, and the public actions.
+    ///
+    /// Hidden cards are rendered as `??`. When `suit_isomorphism` is `true`, suits are
+    /// relabeled so that strategically equivalent histories share the same key.
+    pub fn information_set(&self, player_index: usize, suit_isomorphism: bool) -> String {
+        enum Segment { Text(String), Cards(Vec<Card>) }
+
+        let hidden = |count: usize| "??".repeat(count);
+        let mut segments = vec![Segment::Text(format!("p{}:", player_index + 1))];
+
+        for operation in self.operations.iter() {
+            match operation {
+                Operation::HoleDealing(op) => {
+                    segments.push(Segment::Text(format!("d dh p{} ", op.player_index + 1)));
+                    if op.player_index == player_index {
+                        segments.push(Segment::Cards(op.cards.clone()));
+                    } else {
+                        let shown = op.cards.iter().zip(&op.statuses).filter(|(_, &status)| status).map(|(&card, _)| card).collect();
+                        segments.push(Segment::Cards(shown));
+                        segments.push(Segment::Text(hidden(op.statuses.iter().filter(|&&status| !status).count())));
+                    }
+                }
+                Operation::BoardDealing(op) => {
+                    segments.push(Segment::Text("d db ".to_string()));
+                    segments.push(Segment::Cards(op.cards.clone()));
+                }
+                Operation::StandingPatOrDiscarding(op) => {
+                    segments.push(Segment::Text(format!("p{} sd ", op.player_index + 1)));
+                    if op.player_index == player_index {
+                        segments.push(Segment::Cards(op.cards.clone()));
+                    } else {
+                        segments.push(Segment::Text(hidden(op.cards.len())));
+                    }
+                }
+                Operation::Folding(op) => segments.push(Segment::Text(format!("p{} f", op.player_index + 1))),
+                Operation::CheckingOrCalling(op) => segments.push(Segment::Text(format!("p{} cc", op.player_index + 1))),
+                Operation::BringInPosting(op) => segments.push(Segment::Text(format!("p{} pb", op.player_index + 1))),
+                Operation::CompletionBettingOrRaisingTo(op) => {
+                    segments.push(Segment::Text(format!("p{} cbr {}", op.player_index + 1, op.amount)));
+                }
+                Operation::RunoutCountSelection(op) => {
+                    let count = op.runout_count.map_or(String::new(), |count| format!(" {}", count));
+                    segments.push(Segment::Text(format!("p{} sr{}", op.player_index + 1, count)));
+                }
+                Operation::HoleCardsShowingOrMucking(op) => {
+                    segments.push(Segment::Text(format!("p{} sm ", op.player_index + 1)));
+                    segments.push(Segment::Cards(op.hole_cards.clone()));
+                }
+                _ => continue,
+            }
+            segments.push(Segment::Text("/".to_string()));
+        }
+
+        let groups: Vec<Vec<Card>> = segments.iter()
+            .filter_map(|segment| match segment { Segment::Cards(cards) => Some(cards.clone()), _ => None })
+            .collect();
+        let mut groups = if suit_isomorphism { canonicalize_suits(&groups) } else { groups }.into_iter();
+
+        segments.iter()
+            .map(|segment| match segment {
+                Segment::Text(text) => text.clone(),
+                Segment::Cards(_) => groups.next().unwrap().iter().map(|c| c.to_string()).collect(),
+            })
+            .collect::<String>()
+            .trim_end_matches('/')
+            .to_string()
+    }
+
+    /// Returns a copy of the state as the player sees it, safe to hand to their agent or client.
+    ///
+    /// The face-down hole cards and discards of others, the burned cards, the mucked cards never
+    /// shown, and the remaining deck are replaced by [`Card::UNKNOWN`], in the operations as well.
+    /// The seed, the random number generator, the deck source, and the undo history are dropped,
+    /// as they would reveal the deck. The view is for observing; it cannot deal further cards.
+    pub fn player_view(&self, player_index: usize) -> State {
+        let mut view = self.clone();
+        let known_cards: HashSet<Card> = self.operations.iter()
+            .flat_map(|operation| match operation {
+                Operation::HoleDealing(op) if op.player_index == player_index => op.cards.clone(),
+                Operation::HoleDealing(op) => op.cards.iter().zip(&op.statuses).filter(|(_, &status)| status).map(|(&card, _)| card).collect(),
+                Operation::StandingPatOrDiscarding(op) if op.player_index == player_index => op.cards.clone(),
+                Operation::HoleCardsShowingOrMucking(op) => op.hole_cards.clone(),
+                _ => Vec::new(),
+            })
+            .chain(self.board_cards.iter().flatten().cloned())
+            .collect();
+        let hide = |card: &mut Card| if !known_cards.contains(card) { *card = Card::UNKNOWN };
+
+        for i in (0..self.player_count).filter(|&i| i != player_index) {
+            for (card, &status) in view.hole_cards[i].iter_mut().zip(&self.hole_card_statuses[i]) {
+                if !status {
+                    *card = Card::UNKNOWN;
+                }
+            }
+            view.discarded_cards[i].iter_mut().for_each(hide);
+        }
+        view.deck_cards.iter_mut().for_each(|card| *card = Card::UNKNOWN);
+        view.burn_cards.iter_mut().for_each(|card| *card = Card::UNKNOWN);
+        view.mucked_cards.iter_mut().for_each(hide);
+        for operation in Arc::make_mut(&mut view.operations).iter_mut() {
+            match operation {
+                Operation::CardBurning(op) => op.card = Card::UNKNOWN,
+                Operation::HoleDealing(op) if op.player_index != player_index => op.cards.iter_mut().for_each(hide),
+                Operation::StandingPatOrDiscarding(op) if op.player_index != player_index => op.cards.iter_mut().for_each(hide),
+                _ => {}
+            }
+        }
+
+        view.seed = None;
+        view.rng = None;
+        view.deck_source = None;
+        view.undo_history = Arc::default();
+        view.hand_cache = HandCache::default();
+        view
+    }
 }
@@ -0,0 +1,267 @@
+//! Implements tables playing consecutive hands, and the concurrent running of many of them.
+//!
+//! A [`Table`] spawns a fresh state for every hand from its builder, carrying the stacks over
+//! from one hand to the next, optionally as chips of given denominations, and optionally moving
+//! the button and the blinds by the dead button rule as players bust out and are no longer dealt
+//! in, or playing kill pots. [`Tables`] runs many tables on a pool of threads, routing the
+//! decisions of each seat to its agent, as the backbone of large-scale self-play and hosting.
+
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use num_traits::Zero;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::chips::{color_up, Chips, ColorUp};
+use crate::dataset::play_hand;
+use crate::kill::KillTracker;
+use crate::mcts::Policy;
+use crate::positions::Positions;
+use crate::state::{BuildError, State, StateBuilder, StateError};
+use crate::utilities::Amount;
+
+/// The reason a table cannot play its next hand.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TableError {
+    /// Fewer than two players have chips left.
+    TooFewPlayers,
+    /// The hand is not over yet, so its stacks cannot be carried over.
+    HandInProgress,
+    /// The state of the next hand cannot be built.
+    Build(BuildError),
+    /// The hand cannot be played out.
+    State(StateError),
+}
+
+impl std::fmt::Display for TableError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TableError::TooFewPlayers => write!(f, "Fewer than two players have chips left"),
+            TableError::HandInProgress => write!(f, "The hand is not over yet"),
+            TableError::Build(error) => write!(f, "The next hand cannot be built: {}", error),
+            TableError::State(error) => write!(f, "The hand cannot be played out: {}", error),
+        }
+    }
+}
+
+impl std::error::Error for TableError {}
+
+impl From<BuildError> for TableError {
+    fn from(error: BuildError) -> Self { TableError::Build(error) }
+}
+
+impl From<StateError> for TableError {
+    fn from(error: StateError) -> Self { TableError::State(error) }
+}
+
+impl From<TableError> for String {
+    fn from(error: TableError) -> Self { error.to_string() }
+}
+
+/// A table playing consecutive hands.
+#[derive(Clone)]
+pub struct Table {
+    builder: StateBuilder,
+    pub stacks: Vec<Amount>,
+    /// The chip denominations in play, if stacks are modeled as chips.
+    pub denominations: Option<Vec<Amount>>,
+    pub hand_number: u64,
+    /// The positions of the next hand, if the table moves the button and the blinds itself.
+    pub positions: Option<Positions>,
+    /// The small and big blinds posted by position, if the table moves the button itself.
+    pub blinds: Option<(Amount, Amount)>,
+    /// The winners followed for kill pots, if the table plays them.
+    pub kill_tracker: Option<KillTracker>,
+}
+
+impl Table {
+    /// Creates a table whose hands are built by the builder, starting with the stacks.
+    pub fn new(builder: StateBuilder, stacks: Vec<Amount>) -> Self {
+        Self { builder, stacks, denominations: None, hand_number: 0, positions: None, blinds: None, kill_tracker: None }
+    }
+
+    pub fn denominations(mut self, denominations: Vec<Amount>) -> Self { self.denominations = Some(denominations); self }
+    pub fn kill_tracker(mut self, kill_tracker: KillTracker) -> Self { self.kill_tracker = Some(kill_tracker); self }
+
+    /// Treats the stacks as seats, dealing only the players with chips, and starts the button on
+    /// the seat, moving it and the blinds by the dead button rule from then on.
+    pub fn button(mut self, button_index: usize, small_blind: Amount, big_blind: Amount) -> Self {
+        self.positions = Positions::new(button_index, &self.occupied());
+        self.blinds = Some((small_blind, big_blind));
+        self
+    }
+
+    /// Returns whether each seat has a player with chips.
+    pub fn occupied(&self) -> Vec<bool> {
+        self.stacks.iter().map(|&stack| stack > Amount::zero()).collect()
+    }
+
+    /// Returns the seats dealt into the next hand, in the order of its players.
+    pub fn seat_indices(&self) -> Vec<usize> {
+        match (self.blinds, self.positions) {
+            (Some(_), Some(positions)) => positions.seat_indices(&self.occupied()),
+            (Some(_), None) => Vec::new(),
+            (None, _) => (0..self.stacks.len()).collect(),
+        }
+    }
+
+    /// Returns whether the table can deal no more hands, as fewer than two players have chips.
+    pub fn is_over(&self) -> bool {
+        self.occupied().into_iter().filter(|&status| status).count() < 2
+    }
+
+    /// Returns the stacks broken down into chips.
+    pub fn chips(&self) -> Result<Vec<Chips>, String> {
+        let denominations = self.denominations.as_ref().ok_or("The table has no chip denominations.")?;
+        self.stacks.iter().map(|&stack| Chips::make_change(stack, denominations)).collect()
+    }
+
+    /// Colors up the smallest denomination between hands, e.g. at the end of a level.
+    pub fn color_up<R: Rng + ?Sized>(&mut self, rng: &mut R) -> Result<ColorUp, String> {
+        let denominations = self.denominations.as_ref().ok_or("The table has no chip denominations.")?;
+        let color_up = color_up(&self.stacks, denominations, rng)?;
+        self.stacks = color_up.stacks.clone();
+        self.denominations = Some(color_up.denominations.clone());
+        Ok(color_up)
+    }
+
+    /// Builds the state of the next hand, with the stacks the players carry over.
+    ///
+    /// If the table moves the button, the players are the seats with chips, starting left of the
+    /// button, and post the blinds of their positions. A player who killed the hand posts the
+    /// kill blind, unless they are no longer dealt in.
+    pub fn next_state(&self, seed: u64) -> Result<State, TableError> {
+        let builder = self.builder.clone().seed(seed);
+        let killer_index = self.kill_tracker.as_ref().and_then(|kill_tracker| kill_tracker.killer_index);
+        let builder = match killer_index.and_then(|seat_index| self.seat_indices().iter().position(|&i| i == seat_index)) {
+            Some(player_index) => builder.kill(player_index, self.kill_tracker.as_ref().unwrap().kill),
+            None => builder,
+        };
+        let Some((small_blind, big_blind)) = self.blinds else {
+            return Ok(builder.raw_starting_stacks(self.stacks.as_slice()).build()?);
+        };
+        let positions = self.positions.ok_or(TableError::TooFewPlayers)?;
+        let occupied = self.occupied();
+        let stacks: Vec<Amount> = self.seat_indices().iter().map(|&i| self.stacks[i]).collect();
+        Ok(builder
+            .player_count(stacks.len())
+            .raw_blinds_or_straddles(positions.blinds(&occupied, small_blind, big_blind).as_slice())
+            .raw_starting_stacks(stacks.as_slice())
+            .build()?)
+    }
+
+    /// Carries the stacks of the finished hand over to the next one.
+    pub fn finish_hand(&mut self, state: &State) -> Result<(), TableError> {
+        if state.status {
+            return Err(TableError::HandInProgress);
+        }
+        let seat_indices = self.seat_indices();
+        if let Some(kill_tracker) = self.kill_tracker.as_mut() {
+            kill_tracker.record(state, &seat_indices);
+        }
+        if self.blinds.is_some() {
+            let positions = self.positions.ok_or(TableError::TooFewPlayers)?;
+            for (seat_index, &stack) in seat_indices.into_iter().zip(&state.stacks) {
+                self.stacks[seat_index] = stack;
+            }
+            self.positions = positions.next(&self.occupied());
+        } else {
+            self.stacks = state.stacks.clone();
+        }
+        self.hand_number += 1;
+        Ok(())
+    }
+
+    /// Plays the next hand with the decisions of each seat made by its agent, and carries the
+    /// stacks over.
+    pub fn play_hand<R: Rng + ?Sized>(&mut self, agents: &[&dyn Policy], rng: &mut R) -> Result<State, TableError> {
+        let mut state = self.next_state(rng.gen())?;
+        let agents: Vec<&dyn Policy> = self.seat_indices().into_iter().map(|i| agents[i]).collect();
+        play_hand(&mut state, &agents, rng)?;
+        self.finish_hand(&state)?;
+        Ok(state)
+    }
+}
+
+/// An agent making the decisions of a seat.
+pub type SeatAgent = Arc<dyn Policy + Send + Sync>;
+
+/// Runs many tables concurrently.
+pub struct Tables {
+    tables: Vec<(Table, Vec<SeatAgent>)>,
+    seed: u64,
+    thread_count: usize,
+}
+
+impl Default for Tables {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Tables {
+    pub fn new() -> Self {
+        Self { tables: Vec::new(), seed: 0, thread_count: 1 }
+    }
+
+    pub fn seed(mut self, seed: u64) -> Self { self.seed = seed; self }
+    pub fn thread_count(mut self, thread_count: usize) -> Self { self.thread_count = thread_count.max(1); self }
+
+    /// Adds a table with one agent per seat and returns its index.
+    pub fn add_table(&mut self, table: Table, agents: Vec<SeatAgent>) -> usize {
+        self.tables.push((table, agents));
+        self.tables.len() - 1
+    }
+
+    pub fn tables(&self) -> impl Iterator<Item = &Table> {
+        self.tables.iter().map(|(table, _)| table)
+    }
+
+    /// Plays the hands at each table, spreading the tables over the threads, and returns the
+    /// hands of each table in order.
+    ///
+    /// A table stops once it is over, or at its first hand that fails, which is then its last
+    /// result. Each table draws its randomness from the seed and its index, so the results do not
+    /// depend on the number of threads.
+    pub fn run(&mut self, hand_count: u64) -> Vec<Vec<Result<State, TableError>>> {
+        let seed = self.seed;
+        let queue = Mutex::new(self.tables.iter_mut().enumerate());
+        let mut results: Vec<_> = thread::scope(|scope| {
+            let handles: Vec<_> = (0..self.thread_count)
+                .map(|_| scope.spawn(|| {
+                    let mut results = Vec::new();
+                    while let Some((i, (table, agents))) = queue.lock().unwrap().next() {
+                        let mut rng = StdRng::seed_from_u64(seed.wrapping_add(i as u64));
+                        results.push((i, run_table(table, agents, hand_count, &mut rng)));
+                    }
+                    results
+                }))
+                .collect();
+
+            handles.into_iter().flat_map(|handle| handle.join().unwrap()).collect()
+        });
+
+        results.sort_by_key(|&(i, _)| i);
+        results.into_iter().map(|(_, hands)| hands).collect()
+    }
+}
+
+fn run_table(table: &mut Table, agents: &[SeatAgent], hand_count: u64, rng: &mut StdRng) -> Vec<Result<State, TableError>> {
+    let agents: Vec<&dyn Policy> = agents.iter().map(|agent| agent.as_ref() as &dyn Policy).collect();
+    let mut hands = Vec::new();
+
+    for _ in 0..hand_count {
+        if table.is_over() {
+            break;
+        }
+        let hand = table.play_hand(&agents, rng);
+        let failed = hand.is_err();
+        hands.push(hand);
+        if failed {
+            break;
+        }
+    }
+
+    hands
+}
@@ -0,0 +1,171 @@
+//! Implements the enumeration of betting trees under a bet-size abstraction.
+//!
+//! Starting from a state, a [`TreeBuilder`] expands every fold, check or call, and the
+//! completions, bets, or raises of the configured sizes, until the hand ends or awaits a
+//! non-player operation such as dealing. The result is a flat [`GameTree`] whose leaves carry the
+//! payoffs of an evaluation hook, ready to be handed to a solver.
+
+use std::sync::Arc;
+
+use num_traits::{ToPrimitive, Zero};
+
+use crate::state::{Action, State, StateError};
+use crate::utilities::Amount;
+
+/// A size of completion, bet, or raise.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BetSize {
+    /// The smallest legal amount.
+    Min,
+    /// A fraction of the pot after calling, added on top of the call.
+    PotFraction(f64),
+    /// The largest legal amount.
+    AllIn,
+}
+
+/// The kind of a node of a game tree.
+#[derive(Debug, Clone, PartialEq)]
+pub enum NodeKind {
+    /// The player decides between the actions leading to the children.
+    Decision { player_index: usize },
+    /// The state awaits a non-player operation, such as dealing, and is not expanded further.
+    Chance { payoffs: Vec<f64> },
+    /// The hand is over.
+    Terminal { payoffs: Vec<f64> },
+}
+
+/// A node of a game tree.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Node {
+    pub kind: NodeKind,
+    pub parent_index: Option<usize>,
+    /// The action that led to the node from its parent.
+    pub action: Option<Action>,
+    pub child_indices: Vec<usize>,
+    /// The total amount committed to the pot at the node.
+    pub pot: Amount,
+}
+
+/// A game tree, with the root at index 0.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GameTree {
+    pub nodes: Vec<Node>,
+}
+
+impl GameTree {
+    pub fn root(&self) -> &Node {
+        &self.nodes[0]
+    }
+
+    /// Returns the indices of the children of the node along with the actions leading to them.
+    pub fn children(&self, node_index: usize) -> impl Iterator<Item = (usize, &Action)> + '_ {
+        self.nodes[node_index].child_indices.iter()
+            .map(|&i| (i, self.nodes[i].action.as_ref().unwrap()))
+    }
+
+    /// Returns the actions leading from the root to the node.
+    pub fn line(&self, node_index: usize) -> Vec<Action> {
+        let mut actions = Vec::new();
+        let mut node = &self.nodes[node_index];
+        while let (Some(action), Some(parent_index)) = (&node.action, node.parent_index) {
+            actions.push(action.clone());
+            node = &self.nodes[parent_index];
+        }
+        actions.reverse();
+        actions
+    }
+
+    pub fn decision_node_count(&self) -> usize {
+        self.nodes.iter().filter(|node| matches!(node.kind, NodeKind::Decision { .. })).count()
+    }
+}
+
+/// Evaluates the payoffs of each player at a leaf.
+pub type PayoffHook = Arc<dyn Fn(&State) -> Vec<f64> + Send + Sync>;
+
+/// Builds game trees under a bet-size abstraction.
+#[derive(Clone)]
+pub struct TreeBuilder {
+    bet_sizes: Vec<BetSize>,
+    raise_cap: Option<usize>,
+    payoff: PayoffHook,
+}
+
+impl TreeBuilder {
+    /// Creates a builder considering the bet sizes, whose leaves are evaluated with the payoffs
+    /// of the states.
+    pub fn new(bet_sizes: Vec<BetSize>) -> Self {
+        Self {
+            bet_sizes,
+            raise_cap: None,
+            payoff: Arc::new(|state: &State| state.payoffs.iter().map(|payoff| payoff.to_f64().unwrap_or_default()).collect()),
+        }
+    }
+
+    /// Only considers checking or calling and folding once this many completions, bets, or
+    /// raises have been made on the street.
+    pub fn raise_cap(mut self, raise_cap: usize) -> Self { self.raise_cap = Some(raise_cap); self }
+    pub fn payoff(mut self, payoff: impl Fn(&State) -> Vec<f64> + Send + Sync + 'static) -> Self { self.payoff = Arc::new(payoff); self }
+
+    /// Returns the actions available to the player in turn under the abstraction.
+    pub fn actions(&self, state: &State) -> Vec<Action> {
+        let Some(&player_index) = state.actor_indices.front() else { return Vec::new() };
+        let max_bet = state.bets.iter().cloned().max().unwrap_or_default();
+        let mut actions = Vec::new();
+
+        if max_bet > state.bets[player_index] && state.stacks[player_index] > Amount::zero() {
+            actions.push(Action::Fold);
+        }
+        actions.push(Action::CheckOrCall);
+
+        let capped = self.raise_cap.is_some_and(|cap| state.completion_betting_or_raising_count >= cap);
+        if let (Some(min_amount), Some(max_amount), false) = (state.min_raise_to(), state.max_raise_to(), capped) {
+            let mut amounts: Vec<Amount> = self.bet_sizes.iter()
+                .map(|&bet_size| match bet_size {
+                    BetSize::Min => min_amount,
+                    BetSize::PotFraction(fraction) => state.pot_fraction_raise_to(fraction).unwrap_or(max_amount),
+                    BetSize::AllIn => max_amount,
+                })
+                .collect();
+            amounts.sort_unstable();
+            amounts.dedup();
+            actions.extend(amounts.into_iter().map(Action::CompleteBetOrRaiseTo));
+        }
+
+        actions
+    }
+
+    /// Expands the betting tree from the state.
+    pub fn build(&self, state: &State) -> Result<GameTree, StateError> {
+        let mut tree = GameTree { nodes: Vec::new() };
+        self.expand(&mut tree, state, None, None)?;
+        Ok(tree)
+    }
+
+    fn expand(&self, tree: &mut GameTree, state: &State, parent_index: Option<usize>, action: Option<Action>) -> Result<usize, StateError> {
+        let kind = match state.actor_indices.front() {
+            _ if !state.status => NodeKind::Terminal { payoffs: (self.payoff)(state) },
+            Some(&player_index) => NodeKind::Decision { player_index },
+            None => NodeKind::Chance { payoffs: (self.payoff)(state) },
+        };
+        let node_index = tree.nodes.len();
+        tree.nodes.push(Node {
+            kind,
+            parent_index,
+            action,
+            child_indices: Vec::new(),
+            pot: -state.payoffs.iter().sum::<Amount>(),
+        });
+
+        if matches!(tree.nodes[node_index].kind, NodeKind::Decision { .. }) {
+            for action in self.actions(state) {
+                let mut child_state = state.clone();
+                child_state.apply(action.clone())?;
+                let child_index = self.expand(tree, &child_state, Some(node_index), Some(action))?;
+                tree.nodes[node_index].child_indices.push(child_index);
+            }
+        }
+
+        Ok(node_index)
+    }
+}
@@ -8,28 +8,23 @@
 use std::collections::{BTreeMap, VecDeque};
 use std::fmt;
 use std::str::FromStr;
-use std::ops::{Div, Rem, Sub};
 
-use chrono::{NaiveTime, Timelike};
+use chrono::NaiveTime;
 use itertools::Itertools;
 use num_bigint::BigInt;
-use num_traits::{cast, Num, Signed, Zero};
+use num_traits::{Num, Signed, Zero};
 use rand::seq::SliceRandom;
-use rand::thread_rng;
-use regex::Regex;
+use rand::{thread_rng, Rng};
 use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
 use strum_macros::{Display, EnumString};
 
-// A placeholder for the full State struct defined in `state.rs`.
-// This is needed for the function signature of `rake`.
-use crate::state::State;
-
-
 /// A regular expression pattern that can never be matched.
 pub const UNMATCHABLE_PATTERN: &str = r"(?!)";
 
 /// Represents the rank of a card.
-#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, EnumString, Display)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, EnumString, Display, Serialize, Deserialize)]
 pub enum Rank {
     #[strum(serialize = "A")]
     Ace,
@@ -61,6 +56,49 @@ pub enum Rank {
     Unknown,
 }
 
+impl Rank {
+    /// Returns the single-character notation of the rank.
+    pub fn to_char(self) -> char {
+        match self {
+            Rank::Ace => 'A',
+            Rank::Deuce => '2',
+            Rank::Trey => '3',
+            Rank::Four => '4',
+            Rank::Five => '5',
+            Rank::Six => '6',
+            Rank::Seven => '7',
+            Rank::Eight => '8',
+            Rank::Nine => '9',
+            Rank::Ten => 'T',
+            Rank::Jack => 'J',
+            Rank::Queen => 'Q',
+            Rank::King => 'K',
+            Rank::Unknown => '?',
+        }
+    }
+
+    /// Returns the rank of the single-character notation, if valid.
+    pub const fn from_char(c: char) -> Option<Rank> {
+        match c {
+            'A' => Some(Rank::Ace),
+            '2' => Some(Rank::Deuce),
+            '3' => Some(Rank::Trey),
+            '4' => Some(Rank::Four),
+            '5' => Some(Rank::Five),
+            '6' => Some(Rank::Six),
+            '7' => Some(Rank::Seven),
+            '8' => Some(Rank::Eight),
+            '9' => Some(Rank::Nine),
+            'T' => Some(Rank::Ten),
+            'J' => Some(Rank::Jack),
+            'Q' => Some(Rank::Queen),
+            'K' => Some(Rank::King),
+            '?' => Some(Rank::Unknown),
+            _ => None,
+        }
+    }
+}
+
 /// Defines the ordering of ranks for different poker variants.
 pub struct RankOrder;
 
@@ -88,7 +126,8 @@ impl RankOrder {
 }
 
 /// Represents the suit of a card.
-#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, EnumString, Display)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, EnumString, Display, Serialize, Deserialize)]
 pub enum Suit {
     #[strum(serialize = "c")]
     Club,
@@ -102,8 +141,23 @@ pub enum Suit {
     Unknown,
 }
 
+impl Suit {
+    /// Returns the suit of the single-character notation, if valid.
+    pub const fn from_char(c: char) -> Option<Suit> {
+        match c {
+            'c' => Some(Suit::Club),
+            'd' => Some(Suit::Diamond),
+            'h' => Some(Suit::Heart),
+            's' => Some(Suit::Spade),
+            '?' => Some(Suit::Unknown),
+            _ => None,
+        }
+    }
+}
+
 /// Represents a playing card with a rank and a suit.
-#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Serialize, Deserialize)]
 pub struct Card {
     pub rank: Rank,
     pub suit: Suit,
@@ -115,10 +169,60 @@ impl Card {
         suit: Suit::Unknown,
     };
 
-    pub fn new(rank: Rank, suit: Suit) -> Self {
+    pub const fn new(rank: Rank, suit: Suit) -> Self {
         Self { rank, suit }
     }
 
+    /// Counts the cards of the notation, as separated by whitespace or commas.
+    pub const fn count_cards(s: &str) -> usize {
+        let bytes = s.as_bytes();
+        let mut count = 0;
+        let mut i = 0;
+        while i < bytes.len() {
+            if !bytes[i].is_ascii_whitespace() && bytes[i] != b',' {
+                count += 1;
+            }
+            i += 1;
+        }
+        count / 2
+    }
+
+    /// Parses the notation into an array of cards, in a const context if need be.
+    ///
+    /// Unlike [`Card::parse_cards`], this panics on malformed notation, which makes it a compile
+    /// error in a const context, and does not accept `10` for tens.
+    pub const fn parse_array<const N: usize>(s: &str) -> [Card; N] {
+        let bytes = s.as_bytes();
+        let mut cards = [Card::UNKNOWN; N];
+        let mut count = 0;
+        let mut i = 0;
+        while i < bytes.len() {
+            if bytes[i].is_ascii_whitespace() || bytes[i] == b',' {
+                i += 1;
+                continue;
+            }
+            if count == N || i + 1 == bytes.len() {
+                panic!("The notation does not hold the expected number of cards");
+            }
+            let (Some(rank), Some(suit)) = (Rank::from_char(bytes[i] as char), Suit::from_char(bytes[i + 1] as char)) else {
+                panic!("The notation holds an invalid card");
+            };
+            cards[count] = Card::new(rank, suit);
+            count += 1;
+            i += 2;
+        }
+        if count != N {
+            panic!("The notation does not hold the expected number of cards");
+        }
+
+        cards
+    }
+
+    /// Returns whether the rank or the suit of the card is unknown.
+    pub fn is_unknown(&self) -> bool {
+        self.rank == Rank::Unknown || self.suit == Suit::Unknown
+    }
+
     pub fn get_ranks(cards: &[Card]) -> impl Iterator<Item = Rank> + '_ {
         cards.iter().map(|c| c.rank)
     }
@@ -184,6 +288,26 @@ impl FromStr for Card {
     }
 }
 
+/// Builds a card from its notation at compile time, e.g. `card!("As")`.
+#[macro_export]
+macro_rules! card {
+    ($notation:expr) => {{
+        const CARDS: [$crate::utilities::Card; 1] = $crate::utilities::Card::parse_array($notation);
+        CARDS[0]
+    }};
+}
+
+/// Builds a vector of cards from their notation, parsed at compile time, e.g.
+/// `cards!("As Kd Qh")`.
+#[macro_export]
+macro_rules! cards {
+    ($notation:expr) => {{
+        const CARDS: [$crate::utilities::Card; $crate::utilities::Card::count_cards($notation)] =
+            $crate::utilities::Card::parse_array($notation);
+        CARDS.to_vec()
+    }};
+}
+
 /// Represents a deck of cards.
 pub struct Deck;
 
@@ -206,16 +330,43 @@ impl Deck {
 }
 
 pub fn min_or_none<T: Ord>(values: impl IntoIterator<Item = Option<T>>) -> Option<T> {
-    values.into_iter().filter_map(|x| x).min()
+    values.into_iter().flatten().min()
 }
 
 pub fn max_or_none<T: Ord>(values: impl IntoIterator<Item = Option<T>>) -> Option<T> {
-    values.into_iter().filter_map(|x| x).max()
+    values.into_iter().flatten().max()
 }
 
+/// The type of chip amounts: stacks, bets, blinds, pots, and payoffs.
+///
+/// Amounts are whole chips, unless the `decimal` feature is enabled, in which case they are
+/// decimal numbers, so that stakes such as $0.05/$0.10 need not be scaled to whole chips.
+#[cfg(not(feature = "decimal"))]
+pub type Amount = i64;
+/// The type of chip amounts: stacks, bets, blinds, pots, and payoffs.
+///
+/// Amounts are whole chips, unless the `decimal` feature is enabled, in which case they are
+/// decimal numbers, so that stakes such as $0.05/$0.10 need not be scaled to whole chips.
+#[cfg(feature = "decimal")]
+pub type Amount = Decimal;
+
+/// Converts a count, such as of chips or winners, to an amount.
+#[cfg(not(feature = "decimal"))]
+pub fn to_amount(count: usize) -> Amount { count as Amount }
+/// Converts a count, such as of chips or winners, to an amount.
+#[cfg(feature = "decimal")]
+pub fn to_amount(count: usize) -> Amount { Amount::from(count) }
+
+/// The default smallest amount of chips: a whole chip.
+#[cfg(not(feature = "decimal"))]
+pub fn default_chip_unit() -> Amount { 1 }
+/// The default smallest amount of chips: a hundredth, e.g. a cent.
+#[cfg(feature = "decimal")]
+pub fn default_chip_unit() -> Amount { Decimal::new(1, 2) }
+
 /// "Cleans" a collection of values into a vector of a fixed size.
-pub fn clean_values(values: &BTreeMap<usize, i64>, count: usize) -> Vec<i64> {
-    let mut cleaned = vec![0; count];
+pub fn clean_values(values: &BTreeMap<usize, Amount>, count: usize) -> Vec<Amount> {
+    let mut cleaned = vec![Amount::zero(); count];
     for (&k, &v) in values {
         if k < count {
             cleaned[k] = v;
@@ -224,10 +375,83 @@ pub fn clean_values(values: &BTreeMap<usize, i64>, count: usize) -> Vec<i64> {
     cleaned
 }
 
+/// Raw values, such as antes, blinds or straddles, or starting stacks, before they are cleaned
+/// into one value per player.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RawValues {
+    /// The same value for every player.
+    Uniform(Amount),
+    /// The values of the players at the indices, with zero for the others.
+    Indexed(BTreeMap<usize, Amount>),
+}
+
+impl RawValues {
+    /// Cleans the values into a vector of a fixed size.
+    pub fn clean(&self, count: usize) -> Vec<Amount> {
+        match self {
+            RawValues::Uniform(value) => vec![*value; count],
+            RawValues::Indexed(values) => clean_values(values, count),
+        }
+    }
+}
+
+impl Default for RawValues {
+    fn default() -> Self {
+        RawValues::Indexed(BTreeMap::new())
+    }
+}
+
+impl From<Amount> for RawValues {
+    fn from(value: Amount) -> Self {
+        RawValues::Uniform(value)
+    }
+}
+
+impl From<BTreeMap<usize, Amount>> for RawValues {
+    fn from(values: BTreeMap<usize, Amount>) -> Self {
+        RawValues::Indexed(values)
+    }
+}
+
+impl From<&[Amount]> for RawValues {
+    fn from(values: &[Amount]) -> Self {
+        values.iter().cloned().collect()
+    }
+}
+
+impl From<Vec<Amount>> for RawValues {
+    fn from(values: Vec<Amount>) -> Self {
+        values.into_iter().collect()
+    }
+}
+
+impl<const N: usize> From<[Amount; N]> for RawValues {
+    fn from(values: [Amount; N]) -> Self {
+        values.into_iter().collect()
+    }
+}
+
+/// Collects positional values, one per player in order.
+impl FromIterator<Amount> for RawValues {
+    fn from_iter<I: IntoIterator<Item = Amount>>(values: I) -> Self {
+        RawValues::Indexed(values.into_iter().enumerate().collect())
+    }
+}
+
+/// Collects the values of the players at the indices.
+impl FromIterator<(usize, Amount)> for RawValues {
+    fn from_iter<I: IntoIterator<Item = (usize, Amount)>>(values: I) -> Self {
+        RawValues::Indexed(values.into_iter().collect())
+    }
+}
+
 pub fn shuffled<T: Clone>(values: &[T]) -> Vec<T> {
-    let mut rng = thread_rng();
+    shuffled_with(values, &mut thread_rng())
+}
+
+pub fn shuffled_with<T: Clone, R: Rng + ?Sized>(values: &[T], rng: &mut R) -> Vec<T> {
     let mut shuffled_values = values.to_vec();
-    shuffled_values.shuffle(&mut rng);
+    shuffled_values.shuffle(rng);
     shuffled_values
 }
 
@@ -236,19 +460,71 @@ pub fn rotated<T: Clone>(values: &[T], count: isize) -> VecDeque<T> {
     if count > 0 {
         deque.rotate_right(count as usize);
     } else {
-        deque.rotate_left(count.abs() as usize);
+        deque.rotate_left(count.unsigned_abs());
     }
     deque
 }
 
+/// Relabels the suits of the given card groups so that suit-isomorphic inputs map to the
+/// same output.
+///
+/// Every permutation of the four suits is tried and the lexicographically smallest result is
+/// kept. Each group is sorted after relabeling since the order of cards dealt together carries
+/// no information. Unknown cards are left untouched.
+pub fn canonicalize_suits(groups: &[Vec<Card>]) -> Vec<Vec<Card>> {
+    let suits = [Suit::Club, Suit::Diamond, Suit::Heart, Suit::Spade];
+    let key = |card: &Card| (card.rank as u8, card.suit as u8);
+
+    suits
+        .iter()
+        .permutations(suits.len())
+        .map(|permutation| {
+            groups
+                .iter()
+                .map(|group| {
+                    let mut cards: Vec<Card> = group
+                        .iter()
+                        .map(|card| match suits.iter().position(|&suit| suit == card.suit) {
+                            Some(i) => Card::new(card.rank, *permutation[i]),
+                            None => *card,
+                        })
+                        .collect();
+                    cards.sort_by_key(key);
+                    cards
+                })
+                .collect::<Vec<Vec<Card>>>()
+        })
+        .min_by_key(|candidate| {
+            candidate.iter().map(|cards| cards.iter().map(key).collect::<Vec<_>>()).collect::<Vec<_>>()
+        })
+        .unwrap_or_default()
+}
+
 /// The default divmod function, using standard integer division.
-pub fn div_mod(dividend: i64, divisor: i64) -> (i64, i64) {
+#[cfg(not(feature = "decimal"))]
+pub fn div_mod(dividend: Amount, divisor: Amount) -> (Amount, Amount) {
     (dividend / divisor, dividend % divisor)
 }
+/// The default divmod function, dividing into whole units.
+///
+/// States divide amounts counted in their chip unit, e.g. cents for a pot of $1.25.
+#[cfg(feature = "decimal")]
+pub fn div_mod(dividend: Amount, divisor: Amount) -> (Amount, Amount) {
+    let quotient = (dividend / divisor).trunc();
+    (quotient, dividend - quotient * divisor)
+}
+
+/// Scales the amount by the factor, rounded to the chip unit.
+#[cfg(not(feature = "decimal"))]
+pub fn scale_amount(amount: Amount, factor: f64, chip_unit: Amount) -> Amount {
+    (amount as f64 * factor / chip_unit as f64).round() as Amount * chip_unit
+}
+/// Scales the amount by the factor, rounded to the chip unit.
+#[cfg(feature = "decimal")]
+pub fn scale_amount(amount: Amount, factor: f64, chip_unit: Amount) -> Amount {
+    use num_traits::FromPrimitive;
 
-/// The default rake function, which takes no rake.
-pub fn rake(_state: &State, amount: i64) -> (i64, i64) {
-    (0, amount)
+    (amount * Decimal::from_f64(factor).unwrap_or_default() / chip_unit).round() * chip_unit
 }
 
 pub fn parse_value(raw_value: &str) -> Result<Box<impl Num>, String> {
@@ -0,0 +1,21 @@
+use pokerkit::state::{Opening, SeatAutomation, StateBuilder, Street};
+use pokerkit::utilities::to_amount;
+
+#[test]
+fn automate_all_except_follows_later_player_counts() {
+    let streets = vec![Street::new(false, vec![false; 2], 0, false, Opening::Position, to_amount(2), None).unwrap()];
+    let state = StateBuilder::new(2)
+        .automate_all_except(1, SeatAutomation::CheckOrFold)
+        .streets(streets)
+        .raw_blinds_or_straddles(vec![to_amount(1), to_amount(2)])
+        .raw_starting_stacks(to_amount(200))
+        .player_count(4)
+        .seat_automation(3, SeatAutomation::Fold)
+        .build()
+        .unwrap();
+
+    assert_eq!(
+        state.seat_automations,
+        vec![Some(SeatAutomation::CheckOrFold), None, Some(SeatAutomation::CheckOrFold), Some(SeatAutomation::Fold)],
+    );
+}
@@ -0,0 +1,21 @@
+use pokerkit::dealing::{DeckError, ScriptedDeck};
+use pokerkit::state::{Automation, Opening, StateBuilder, StateError, Street};
+use pokerkit::utilities::{to_amount, Card};
+
+#[test]
+fn exhausted_scripted_deck_is_a_typed_error() {
+    let streets = vec![Street::new(false, vec![false; 2], 0, false, Opening::Position, to_amount(2), None).unwrap()];
+    let mut state = StateBuilder::new(2)
+        .automations(&[Automation::AntePosting, Automation::BetCollection, Automation::BlindOrStraddlePosting])
+        .streets(streets)
+        .raw_blinds_or_straddles(vec![to_amount(1), to_amount(2)])
+        .raw_starting_stacks(to_amount(200))
+        .deck_source(ScriptedDeck::new(Card::parse_cards("AsKsQs").unwrap()))
+        .build()
+        .unwrap();
+    for _ in 0..3 {
+        state.deal_hole(None, None, None).unwrap();
+    }
+
+    assert_eq!(state.deal_hole(None, None, None), Err(StateError::DeckSource(DeckError::Exhausted { count: 1, available: 0 })));
+}
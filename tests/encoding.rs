@@ -0,0 +1,51 @@
+use pokerkit::encoding::{decode, encode};
+use pokerkit::games::NoLimitTexasHoldem;
+use pokerkit::state::{Action, AntePlacement, Automation, HoleDealing, Mode, Operation};
+use pokerkit::utilities::{to_amount, Card, Deck};
+
+#[test]
+fn hand_round_trips_through_the_encoding() {
+    let automations = [Automation::AntePosting, Automation::BetCollection, Automation::BlindOrStraddlePosting];
+    let mut state = NoLimitTexasHoldem::create_state(
+        &automations, true, to_amount(0), AntePlacement::PerPlayer, vec![to_amount(1), to_amount(2)], to_amount(2), to_amount(200), 3, Mode::CashGame,
+    ).unwrap();
+    while state.actor_indices.is_empty() {
+        state.deal_hole(None, None, None).unwrap();
+    }
+    state.complete_bet_or_raise_to(to_amount(6), Some("Opens from the button".to_string())).unwrap();
+    state.play_out(|_, _| Action::CheckOrCall).unwrap();
+
+    let operations: Vec<Operation> = state.operations.iter().cloned().collect();
+    assert_eq!(decode(&encode(&operations).unwrap()).unwrap(), operations);
+}
+
+#[test]
+fn malformed_encodings_are_rejected() {
+    // A hole dealing of 70 cards, whose statuses do not fit in 64 bits.
+    let mut bytes = vec![4, 0, 70];
+    bytes.extend([0; 70]);
+    bytes.extend([0, 0x7F]);
+    assert!(decode(&bytes).is_err());
+
+    // A commentary claiming to be longer than any buffer.
+    let mut bytes = vec![16 | 0x80];
+    bytes.extend([0xFF; 8]);
+    bytes.extend([0x7F, 0x7F]);
+    assert!(decode(&bytes).is_err());
+
+    // Every truncation of a valid encoding.
+    let operations = vec![Operation::HoleDealing(HoleDealing {
+        player_index: 1,
+        cards: Deck::standard()[..2].to_vec(),
+        statuses: vec![false, true],
+        commentary: Some("Dealt".to_string()),
+    })];
+    let bytes = encode(&operations).unwrap();
+    for length in 0..bytes.len() {
+        assert!(decode(&bytes[..length]).is_err());
+    }
+
+    let cards: Vec<Card> = Deck::standard().into_iter().chain(Deck::standard()).collect();
+    let operations = vec![Operation::HoleDealing(HoleDealing { player_index: 0, statuses: vec![false; cards.len()], cards, commentary: None })];
+    assert!(encode(&operations).is_err());
+}
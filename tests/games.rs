@@ -0,0 +1,19 @@
+use num_traits::Zero;
+use pokerkit::games::FixedLimitSevenCardStud;
+use pokerkit::state::{Action, AntePlacement, Automation, Mode};
+use pokerkit::utilities::{to_amount, Amount};
+
+#[test]
+fn eight_handed_seven_card_stud_reaches_showdown() {
+    let automations = [Automation::AntePosting, Automation::BetCollection];
+    let mut state = FixedLimitSevenCardStud::create_state(
+        &automations, true, to_amount(1), AntePlacement::PerPlayer, to_amount(2), to_amount(5), to_amount(10), to_amount(200), 8, Mode::CashGame,
+    ).unwrap();
+    state.play_out(|state, _| if state.can_post_bring_in() { Action::PostBringIn } else { Action::CheckOrCall }).unwrap();
+
+    // The deck runs out on seventh street, which is dealt as a community card without a burn.
+    assert!(!state.status);
+    assert_eq!(state.board_cards[0].len(), 1);
+    assert_eq!(state.burn_cards.len(), 3);
+    assert_eq!(state.payoffs.iter().cloned().sum::<Amount>(), Amount::zero());
+}
@@ -0,0 +1,83 @@
+use pokerkit::dealing::ScriptedDeck;
+use pokerkit::hands::HandType;
+use pokerkit::kill::{Kill, KillTracker};
+use pokerkit::state::{Action, Automation, BettingStructure, BuildError, Opening, State, StateBuilder, Street};
+use pokerkit::utilities::{to_amount, Card};
+
+fn limit_holdem_builder(player_count: usize) -> StateBuilder {
+    let streets = vec![
+        Street::new(false, vec![false; 2], 0, false, Opening::Position, to_amount(2), Some(4)).unwrap(),
+        Street::new(true, vec![], 3, false, Opening::Position, to_amount(2), Some(4)).unwrap(),
+        Street::new(true, vec![], 1, false, Opening::Position, to_amount(4), Some(4)).unwrap(),
+        Street::new(true, vec![], 1, false, Opening::Position, to_amount(4), Some(4)).unwrap(),
+    ];
+    StateBuilder::new(player_count)
+        .automations(&[
+            Automation::AntePosting,
+            Automation::BetCollection,
+            Automation::BlindOrStraddlePosting,
+            Automation::HoleCardsShowingOrMucking,
+            Automation::HandKilling,
+            Automation::ChipsPushing,
+            Automation::ChipsPulling,
+        ])
+        .streets(streets)
+        .betting_structure(BettingStructure::FixedLimit)
+        .raw_blinds_or_straddles(vec![to_amount(1), to_amount(2)])
+        .raw_starting_stacks(to_amount(200))
+}
+
+/// Plays a heads-up hand out with the scripted cards, hole cards first, one per player in turn.
+fn play_hand(builder: StateBuilder, cards: &str) -> State {
+    let mut state = builder.deck_source(ScriptedDeck::new(Card::parse_cards(cards).unwrap())).build().unwrap();
+    state.play_out(|_, _| Action::CheckOrCall).unwrap();
+    state
+}
+
+#[test]
+fn two_wins_in_a_row_kill_the_next_hand() {
+    // Aces beat kings on a dry board, and both play the royal flush on the other.
+    let aces_win = "AhKhAdKd8c2s7d9c8d4h8h3s";
+    let chopped = "2c2d3c3d4hAsKsQs4dJs4cTs";
+    let mut kill_tracker = KillTracker::new(Kill::Full);
+
+    kill_tracker.record(&play_hand(limit_holdem_builder(2), aces_win), &[3, 5]);
+    assert_eq!((kill_tracker.winner_index, kill_tracker.win_count, kill_tracker.killer_index), (Some(3), 1, None));
+
+    kill_tracker.record(&play_hand(limit_holdem_builder(2), aces_win), &[3, 5]);
+    assert_eq!((kill_tracker.winner_index, kill_tracker.win_count, kill_tracker.killer_index), (Some(3), 2, Some(3)));
+
+    // A chopped pot breaks the run.
+    kill_tracker.record(&play_hand(limit_holdem_builder(2), chopped), &[3, 5]);
+    assert_eq!((kill_tracker.winner_index, kill_tracker.win_count, kill_tracker.killer_index), (None, 0, None));
+
+    // So does a pot smaller than the minimum.
+    let mut kill_tracker = KillTracker::new(Kill::Full).min_pot_amount(to_amount(5));
+    kill_tracker.record(&play_hand(limit_holdem_builder(2), aces_win), &[0, 1]);
+    assert_eq!(kill_tracker.winner_index, None);
+}
+
+#[test]
+fn single_scoop_kills_the_next_hi_lo_hand() {
+    // Kings fill up on a board without a low.
+    let builder = limit_holdem_builder(2).hand_types(vec![HandType::StandardHighHand, HandType::EightOrBetterLowHand]);
+    let mut kill_tracker = KillTracker::new(Kill::Half);
+    kill_tracker.record(&play_hand(builder, "AhKhAdKd8cKsQh9c8d9d8hJs"), &[0, 1]);
+
+    assert_eq!(kill_tracker.killer_index, Some(1));
+}
+
+#[test]
+fn killer_posts_the_kill_blind_and_the_stakes_go_up() {
+    let state = limit_holdem_builder(3).kill(2, Kill::Full).build().unwrap();
+    assert_eq!(state.blinds_or_straddles, vec![to_amount(1), to_amount(2), to_amount(4)]);
+    let min_bets: Vec<_> = state.streets.iter().map(|street| street.min_completion_betting_or_raising_amount).collect();
+    assert_eq!(min_bets, vec![to_amount(4), to_amount(4), to_amount(8), to_amount(8)]);
+
+    let state = limit_holdem_builder(3).kill(2, Kill::Half).build().unwrap();
+    assert_eq!(state.blinds_or_straddles, vec![to_amount(1), to_amount(2), to_amount(3)]);
+    assert_eq!(state.streets[0].min_completion_betting_or_raising_amount, to_amount(3));
+
+    // The killer must be dealt in.
+    assert_eq!(limit_holdem_builder(3).kill(3, Kill::Full).build().err(), Some(BuildError::InvalidKiller { player_index: 3 }));
+}
@@ -0,0 +1,25 @@
+use pokerkit::games::NoLimitTexasHoldem;
+use pokerkit::mcts::candidate_actions;
+use pokerkit::state::{Action, AntePlacement, Automation, Mode};
+use pokerkit::utilities::to_amount;
+
+#[test]
+fn candidate_raises_are_the_minimum_the_pot_and_all_in() {
+    let automations = [Automation::AntePosting, Automation::BetCollection, Automation::BlindOrStraddlePosting, Automation::HoleDealing];
+    let state = NoLimitTexasHoldem::create_state(
+        &automations, true, to_amount(0), AntePlacement::PerPlayer, vec![to_amount(1), to_amount(2)], to_amount(2), to_amount(200), 2, Mode::CashGame,
+    ).unwrap();
+
+    // The small blind calls 1 into a pot of 3, so a pot-sized raise is to 2 + 1 + 3.
+    assert_eq!(state.pot_raise_to(), Some(to_amount(6)));
+    assert_eq!(
+        candidate_actions(&state),
+        vec![
+            Action::Fold,
+            Action::CheckOrCall,
+            Action::CompleteBetOrRaiseTo(to_amount(4)),
+            Action::CompleteBetOrRaiseTo(to_amount(6)),
+            Action::CompleteBetOrRaiseTo(to_amount(200)),
+        ],
+    );
+}
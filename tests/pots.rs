@@ -0,0 +1,217 @@
+use pokerkit::dealing::ScriptedDeck;
+use pokerkit::rake::PercentageRake;
+use pokerkit::hands::HandType;
+use pokerkit::state::{Action, Automation, BettingStructure, OddChipRule, Opening, Operation, State, StateBuilder, Street};
+use pokerkit::utilities::{to_amount, Amount, Card};
+
+/// Returns a hold'em builder dealing the scripted cards, hole cards first, one per player in turn.
+fn holdem_builder(player_count: usize, cards: &str) -> StateBuilder {
+    let streets = vec![
+        Street::new(false, vec![false; 2], 0, false, Opening::Position, to_amount(2), None).unwrap(),
+        Street::new(true, vec![], 3, false, Opening::Position, to_amount(2), None).unwrap(),
+        Street::new(true, vec![], 1, false, Opening::Position, to_amount(2), None).unwrap(),
+        Street::new(true, vec![], 1, false, Opening::Position, to_amount(2), None).unwrap(),
+    ];
+    StateBuilder::new(player_count)
+        .automations(&[
+            Automation::AntePosting,
+            Automation::BetCollection,
+            Automation::BlindOrStraddlePosting,
+            Automation::HoleCardsShowingOrMucking,
+            Automation::HandKilling,
+            Automation::ChipsPushing,
+            Automation::ChipsPulling,
+        ])
+        .streets(streets)
+        .raw_blinds_or_straddles(vec![to_amount(1), to_amount(2)])
+        .raw_starting_stacks(to_amount(200))
+        .deck_source(ScriptedDeck::new(Card::parse_cards(cards).unwrap()))
+}
+
+/// Returns an Omaha eight or better builder dealing the scripted cards like the hold'em one.
+fn omaha_hi_lo_builder(player_count: usize, cards: &str) -> StateBuilder {
+    let streets = vec![
+        Street::new(false, vec![false; 4], 0, false, Opening::Position, to_amount(2), None).unwrap(),
+        Street::new(true, vec![], 3, false, Opening::Position, to_amount(2), None).unwrap(),
+        Street::new(true, vec![], 1, false, Opening::Position, to_amount(2), None).unwrap(),
+        Street::new(true, vec![], 1, false, Opening::Position, to_amount(2), None).unwrap(),
+    ];
+    holdem_builder(player_count, cards)
+        .streets(streets)
+        .hand_types(vec![HandType::OmahaHoldemHand, HandType::OmahaEightOrBetterLowHand])
+        .betting_structure(BettingStructure::PotLimit)
+}
+
+/// Returns a heads-up hold'em builder in which both players play the royal flush on the board.
+fn chopped_builder() -> StateBuilder {
+    holdem_builder(2, "2c2d3c3d4hAsKsQs4dJs4cTs")
+}
+
+/// Raises preflop to the amount, unless someone already did, and checks or calls otherwise.
+fn raise_preflop_to(state: &mut State, amount: Amount) {
+    state.play_out(|state, _| {
+        let max_bet = state.bets.iter().cloned().max().unwrap_or_default();
+        if state.street_index == Some(0) && max_bet < amount { Action::CompleteBetOrRaiseTo(amount) } else { Action::CheckOrCall }
+    }).unwrap();
+}
+
+/// Returns the index and amounts of each pot pushed, in order.
+fn pushed_pots(state: &State) -> Vec<(usize, Vec<Amount>)> {
+    state.operations.iter()
+        .filter_map(|operation| match operation {
+            Operation::ChipsPushing(op) => Some((op.pot_index, op.amounts.clone())),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn best_hand_wins_the_pot() {
+    // Aces against kings on a dry board.
+    let mut state = holdem_builder(2, "AhKhAdKd8c2s7d9c8d4h8h3s").build().unwrap();
+    raise_preflop_to(&mut state, to_amount(10));
+
+    assert_eq!(pushed_pots(&state), vec![(0, vec![to_amount(20), to_amount(0)])]);
+    assert_eq!(state.payoffs, vec![to_amount(10), -to_amount(10)]);
+    assert_eq!(state.stacks, vec![to_amount(210), to_amount(190)]);
+}
+
+#[test]
+fn side_pot_goes_to_the_best_hand_among_its_players() {
+    // The short stack holds aces, the big blind kings, and the button queens.
+    let mut state = holdem_builder(3, "AhKhQhAdKdQd8c2s7d9c8d4h8h3s")
+        .raw_starting_stacks(vec![to_amount(50), to_amount(200), to_amount(200)])
+        .build()
+        .unwrap();
+    raise_preflop_to(&mut state, to_amount(200));
+
+    assert_eq!(
+        pushed_pots(&state),
+        vec![(0, vec![to_amount(150), to_amount(0), to_amount(0)]), (1, vec![to_amount(0), to_amount(300), to_amount(0)])],
+    );
+    assert_eq!(state.payoffs, vec![to_amount(100), to_amount(100), -to_amount(200)]);
+}
+
+#[test]
+fn chopped_pot_gives_the_odd_chip_to_the_first_winner() {
+    // The button folds after the antes, leaving 7 chips for the blinds to chop.
+    let mut state = holdem_builder(3, "2c2d2h3c3d3h4hAsKsQs4dJs4cTs").raw_antes(to_amount(1)).chip_unit(to_amount(1)).build().unwrap();
+    state.play_out(|_, player_index| if player_index == 2 { Action::Fold } else { Action::CheckOrCall }).unwrap();
+
+    assert_eq!(state.payoffs, vec![to_amount(1), to_amount(0), -to_amount(1)]);
+}
+
+#[test]
+fn chopped_pot_gives_the_odd_chip_to_the_highest_card_under_the_high_card_rule() {
+    // The big blind's trey of diamonds outranks the small blind's trey of clubs.
+    let mut state = holdem_builder(3, "2c2d2h3c3d3h4hAsKsQs4dJs4cTs")
+        .raw_antes(to_amount(1))
+        .chip_unit(to_amount(1))
+        .odd_chip_rule(OddChipRule::HighCard)
+        .build()
+        .unwrap();
+    state.play_out(|_, player_index| if player_index == 2 { Action::Fold } else { Action::CheckOrCall }).unwrap();
+
+    assert_eq!(state.payoffs, vec![to_amount(0), to_amount(1), -to_amount(1)]);
+}
+
+#[test]
+fn hi_lo_pot_is_split_between_the_high_and_low_winners() {
+    // Ace-deuce makes the low, pocket kings the high with trips, and the button neither.
+    let mut state = omaha_hi_lo_builder(3, "AcKcQc2cKdJc9sJhTc9hJd5d2h3s4h7d2dKh2sQs").build().unwrap();
+    state.play_out(|_, _| Action::CheckOrCall).unwrap();
+
+    let pushings: Vec<_> = state.operations.iter()
+        .filter_map(|operation| match operation {
+            Operation::ChipsPushing(op) => Some((op.hand_type_index, op.amounts.clone())),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        pushings,
+        vec![(Some(0), vec![to_amount(0), to_amount(3), to_amount(0)]), (Some(1), vec![to_amount(3), to_amount(0), to_amount(0)])],
+    );
+    assert_eq!(state.payoffs, vec![to_amount(1), to_amount(1), -to_amount(2)]);
+}
+
+#[test]
+fn hi_lo_pot_without_a_qualifying_low_is_scooped() {
+    let mut state = omaha_hi_lo_builder(3, "AcKcQc2cKdJc9sJhTc9hJd5d2hKsKh9d2dTs2sQd").build().unwrap();
+    state.play_out(|_, _| Action::CheckOrCall).unwrap();
+
+    assert_eq!(pushed_pots(&state), vec![(0, vec![to_amount(0), to_amount(6), to_amount(0)])]);
+    assert_eq!(state.payoffs, vec![-to_amount(2), to_amount(4), -to_amount(2)]);
+}
+
+#[test]
+fn rake_is_taken_before_the_pot_is_awarded() {
+    let mut state = holdem_builder(2, "AhKhAdKd8c2s7d9c8d4h8h3s").rake(PercentageRake::new(0.05)).build().unwrap();
+    raise_preflop_to(&mut state, to_amount(50));
+
+    assert_eq!(state.total_rake(), to_amount(5));
+    assert_eq!(pushed_pots(&state), vec![(0, vec![to_amount(95), to_amount(0)])]);
+    assert_eq!(state.payoffs, vec![to_amount(45), -to_amount(50)]);
+
+    let mut state = holdem_builder(2, "AhKhAdKd8c2s7d9c8d4h8h3s").rake(PercentageRake::new(0.05).cap(to_amount(3))).build().unwrap();
+    raise_preflop_to(&mut state, to_amount(50));
+
+    assert_eq!(state.total_rake(), to_amount(3));
+    assert_eq!(state.payoffs, vec![to_amount(47), -to_amount(50)]);
+}
+
+#[test]
+fn each_pot_is_raked_on_its_own() {
+    let side_pot_builder = || {
+        holdem_builder(3, "AhKhQhAdKdQd8c2s7d9c8d4h8h3s").raw_starting_stacks(vec![to_amount(50), to_amount(200), to_amount(200)])
+    };
+    let raked_pots = |state: &State| -> Vec<(usize, Amount)> {
+        state.operations.iter()
+            .filter_map(|operation| match operation {
+                Operation::ChipsPushing(op) => Some((op.pot_index, op.raked_amount)),
+                _ => None,
+            })
+            .collect()
+    };
+
+    let mut state = side_pot_builder().rake(PercentageRake::new(0.1)).build().unwrap();
+    raise_preflop_to(&mut state, to_amount(200));
+
+    assert_eq!(raked_pots(&state), vec![(0, to_amount(15)), (1, to_amount(30))]);
+    assert_eq!(state.total_rake(), to_amount(45));
+    assert_eq!(state.payoffs, vec![to_amount(85), to_amount(70), -to_amount(200)]);
+
+    // The cap is used up by the main pot first.
+    let mut state = side_pot_builder().rake(PercentageRake::new(0.1).cap(to_amount(20))).build().unwrap();
+    raise_preflop_to(&mut state, to_amount(200));
+
+    assert_eq!(raked_pots(&state), vec![(0, to_amount(15)), (1, to_amount(5))]);
+    assert_eq!(state.payoffs.iter().cloned().sum::<Amount>(), -to_amount(20));
+}
+
+#[test]
+fn rake_and_shares_are_rounded_to_the_chip_unit() {
+    let mut state = chopped_builder().rake(PercentageRake::new(0.05)).chip_unit(to_amount(2)).build().unwrap();
+    raise_preflop_to(&mut state, to_amount(51));
+
+    // The 5.1 chip rake of the 102 chip pot is rounded to 6, leaving 48 chips for each player.
+    assert_eq!(state.total_rake(), to_amount(6));
+    assert_eq!(state.payoffs, vec![-to_amount(3), -to_amount(3)]);
+}
+
+#[test]
+fn non_positive_chip_unit_is_rejected() {
+    assert!(chopped_builder().chip_unit(to_amount(0)).build().is_err());
+}
+
+#[cfg(feature = "decimal")]
+#[test]
+fn fractional_rake_and_shares_are_paid_in_cents() {
+    use rust_decimal::Decimal;
+
+    let mut state = chopped_builder().rake(PercentageRake::new(0.05)).build().unwrap();
+    state.play_out(|_, _| Action::CheckOrCall).unwrap();
+
+    // The 4 chip pot is raked 0.20 and chopped 1.90 apiece, though neither amount has cents.
+    assert_eq!(state.total_rake(), Decimal::new(20, 2));
+    assert_eq!(state.payoffs, vec![Decimal::new(-10, 2), Decimal::new(-10, 2)]);
+}
@@ -0,0 +1,28 @@
+use pokerkit::hands::HandType;
+use pokerkit::replay::verify_replay;
+use pokerkit::state::{Action, Automation, BettingStructure, Opening, StateBuilder, Street};
+use pokerkit::utilities::to_amount;
+
+#[test]
+fn single_draw_hand_replays_from_its_actions() {
+    let streets = vec![
+        Street::new(false, vec![false; 5], 0, false, Opening::Position, to_amount(2), None).unwrap(),
+        Street::new(true, vec![], 0, true, Opening::Position, to_amount(2), None).unwrap(),
+    ];
+    let builder = StateBuilder::new(2)
+        .automations(&[Automation::AntePosting, Automation::BetCollection, Automation::BlindOrStraddlePosting, Automation::ChipsPushing, Automation::ChipsPulling])
+        .streets(streets)
+        .hand_types(vec![HandType::StandardLowHand])
+        .betting_structure(BettingStructure::NoLimit)
+        .raw_blinds_or_straddles(vec![to_amount(1), to_amount(2)])
+        .raw_starting_stacks(to_amount(200))
+        .seed(3);
+    let mut state = builder.clone().build().unwrap();
+    state.play_out(|state, player_index| match state.stand_pat_or_discarder_index() {
+        Some(_) => Action::StandPatOrDiscard(state.hole_cards[player_index][..player_index + 1].to_vec()),
+        None => Action::CheckOrCall,
+    }).unwrap();
+
+    assert!(!state.status);
+    assert_eq!(verify_replay(&state, builder), Ok(()));
+}
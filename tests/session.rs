@@ -0,0 +1,46 @@
+use num_traits::{ToPrimitive, Zero};
+use pokerkit::analysis::session::SessionTracker;
+use pokerkit::dealing::ScriptedDeck;
+use pokerkit::rake::PercentageRake;
+use pokerkit::state::{Action, Automation, Opening, StateBuilder, Street};
+use pokerkit::utilities::{to_amount, Amount, Card};
+
+#[test]
+fn chopped_raked_pot_charges_the_whole_rake() {
+    let streets = vec![
+        Street::new(false, vec![false; 2], 0, false, Opening::Position, to_amount(2), None).unwrap(),
+        Street::new(true, vec![], 3, false, Opening::Position, to_amount(2), None).unwrap(),
+        Street::new(true, vec![], 1, false, Opening::Position, to_amount(2), None).unwrap(),
+        Street::new(true, vec![], 1, false, Opening::Position, to_amount(2), None).unwrap(),
+    ];
+    // Both players play the royal flush on the board.
+    let cards = Card::parse_cards("2c2d3c3d4hAsKsQs4dJs4cTs").unwrap();
+    let mut state = StateBuilder::new(2)
+        .automations(&[
+            Automation::AntePosting,
+            Automation::BetCollection,
+            Automation::BlindOrStraddlePosting,
+            Automation::HoleCardsShowingOrMucking,
+            Automation::HandKilling,
+            Automation::ChipsPushing,
+            Automation::ChipsPulling,
+        ])
+        .streets(streets)
+        .raw_blinds_or_straddles(vec![to_amount(1), to_amount(2)])
+        .raw_starting_stacks(to_amount(200))
+        .rake(PercentageRake::new(0.05))
+        .deck_source(ScriptedDeck::new(cards))
+        .build()
+        .unwrap();
+    state.play_out(|state, _| {
+        let max_bet = state.bets.iter().cloned().max().unwrap_or_default();
+        if state.street_index == Some(0) && max_bet < to_amount(51) { Action::CompleteBetOrRaiseTo(to_amount(51)) } else { Action::CheckOrCall }
+    }).unwrap();
+
+    let mut tracker = SessionTracker::new();
+    tracker.record(&state, &["Alice", "Bob"]).unwrap();
+    let rake_paid: f64 = tracker.records().iter().map(|record| record.rake_paid).sum();
+    // The raked pot is chopped unevenly, as the odd chip goes to one of the players.
+    assert!(state.total_rake() > Amount::zero());
+    assert!((rake_paid - state.total_rake().to_f64().unwrap()).abs() < 1e-9);
+}
@@ -0,0 +1,44 @@
+use pokerkit::rake::PercentageRake;
+use pokerkit::state::{Action, Automation, Mode, Opening, State, StateBuilder, Street};
+use pokerkit::utilities::{to_amount, Amount};
+
+#[test]
+fn raked_hand_resumes_with_the_same_payoffs() {
+    let streets = vec![
+        Street::new(false, vec![false; 2], 0, false, Opening::Position, to_amount(2), None).unwrap(),
+        Street::new(true, vec![], 3, false, Opening::Position, to_amount(2), None).unwrap(),
+        Street::new(true, vec![], 1, false, Opening::Position, to_amount(2), None).unwrap(),
+        Street::new(true, vec![], 1, false, Opening::Position, to_amount(2), None).unwrap(),
+    ];
+    let builder = StateBuilder::new(3)
+        .automations(&[
+            Automation::AntePosting,
+            Automation::BetCollection,
+            Automation::BlindOrStraddlePosting,
+            Automation::CardBurning,
+            Automation::HoleDealing,
+            Automation::BoardDealing,
+            Automation::HoleCardsShowingOrMucking,
+            Automation::HandKilling,
+            Automation::ChipsPushing,
+            Automation::ChipsPulling,
+        ])
+        .streets(streets)
+        .raw_blinds_or_straddles(vec![to_amount(1), to_amount(2)])
+        .raw_starting_stacks(to_amount(200))
+        .mode(Mode::CashGame)
+        .rake(PercentageRake::new(0.05))
+        .seed(1);
+    let mut state = builder.clone().build().unwrap();
+    state.complete_bet_or_raise_to(to_amount(20), None).unwrap();
+    state.check_or_call(None).unwrap();
+    state.check_or_call(None).unwrap();
+
+    let mut resumed_state = State::resume(&state.snapshot().unwrap(), &builder).unwrap();
+    state.play_out(|_, _| Action::CheckOrCall).unwrap();
+    resumed_state.play_out(|_, _| Action::CheckOrCall).unwrap();
+
+    // The 5% rake of the 60 chip pot is taken after resuming too.
+    assert_eq!(resumed_state.payoffs, state.payoffs);
+    assert_eq!(resumed_state.payoffs.iter().cloned().sum::<Amount>(), -to_amount(3));
+}